@@ -0,0 +1,20 @@
+#ifndef CHAPATY_H
+#define CHAPATY_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Runs the backtest described by the JSON config and returns the report as JSON.
+ * On failure a JSON object of the form {"error": "..."} is returned.
+ * The returned string must be released with chapaty_free_string. */
+char *chapaty_run_backtest(const char *config_json);
+
+/* Releases a string returned by chapaty_run_backtest. */
+void chapaty_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CHAPATY_H */
@@ -1,5 +1,10 @@
 use crate::{
+    converter::report_format::{FormattedColumnKind, ReportFormat},
     data_frame_operations::io_operations::save_df_as_csv,
+    enums::column_names::{
+        PerformanceReportColumnKind, PnLReportColumnKind, TradeBreakDownReportColumnKind,
+    },
+    lazy_frame_operations::trait_extensions::MyLazyFrameVecOperations,
     equity_curve::{EquityCurvesAggMarkets, EquityCurvesAggYears, EquityCurvesReport},
     performance_report::{
         PerformanceReportAggMarkets, PerformanceReports, PerformanceReportsAggYears,
@@ -8,16 +13,19 @@ use crate::{
         pnl_statement::PnLStatement, pnl_statement_agg_markets::PnLStatementAggMarkets,
         pnl_statement_agg_markets_and_agg_years::PnLStatementAggMarketsAggYears,
         pnl_statement_agg_years::PnLStatementAggYears,
+        simulation_artifacts::detect_simulation_artifacts,
     },
     trade_breakdown_report::{
         TradeBreakDownReportAggMarkets,
         TradeBreakDownReportsAggYears,
         TradeBreakdownReports,
     },
+    MarketKind,
 };
 
-use polars::prelude::DataFrame;
+use polars::prelude::{col, DataFrame, IntoLazy, LazyFrame};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BacktestResult {
@@ -108,42 +116,205 @@ impl From<AggMarketsAndYearBacktestResult> for AggMarketsAndAggYearsBacktestResu
 }
 
 impl BacktestResult {
+    /// Saves all reports as CSV with the precise default `ReportFormat`.
     pub fn save_as_csv(&self, file_name: &str) {
-        self.market_and_year.save_as_csv(file_name);
-        self.agg_market_and_year.save_as_csv(file_name);
-        self.market_and_agg_year.save_as_csv(file_name);
-        self.agg_market_and_agg_year.save_as_csv(file_name);
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    /// Saves all reports as CSV. The performance and trade breakdown reports are exported with
+    /// `format`, see [`ReportFormat::export`].
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
+        self.market_and_year.save_as_csv_with_format(file_name, format);
+        self.agg_market_and_year.save_as_csv_with_format(file_name, format);
+        self.market_and_agg_year.save_as_csv_with_format(file_name, format);
+        self.agg_market_and_agg_year.save_as_csv_with_format(file_name, format);
+        let name = format!("{file_name}_consolidated_performance_report");
+        let mut report =
+            format.export::<PerformanceReportColumnKind>(&self.consolidated_performance_report());
+        save_df_as_csv(&mut report, &name);
+        let name = format!("{file_name}_consolidated_trade_breakdown_report");
+        let mut report = format
+            .export::<TradeBreakDownReportColumnKind>(&self.consolidated_trade_breakdown_report());
+        save_df_as_csv(&mut report, &name);
+        let name = format!("{file_name}_simulation_artifacts_report");
+        save_df_as_csv(&mut self.simulation_artifacts_report(), &name);
+    }
+
+    /// Returns the trades of all markets and years that show a `SimulationArtifactKind`, e.g. an
+    /// entry and exit within the same candle, to help spotting bugs in the strategy logic.
+    pub fn simulation_artifacts_report(&self) -> DataFrame {
+        detect_simulation_artifacts(&self.agg_market_and_agg_year.pnl_statement.pnl)
+    }
+
+    /// Rounds the performance and trade breakdown reports according to `format`. The reports keep
+    /// their `Float64` columns, and the profit and loss statements keep their raw values.
+    pub fn round_reports(&mut self, format: &ReportFormat) {
+        type Performance = PerformanceReportColumnKind;
+        type TradeBreakDown = TradeBreakDownReportColumnKind;
+        let per_year = &mut self.market_and_year;
+        round_each::<Performance>(format, &mut per_year.performance_reports.reports);
+        round_each::<TradeBreakDown>(format, &mut per_year.trade_breakdown_reports.reports);
+
+        let agg_years = &mut self.market_and_agg_year;
+        round_each::<Performance>(format, &mut agg_years.performance_report.reports);
+        round_each::<TradeBreakDown>(format, &mut agg_years.trade_breakdown_report.reports);
+
+        let agg_markets = &mut self.agg_market_and_year;
+        agg_markets.performance_report.report =
+            format.round::<Performance>(&agg_markets.performance_report.report);
+        agg_markets.trade_breakdown_report.report =
+            format.round::<TradeBreakDown>(&agg_markets.trade_breakdown_report.report);
+
+        let agg_markets_and_years = &mut self.agg_market_and_agg_year;
+        agg_markets_and_years.performance_report =
+            format.round::<Performance>(&agg_markets_and_years.performance_report);
+        agg_markets_and_years.trade_breakdown_report =
+            format.round::<TradeBreakDown>(&agg_markets_and_years.trade_breakdown_report);
+    }
+
+    /// Returns the performance reports of every market and year, followed by the reports across
+    /// all years per market, across all markets per year and across all markets and years, as a
+    /// single `DataFrame`.
+    pub fn consolidated_performance_report(&self) -> DataFrame {
+        let markets = &self.market_and_year.pnl_statement.markets;
+        let reports = markets
+            .iter()
+            .map(|market| &self.market_and_year.performance_reports.reports[market])
+            .chain(
+                markets
+                    .iter()
+                    .map(|market| &self.market_and_agg_year.performance_report.reports[market]),
+            )
+            .chain([
+                &self.agg_market_and_year.performance_report.report,
+                &self.agg_market_and_agg_year.performance_report,
+            ]);
+        consolidate_reports(reports)
     }
+
+    /// Returns the trade breakdown reports in the same order as
+    /// [`BacktestResult::consolidated_performance_report`].
+    pub fn consolidated_trade_breakdown_report(&self) -> DataFrame {
+        let markets = &self.market_and_year.pnl_statement.markets;
+        let reports = markets
+            .iter()
+            .map(|market| &self.market_and_year.trade_breakdown_reports.reports[market])
+            .chain(
+                markets
+                    .iter()
+                    .map(|market| &self.market_and_agg_year.trade_breakdown_report.reports[market]),
+            )
+            .chain([
+                &self.agg_market_and_year.trade_breakdown_report.report,
+                &self.agg_market_and_agg_year.trade_breakdown_report,
+            ]);
+        consolidate_reports(reports)
+    }
+}
+
+fn round_each<C: FormattedColumnKind>(
+    format: &ReportFormat,
+    reports: &mut HashMap<MarketKind, DataFrame>,
+) {
+    reports
+        .values_mut()
+        .for_each(|report| *report = format.round::<C>(report))
+}
+
+fn consolidate_reports<'a>(reports: impl Iterator<Item = &'a DataFrame>) -> DataFrame {
+    let id = PnLReportColumnKind::Id.to_string();
+    let year = PerformanceReportColumnKind::Year.to_string();
+    reports
+        .map(|report| {
+            report
+                .drop(&id)
+                .unwrap()
+                .lazy()
+                .sort_by_exprs(vec![col(&year)], vec![false], false, false)
+        })
+        .collect::<Vec<LazyFrame>>()
+        .concatenate_to_data_frame()
+        .with_row_count(&id, Some(1))
+        .unwrap()
 }
 
 impl MarketAndYearBacktestResult {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.pnl_statement.save_as_csv(file_name);
-        self.performance_reports.save_as_csv(file_name);
-        self.trade_breakdown_reports.save_as_csv(file_name);
+        self.performance_reports.save_as_csv_with_format(file_name, format);
+        self.trade_breakdown_reports.save_as_csv_with_format(file_name, format);
     }
 }
 
 impl AggMarketsAndYearBacktestResult {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.pnl_statement.save_as_csv(file_name);
-        self.performance_report.save_as_csv(file_name);
-        self.trade_breakdown_report.save_as_csv(file_name);
+        self.performance_report.save_as_csv_with_format(file_name, format);
+        self.trade_breakdown_report.save_as_csv_with_format(file_name, format);
     }
 }
 impl MarketAndAggYearsBacktestResult {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.pnl_statement.save_as_csv(file_name);
-        self.performance_report.save_as_csv(file_name);
-        self.trade_breakdown_report.save_as_csv(file_name);
+        self.performance_report.save_as_csv_with_format(file_name, format);
+        self.trade_breakdown_report.save_as_csv_with_format(file_name, format);
     }
 }
 impl AggMarketsAndAggYearsBacktestResult {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.pnl_statement.save_as_csv(file_name);
         let name = format!("{file_name}_all_markets_all_years_performance_report");
-        save_df_as_csv(&mut self.performance_report.clone(), &name);
+        let mut report = format.export::<PerformanceReportColumnKind>(&self.performance_report);
+        save_df_as_csv(&mut report, &name);
         let name = format!("{file_name}_all_markets_all_years_trade_breakdown_report");
-        save_df_as_csv(&mut self.trade_breakdown_report.clone(), &name);
+        let mut report =
+            format.export::<TradeBreakDownReportColumnKind>(&self.trade_breakdown_report);
+        save_df_as_csv(&mut report, &name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{df, prelude::NamedFrom};
+
+    #[test]
+    fn test_consolidate_reports() {
+        let per_year = df!(
+            "Id" => &[1_u32, 2],
+            "Year" => &["2022", "2021"],
+            "NetProfit" => &[20.0, 10.0],
+        )
+        .unwrap();
+        let all_years = df!(
+            "Id" => &[1_u32],
+            "Year" => &["All Years"],
+            "NetProfit" => &[30.0],
+        )
+        .unwrap();
+
+        let target = df!(
+            "Id" => &[1_u32, 2, 3],
+            "Year" => &["2021", "2022", "All Years"],
+            "NetProfit" => &[10.0, 20.0, 30.0],
+        )
+        .unwrap();
+        assert_eq!(target, consolidate_reports([&per_year, &all_years].into_iter()));
     }
 }
@@ -1,6 +1,9 @@
 mod backtesting_batch_data;
+pub mod dataset_export;
 mod execution_data;
+pub mod excluded_time_range;
 pub mod indicator_data_pair;
+pub mod instrument_override;
 pub mod pre_trade_data;
 pub mod time_frame_snapshot;
 pub mod time_interval;
@@ -8,31 +11,46 @@ pub mod trade;
 pub mod trading_session;
 pub mod transformer;
 use self::{
-    indicator_data_pair::IndicatorDataPair, time_interval::TimeInterval,
+    excluded_time_range::ExcludedTimeRange, indicator_data_pair::IndicatorDataPair,
+    instrument_override::InstrumentOverride, time_interval::TimeInterval,
     trading_session::TradingSessionBuilder,
 };
 use crate::{
     backtest_result::{BacktestResult, MarketAndYearBacktestResult},
+    calculator::pre_trade_values_cache::PreTradeValuesCache,
     config::GoogleCloudBucket,
+    converter::report_format::ReportFormat,
     data_provider::DataProvider,
+    equity_curve::Capital,
     enums::{
-        bot::TimeFrameKind,
-        data::{HdbSourceDirKind, MarketSimulationDataKind},
+        bot::{ExecutionModeKind, PeriodKind, TimeFrameKind},
+        data::{CompressionKind, HdbSourceDirKind, MarketSimulationDataKind},
         error::ChapatyErrorKind,
+        indicator::{PocSelectionRule, ValueAreaRule},
         markets::MarketKind,
     },
+    instrumentation,
+    notification::{spawn_signal_dispatcher, NotificationSink},
     pnl::{
         pnl_report::{PnLReport, PnLReports},
         pnl_statement::PnLStatement,
     },
+    report_column::{append_trade_columns, ReportColumn},
     strategy::Strategy,
+    streaming_report::{OnlineTradeStatistics, StreamingReportWriter},
+    trading_indicator::price_histogram::DEFAULT_VALUE_AREA_PERCENT,
 };
+use chrono::NaiveDate;
 use google_cloud_storage::client::Client;
 use mockall::automock;
+use polars::prelude::DataFrame;
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Instant,
 };
+use tokio::sync::Semaphore;
 
 #[derive(Clone)]
 pub struct Bot {
@@ -45,9 +63,28 @@ pub struct Bot {
     years: Vec<u32>,
     market_simulation_data: MarketSimulationDataKind,
     time_interval: Option<TimeInterval>,
+    excluded_time_ranges: HashMap<MarketKind, Vec<ExcludedTimeRange>>,
+    instrument_overrides: HashMap<MarketKind, InstrumentOverride>,
     time_frame: TimeFrameKind,
+    period: PeriodKind,
+    holidays: Vec<NaiveDate>,
     save_result_as_csv: bool,
     cache_computations: bool,
+    execution_mode: ExecutionModeKind,
+    poc_selection_rule: Option<PocSelectionRule>,
+    value_area_rule: ValueAreaRule,
+    value_area_percent: f64,
+    pre_trade_values_cache: Option<PreTradeValuesCache>,
+    max_parallel_backtest_units: Option<usize>,
+    incremental_backtest: bool,
+    flat_at_session_end: bool,
+    dataset_export_dir: Option<PathBuf>,
+    streaming_report_file: Option<PathBuf>,
+    cache_compression: CompressionKind,
+    capital: Capital,
+    report_format: ReportFormat,
+    notification_sinks: Vec<Arc<dyn NotificationSink + Send + Sync>>,
+    report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
 }
 pub struct BotBuilder {
     client: Option<Client>,
@@ -59,16 +96,38 @@ pub struct BotBuilder {
     years: Vec<u32>,
     market_simulation_data: MarketSimulationDataKind,
     time_interval: Option<TimeInterval>,
+    excluded_time_ranges: HashMap<MarketKind, Vec<ExcludedTimeRange>>,
+    instrument_overrides: HashMap<MarketKind, InstrumentOverride>,
     time_frame: TimeFrameKind,
+    period: PeriodKind,
+    holidays: Vec<NaiveDate>,
     save_result_as_csv: bool,
     cache_computations: bool,
+    execution_mode: ExecutionModeKind,
+    poc_selection_rule: Option<PocSelectionRule>,
+    value_area_rule: ValueAreaRule,
+    value_area_percent: f64,
+    pre_trade_values_cache: Option<PreTradeValuesCache>,
+    max_parallel_backtest_units: Option<usize>,
+    incremental_backtest: bool,
+    flat_at_session_end: bool,
+    dataset_export_dir: Option<PathBuf>,
+    streaming_report_file: Option<PathBuf>,
+    cache_compression: CompressionKind,
+    capital: Capital,
+    report_format: ReportFormat,
+    notification_sinks: Vec<Arc<dyn NotificationSink + Send + Sync>>,
+    report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
     // news_filter: Option<Vec<EconomicNews>>,
 }
 
 #[automock]
 impl Bot {
-    pub async fn backtest(&self) -> BacktestResult {
-        let pnl_statement = self.compute_pnl_statement().await;
+    /// # Errors
+    /// Returns the first error of a backtest unit, e.g. if a notification sink fails to send a
+    /// trade signal.
+    pub async fn backtest(&self) -> Result<BacktestResult, ChapatyErrorKind> {
+        let pnl_statement = self.compute_pnl_statement().await?;
 
         let performance_report = pnl_statement.compute_performance_report();
         let trade_breakdown_report = pnl_statement.compute_trade_breakdown_report();
@@ -81,13 +140,16 @@ impl Bot {
             equity_curves,
         };
 
-        let res: BacktestResult = market_and_year_backtest_result.into();
+        let mut res: BacktestResult = market_and_year_backtest_result.into();
+        if !self.report_format.is_precise() {
+            res.round_reports(&self.report_format);
+        }
 
         if self.save_result_as_csv {
-            res.save_as_csv(&self.name);
+            res.save_as_csv_with_format(&self.name, &self.report_format);
         }
 
-        res
+        Ok(res)
     }
 
     pub fn get_shared_pointer(&self) -> Arc<Bot> {
@@ -126,67 +188,188 @@ impl Bot {
         &self.time_interval
     }
 
-    async fn compute_pnl_statement(&self) -> PnLStatement {
+    pub fn get_cache_compression(&self) -> CompressionKind {
+        self.cache_compression
+    }
+
+    pub fn get_capital(&self) -> Capital {
+        self.capital
+    }
+
+    pub fn get_period(&self) -> PeriodKind {
+        self.period
+    }
+
+    pub fn get_holidays_ref(&self) -> &[NaiveDate] {
+        &self.holidays
+    }
+
+    pub fn get_excluded_time_ranges(&self, market: &MarketKind) -> &[ExcludedTimeRange] {
+        self.excluded_time_ranges
+            .get(market)
+            .map_or(&[], |ranges| ranges.as_slice())
+    }
+
+    pub fn get_instrument_override(&self, market: &MarketKind) -> Option<InstrumentOverride> {
+        self.instrument_overrides.get(market).copied()
+    }
+
+    /// Streams the profit and loss report of every market and year to the streaming report file
+    /// as soon as it is computed and drops it afterwards. In contrast to `backtest`, no report is
+    /// kept in memory, hence at most `max_parallel_backtest_units` reports are in memory at the
+    /// same time. Returns the aggregate metrics of all streamed trades, which are also saved next
+    /// to the file as `*.statistics.json`.
+    pub async fn backtest_streaming(&self) -> Result<OnlineTradeStatistics, ChapatyErrorKind> {
+        let file = self.streaming_report_file.as_ref().ok_or_else(|| {
+            ChapatyErrorKind::FailedToWriteStreamingReport(
+                "Streaming report file is not set. Use BotBuilder::with_streaming_report_file"
+                    .to_string(),
+            )
+        })?;
+        let writer = Arc::new(Mutex::new(StreamingReportWriter::new(file)?));
+        let backtest_unit_permits = self.backtest_unit_permits();
+        let tasks: Vec<_> = self
+            .markets
+            .iter()
+            .flat_map(|market| self.years.iter().map(move |year| (*market, *year)))
+            .map(|(market, year)| {
+                let _self = self.clone();
+                let permits = backtest_unit_permits.clone();
+                let writer = writer.clone();
+                tokio::spawn(async move {
+                    let pnl = _self.compute_backtest_unit(market, year, permits).await?;
+                    writer.lock().unwrap().append(&pnl)
+                })
+            })
+            .collect();
+
+        for result in futures::future::join_all(tasks).await {
+            result.unwrap()?;
+        }
+
+        let writer = Arc::try_unwrap(writer)
+            .ok()
+            .expect("All backtest units have finished")
+            .into_inner()
+            .unwrap();
+        let statistics = writer.finish()?;
+        let statistics_file = file.with_extension("statistics.json");
+        let json = serde_json::to_string_pretty(&statistics).unwrap();
+        std::fs::write(&statistics_file, json).map_err(|e| {
+            ChapatyErrorKind::FailedToWriteStreamingReport(format!(
+                "Failed to save streaming report statistics {statistics_file:?}: {e}"
+            ))
+        })?;
+
+        Ok(statistics)
+    }
+
+    async fn compute_pnl_statement(&self) -> Result<PnLStatement, ChapatyErrorKind> {
+        let backtest_unit_permits = self.backtest_unit_permits();
         let tasks: Vec<_> = self
             .markets
             .clone()
             .into_iter()
             .map(|market| {
                 let _self = self.clone();
-                tokio::spawn(async move { _self.compute_pnl_reports(market).await })
+                let permits = backtest_unit_permits.clone();
+                tokio::spawn(async move { _self.compute_pnl_reports(market, permits).await })
             })
             .collect();
 
-        let pnl_data = futures::future::join_all(tasks).await.into_iter().fold(
-            HashMap::new(),
-            |mut pnl_data, pnl_report| {
-                let pnl_report = pnl_report.unwrap();
-                pnl_data.insert(pnl_report.market, pnl_report);
-                pnl_data
-            },
-        );
+        let mut pnl_data = HashMap::new();
+        for pnl_report in futures::future::join_all(tasks).await {
+            let pnl_report = pnl_report.unwrap()?;
+            pnl_data.insert(pnl_report.market, pnl_report);
+        }
 
-        PnLStatement {
+        Ok(PnLStatement {
             strategy_name: self.strategy.get_name(),
             markets: self.markets.clone(),
             pnl_data,
-        }
+            capital: self.capital,
+            report_columns: self.report_columns.clone(),
+        })
     }
 
-    async fn compute_pnl_reports(&self, market: MarketKind) -> PnLReports {
-        let trading_session_builder = TradingSessionBuilder::new()
-            .with_bot(self.get_shared_pointer())
-            .with_indicator_data_pair(self.determine_indicator_data_pair())
-            .with_cache_computations(self.cache_computations)
-            .with_market(market)
-            .with_market_sim_data_kind(self.market_simulation_data);
+    /// Every market-year backtest unit holds a permit while its data is loaded and processed.
+    /// Hence, at most `max_parallel_backtest_units` units are in memory at the same time, while
+    /// the remaining units wait in the queue.
+    fn backtest_unit_permits(&self) -> Arc<Semaphore> {
+        let permits = self
+            .max_parallel_backtest_units
+            .unwrap_or(Semaphore::MAX_PERMITS);
+        Arc::new(Semaphore::new(permits))
+    }
 
+    async fn compute_pnl_reports(
+        &self,
+        market: MarketKind,
+        backtest_unit_permits: Arc<Semaphore>,
+    ) -> Result<PnLReports, ChapatyErrorKind> {
         let tasks: Vec<_> = self
             .years
             .clone()
             .into_iter()
             .map(|year| {
-                let builder = trading_session_builder.clone();
-                let strategy = self.strategy.get_name();
+                let _self = self.clone();
+                let permits = backtest_unit_permits.clone();
                 tokio::spawn(async move {
-                    let session = builder.with_year(year).build().await;
-                    PnLReport {
+                    Ok::<_, ChapatyErrorKind>(PnLReport {
                         market,
                         year,
-                        strategy,
-                        pnl: session.compute_pnl_report().await,
-                    }
+                        strategy: _self.strategy.get_name(),
+                        pnl: _self.compute_backtest_unit(market, year, permits).await?,
+                    })
                 })
             })
             .collect();
 
-        futures::future::join_all(tasks)
+        let pnl_reports = futures::future::join_all(tasks)
             .await
             .into_iter()
             .map(Result::unwrap)
-            .collect::<Vec<_>>()
-            .into_iter()
-            .collect()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(pnl_reports.into_iter().collect())
+    }
+
+    /// Computes the profit and loss report of a single market-year backtest unit while holding a
+    /// permit of `backtest_unit_permits`. Trade signals are sent to the notification sinks as soon
+    /// as their session is computed.
+    async fn compute_backtest_unit(
+        &self,
+        market: MarketKind,
+        year: u32,
+        backtest_unit_permits: Arc<Semaphore>,
+    ) -> Result<DataFrame, ChapatyErrorKind> {
+        let _permit = backtest_unit_permits.acquire_owned().await.unwrap();
+        let start = Instant::now();
+        let (signal_sender, signal_dispatcher) = if self.notification_sinks.is_empty() {
+            (None, None)
+        } else {
+            let (tx, dispatcher) = spawn_signal_dispatcher(self.notification_sinks.clone());
+            (Some(tx), Some(dispatcher))
+        };
+        let session = TradingSessionBuilder::new()
+            .with_bot(self.get_shared_pointer())
+            .with_indicator_data_pair(self.determine_indicator_data_pair())
+            .with_cache_computations(self.cache_computations)
+            .with_market(market)
+            .with_market_sim_data_kind(self.market_simulation_data)
+            .with_year(year)
+            .with_signal_sender(signal_sender)
+            .build()
+            .await;
+        if let Some(dir) = &self.dataset_export_dir {
+            session.export_dataset(dir).await?;
+        }
+        let pnl = session.compute_pnl_report().await;
+        let pnl = append_trade_columns(pnl, &self.report_columns)?;
+        instrumentation::record_backtest_unit(start.elapsed());
+        if let Some(signal_dispatcher) = signal_dispatcher {
+            signal_dispatcher.await??;
+        }
+        Ok(pnl)
     }
 
     fn determine_indicator_data_pair(&self) -> Arc<HashSet<IndicatorDataPair>> {
@@ -226,9 +409,28 @@ impl BotBuilder {
             years: vec![],
             market_simulation_data: MarketSimulationDataKind::Ohlc1m,
             time_interval: None,
+            excluded_time_ranges: HashMap::new(),
+            instrument_overrides: HashMap::new(),
             time_frame: TimeFrameKind::Daily,
+            period: PeriodKind::CalendarDay,
+            holidays: Vec::new(),
             save_result_as_csv: false,
             cache_computations: false,
+            execution_mode: ExecutionModeKind::Candle,
+            poc_selection_rule: None,
+            value_area_rule: ValueAreaRule::ClassicTwoRow,
+            value_area_percent: DEFAULT_VALUE_AREA_PERCENT,
+            pre_trade_values_cache: None,
+            max_parallel_backtest_units: None,
+            incremental_backtest: false,
+            flat_at_session_end: false,
+            dataset_export_dir: None,
+            streaming_report_file: None,
+            cache_compression: CompressionKind::None,
+            capital: Capital::default(),
+            report_format: ReportFormat::default(),
+            notification_sinks: Vec::new(),
+            report_columns: Vec::new(),
         }
     }
 
@@ -261,10 +463,52 @@ impl BotBuilder {
         }
     }
 
+    /// Excludes the given time ranges of the market from the backtest, e.g. exchange maintenance
+    /// windows or known data outages. The ranges are applied together with the `TimeInterval`, and
+    /// every candle that overlaps a range is dropped.
+    pub fn with_excluded_time_ranges(
+        mut self,
+        market: MarketKind,
+        excluded_time_ranges: Vec<ExcludedTimeRange>,
+    ) -> Self {
+        self.excluded_time_ranges
+            .entry(market)
+            .or_default()
+            .extend(excluded_time_ranges);
+        self
+    }
+
+    /// Replaces the hard-coded contract specification of the market, e.g. to backtest a micro
+    /// contract such as MES or M6E on the data of the full-size contract. The `fee` in dollar is
+    /// deducted from the profit and loss of every trade.
+    pub fn with_instrument_override(
+        mut self,
+        market: MarketKind,
+        tick_size: f64,
+        tick_value: f64,
+        fee: f64,
+    ) -> Self {
+        self.instrument_overrides
+            .insert(market, InstrumentOverride::new(tick_size, tick_value, fee));
+        self
+    }
+
     pub fn with_time_frame(self, time_frame: TimeFrameKind) -> Self {
         Self { time_frame, ..self }
     }
 
+    /// Sets the `PeriodKind` that determines the trading day of the daily time frame, e.g.
+    /// `PeriodKind::BusinessDay` attaches the Sunday evening session of CME data to Monday.
+    pub fn with_period(self, period: PeriodKind) -> Self {
+        Self { period, ..self }
+    }
+
+    /// Exchange holidays that are skipped by `PeriodKind::BusinessDay`, i.e. their sessions roll
+    /// forward to the next business day.
+    pub fn with_holidays(self, holidays: Vec<NaiveDate>) -> Self {
+        Self { holidays, ..self }
+    }
+
     pub fn with_google_cloud_storage_client(self, client: Client) -> Self {
         Self {
             client: Some(client),
@@ -286,11 +530,164 @@ impl BotBuilder {
         }
     }
 
+    /// Compresses cached computations, e.g. with `CompressionKind::Zstd { level: 3 }`. Cached data
+    /// is decompressed independent of this setting, hence the compression can be changed without
+    /// invalidating the cache.
+    pub fn with_cache_compression(self, cache_compression: CompressionKind) -> Self {
+        Self {
+            cache_compression,
+            ..self
+        }
+    }
+
     pub fn with_google_cloud_bucket(self, bucket: GoogleCloudBucket) -> Self {
         Self { bucket, ..self }
     }
 
+    /// Sets the `ExecutionModeKind`. `ExecutionModeKind::TradeReplay` requires aggregated trades
+    /// data for the selected markets.
+    pub fn with_execution_mode(self, execution_mode: ExecutionModeKind) -> Self {
+        Self {
+            execution_mode,
+            ..self
+        }
+    }
+
+    /// Sets the `PocSelectionRule` that is used by all price histograms to resolve multiple POCs.
+    pub fn with_poc_selection_rule(self, poc_selection_rule: PocSelectionRule) -> Self {
+        Self {
+            poc_selection_rule: Some(poc_selection_rule),
+            ..self
+        }
+    }
+
+    /// Sets the `ValueAreaRule` that is used by all price histograms to expand the value area.
+    pub fn with_value_area_rule(self, value_area_rule: ValueAreaRule) -> Self {
+        Self {
+            value_area_rule,
+            ..self
+        }
+    }
+
+    /// Sets the share of the price histogram that is covered by the value area, e.g. `0.7` for a
+    /// 70% value area. The share must be in `(0, 1]` and defaults to `0.63`.
+    pub fn with_value_area_percent(self, value_area_percent: f64) -> Self {
+        Self {
+            value_area_percent,
+            ..self
+        }
+    }
+
+    /// Shares a `PreTradeValuesCache` with other bots, such that pre-trade values of the same
+    /// market and time window are only computed once. Bots that differ in excluded time ranges,
+    /// period, holidays or instrument overrides do not read each other's values.
+    pub fn with_pre_trade_values_cache(
+        self,
+        pre_trade_values_cache: PreTradeValuesCache,
+    ) -> Self {
+        Self {
+            pre_trade_values_cache: Some(pre_trade_values_cache),
+            ..self
+        }
+    }
+
+    /// Limits the number of market-year backtest units that are computed in parallel. Further units
+    /// are queued until a running unit has finished. By default, all units run in parallel.
+    pub fn with_max_parallel_backtest_units(self, max_parallel_backtest_units: usize) -> Self {
+        Self {
+            max_parallel_backtest_units: Some(max_parallel_backtest_units),
+            ..self
+        }
+    }
+
+    /// Caches the pnl report of every market and year per calendar week in the cached bot data
+    /// bucket. Subsequent backtests only compute calendar weeks that are not cached yet, e.g. after
+    /// a new week of data arrived, and merge them into the cached pnl report. The cache is keyed
+    /// by the name of the bot and a fingerprint of its configuration, e.g. the parameters of the
+    /// strategy, the capital and the execution mode, hence a changed configuration never reuses
+    /// the cached pnl report. The merged pnl report is only cached with `with_cache_computations`.
+    /// Incremental backtests are only supported for the daily time frame.
+    pub fn with_incremental_backtest(self, incremental_backtest: bool) -> Self {
+        Self {
+            incremental_backtest,
+            ..self
+        }
+    }
+
+    /// Closes open trades at the end of the session defined by the `TimeInterval`, i.e. at the first
+    /// `end_h` after the session start for the daily time frame and at the first `end_day:end_h`
+    /// for the weekly time frame, see `TimeInterval::session_end_ts`. These trades are reported
+    /// with `TerminationReason::SessionEnd`. Without a `TimeInterval`, this option has no effect.
+    pub fn with_flat_at_session_end(self, flat_at_session_end: bool) -> Self {
+        Self {
+            flat_at_session_end,
+            ..self
+        }
+    }
+
+    /// Exports the canonicalized, filtered and indicator enriched data of every market and year to
+    /// `{dataset_export_dir}/{market}/{year}` as Parquet files with a `manifest.json`, such that
+    /// the exact inputs of the backtest can be shared. If the export fails, the backtest returns
+    /// `ChapatyErrorKind::FailedToExportDataset`.
+    pub fn with_dataset_export_dir(self, dataset_export_dir: PathBuf) -> Self {
+        Self {
+            dataset_export_dir: Some(dataset_export_dir),
+            ..self
+        }
+    }
+
+    /// Sets the file that `Bot::backtest_streaming` appends the profit and loss report of every
+    /// market and year to, as CSV or, with the extension `parquet`, as Parquet.
+    pub fn with_streaming_report_file(self, streaming_report_file: PathBuf) -> Self {
+        Self {
+            streaming_report_file: Some(streaming_report_file),
+            ..self
+        }
+    }
+
+    /// Sets the initial capital of the equity curves and performance reports. With compounding,
+    /// the position size scales with the current equity. By default, equity curves start at `0.0`
+    /// with a fixed position size.
+    pub fn with_capital(self, capital: Capital) -> Self {
+        Self { capital, ..self }
+    }
+
+    /// Registers sinks that are notified about every trade signal generated by the strategy, as
+    /// soon as the session of the signal is computed. If a sink fails, the backtest returns
+    /// `ChapatyErrorKind::FailedToSendNotification`.
+    pub fn with_notification_sinks(
+        self,
+        notification_sinks: Vec<Arc<dyn NotificationSink + Send + Sync>>,
+    ) -> Self {
+        Self {
+            notification_sinks,
+            ..self
+        }
+    }
+
+    /// Sets the rounding and currency formatting of the performance and trade breakdown reports.
+    /// By default, the reports keep the precise values.
+    pub fn with_report_format(self, report_format: ReportFormat) -> Self {
+        Self {
+            report_format,
+            ..self
+        }
+    }
+
+    /// Registers custom columns that are appended to the profit and loss reports and the trade
+    /// breakdown reports.
+    pub fn with_report_columns(
+        self,
+        report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
+    ) -> Self {
+        Self {
+            report_columns,
+            ..self
+        }
+    }
+
     pub fn build(self) -> Result<Bot, ChapatyErrorKind> {
+        self.validate()?;
         let client = self.client.ok_or(
             ChapatyErrorKind::BuildBotError("Google Cloud Client is not initalized. Use BotBuilder::with_google_cloud_client for initalization"
             .to_string()))?;
@@ -305,11 +702,82 @@ impl BotBuilder {
             years: self.years,
             market_simulation_data: self.market_simulation_data,
             time_interval: self.time_interval,
+            excluded_time_ranges: self.excluded_time_ranges,
+            instrument_overrides: self.instrument_overrides,
             time_frame: self.time_frame,
+            period: self.period,
+            holidays: self.holidays,
             save_result_as_csv: self.save_result_as_csv,
             cache_computations: self.cache_computations,
+            execution_mode: self.execution_mode,
+            poc_selection_rule: self.poc_selection_rule,
+            value_area_rule: self.value_area_rule,
+            value_area_percent: self.value_area_percent,
+            pre_trade_values_cache: self.pre_trade_values_cache,
+            max_parallel_backtest_units: self.max_parallel_backtest_units,
+            incremental_backtest: self.incremental_backtest,
+            flat_at_session_end: self.flat_at_session_end,
+            dataset_export_dir: self.dataset_export_dir,
+            streaming_report_file: self.streaming_report_file,
+            cache_compression: self.cache_compression,
+            capital: self.capital,
+            report_format: self.report_format,
+            notification_sinks: self.notification_sinks,
+            report_columns: self.report_columns,
         })
     }
+    fn validate(&self) -> Result<(), ChapatyErrorKind> {
+        if let Some(range) = self
+            .excluded_time_ranges
+            .values()
+            .flatten()
+            .find(|range| !range.is_valid())
+        {
+            return Err(ChapatyErrorKind::BuildBotError(format!(
+                "Excluded time range <{}, {}> must end after it starts",
+                range.start, range.end
+            )));
+        }
+
+        if let Some((market, _)) = self
+            .instrument_overrides
+            .iter()
+            .find(|(_, instrument_override)| !instrument_override.is_valid())
+        {
+            return Err(ChapatyErrorKind::BuildBotError(format!(
+                "Instrument override of <{market}> must have a positive tick size and tick value, and a non-negative fee"
+            )));
+        }
+
+        if !self.capital.is_valid() {
+            return Err(ChapatyErrorKind::BuildBotError(format!(
+                "Initial capital <{}> must not be negative, and positive for compounding",
+                self.capital.initial
+            )));
+        }
+
+        if !(self.value_area_percent > 0.0 && self.value_area_percent <= 1.0) {
+            return Err(ChapatyErrorKind::BuildBotError(format!(
+                "Value area percent <{}> must be in (0, 1]",
+                self.value_area_percent
+            )));
+        }
+
+        if self.max_parallel_backtest_units == Some(0) {
+            return Err(ChapatyErrorKind::BuildBotError(
+                "The maximum number of parallel backtest units must be positive".to_string(),
+            ));
+        }
+
+        if self.incremental_backtest && self.time_frame != TimeFrameKind::Daily {
+            return Err(ChapatyErrorKind::BuildBotError(format!(
+                "Incremental backtests are only supported for the daily time frame, not <{}>",
+                self.time_frame
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -323,7 +791,7 @@ mod test {
             indicator::{PriceHistogramKind, TradingIndicatorKind},
         },
         strategy::{MockStrategy, RequriedPreTradeValues},
-        BotBuilder,
+        BotBuilder, TimeFrameKind,
     };
     use std::{collections::HashSet, sync::Arc};
 
@@ -377,4 +845,30 @@ mod test {
 
         assert_eq!(*required_data, expected);
     }
+
+    #[test]
+    fn test_incremental_backtest_requires_daily_time_frame() {
+        let builder = || BotBuilder::new(Arc::new(MockStrategy::new()), Arc::new(Cme));
+
+        let weekly = builder()
+            .with_incremental_backtest(true)
+            .with_time_frame(TimeFrameKind::Weekly);
+        assert!(weekly.validate().is_err());
+
+        let daily = builder()
+            .with_incremental_backtest(true)
+            .with_time_frame(TimeFrameKind::Daily);
+        assert!(daily.validate().is_ok());
+    }
+
+    #[test]
+    fn test_value_area_percent_must_be_a_share() {
+        let builder = || BotBuilder::new(Arc::new(MockStrategy::new()), Arc::new(Cme));
+
+        assert!(builder().with_value_area_percent(0.0).validate().is_err());
+        assert!(builder().with_value_area_percent(1.5).validate().is_err());
+        assert!(builder().with_value_area_percent(f64::NAN).validate().is_err());
+        assert!(builder().with_value_area_percent(0.7).validate().is_ok());
+        assert!(builder().with_value_area_percent(1.0).validate().is_ok());
+    }
 }
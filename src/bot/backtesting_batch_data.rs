@@ -5,4 +5,5 @@ pub struct BacktestingBatchData {
     pub time_frame_snapshot: TimeFrameSnapshot,
     pub market_sim_data: DataFrame,
     pub pre_trade_data: PreTradeData,
+    pub trades: Option<DataFrame>,
 }
@@ -0,0 +1,220 @@
+use super::{
+    excluded_time_range::ExcludedTimeRange, time_frame_snapshot::TimeFrameSnapshot,
+    trading_session::TradingSession,
+};
+use crate::{chapaty, enums::error::ChapatyErrorKind};
+use polars::prelude::{DataFrame, NamedFrom, ParquetWriter, Series};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// Describes the exported dataset of a single market and year, such that the exact inputs of a
+/// backtest can be shared and reproduced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    pub bot: String,
+    pub strategy: String,
+    pub data_provider: String,
+    pub market: String,
+    pub year: u32,
+    pub market_simulation_data: String,
+    pub time_frame: String,
+    pub time_interval: Option<String>,
+    pub excluded_time_ranges: Vec<ExcludedTimeRange>,
+    pub streams: Vec<DatasetStream>,
+}
+
+/// A single exported stream, e.g. the market simulation data or a trading indicator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetStream {
+    pub name: String,
+    pub file: String,
+    pub rows: usize,
+    pub columns: Vec<String>,
+}
+
+impl TradingSession {
+    /// Writes every stream of the canonicalized and filtered data of this session to
+    /// `{dir}/{market}/{year}/{stream}.parquet`, together with a `manifest.json`. The rows of each
+    /// stream are tagged with the calendar week `cw` and, for the daily time frame, the `weekday`
+    /// of the time frame snapshot they belong to. The files are written on the blocking thread
+    /// pool of tokio.
+    pub async fn export_dataset(&self, dir: &Path) -> Result<DatasetManifest, ChapatyErrorKind> {
+        let session = self.clone();
+        let dir = dir.to_path_buf();
+        tokio::task::spawn_blocking(move || session.write_dataset(&dir)).await?
+    }
+
+    fn write_dataset(&self, dir: &Path) -> Result<DatasetManifest, ChapatyErrorKind> {
+        let dataset_dir = dir
+            .join(self.market.to_string())
+            .join(self.year.to_string());
+        fs::create_dir_all(&dataset_dir).map_err(to_export_error)?;
+
+        let mut streams = vec![("market_sim_data".to_string(), &self.data.market_sim_data)];
+        let mut indicators: Vec<_> = self
+            .data
+            .trading_indicators
+            .iter()
+            .map(|(indicator, df_map)| (stream_name(&format!("{indicator:?}")), df_map))
+            .collect();
+        indicators.sort_by(|a, b| a.0.cmp(&b.0));
+        streams.extend(indicators);
+        if let Some(trades) = &self.data.trades {
+            streams.push(("trades".to_string(), trades));
+        }
+
+        let streams = streams
+            .into_iter()
+            .map(|(name, df_map)| export_stream(&dataset_dir, name, df_map))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let manifest = DatasetManifest {
+            bot: self.bot.name.clone(),
+            strategy: self.bot.strategy.get_name(),
+            data_provider: self.bot.data_provider.get_name(),
+            market: self.market.to_string(),
+            year: self.year,
+            market_simulation_data: self.market_sim_data_kind.to_string(),
+            time_frame: self.bot.time_frame.to_string(),
+            time_interval: self.bot.time_interval.map(|v| v.to_string()),
+            excluded_time_ranges: self.bot.get_excluded_time_ranges(&self.market).to_vec(),
+            streams,
+        };
+        let manifest_as_json = serde_json::to_string_pretty(&manifest).map_err(to_export_error)?;
+        fs::write(dataset_dir.join("manifest.json"), manifest_as_json).map_err(to_export_error)?;
+
+        Ok(manifest)
+    }
+}
+
+fn export_stream(
+    dataset_dir: &Path,
+    name: String,
+    df_map: &chapaty::types::DataFrameMap,
+) -> Result<DatasetStream, ChapatyErrorKind> {
+    let mut df = concatenate_df_map(df_map)?;
+    let file = format!("{name}.parquet");
+    let writer = fs::File::create(dataset_dir.join(&file)).map_err(to_export_error)?;
+    ParquetWriter::new(writer)
+        .finish(&mut df)
+        .map_err(to_export_error)?;
+
+    Ok(DatasetStream {
+        name,
+        file,
+        rows: df.height(),
+        columns: df
+            .get_column_names()
+            .iter()
+            .map(|c| c.to_string())
+            .collect(),
+    })
+}
+
+/// Stacks the data frames of all time frame snapshots in chronological order.
+fn concatenate_df_map(
+    df_map: &chapaty::types::DataFrameMap,
+) -> Result<DataFrame, ChapatyErrorKind> {
+    let mut snapshots: Vec<_> = df_map.keys().collect();
+    snapshots.sort_by_key(|s| snapshot_columns(s));
+
+    snapshots
+        .into_iter()
+        .map(|snapshot| tag_with_snapshot(&df_map[snapshot], snapshot))
+        .try_fold(DataFrame::default(), |acc, df| {
+            let df = df?;
+            if acc.width() == 0 {
+                Ok(df)
+            } else {
+                acc.vstack(&df).map_err(to_export_error)
+            }
+        })
+}
+
+fn tag_with_snapshot(
+    df: &DataFrame,
+    snapshot: &TimeFrameSnapshot,
+) -> Result<DataFrame, ChapatyErrorKind> {
+    let mut df = df.drop_many(&["cw", "weekday"]);
+    let (cw, weekday) = snapshot_columns(snapshot);
+    let mut tags = vec![Series::new("cw", vec![cw; df.height()])];
+    if let Some(weekday) = weekday {
+        tags.push(Series::new("weekday", vec![weekday; df.height()]));
+    }
+    for (idx, tag) in tags.into_iter().enumerate() {
+        df.insert_at_idx(idx, tag).map_err(to_export_error)?;
+    }
+    Ok(df)
+}
+
+fn snapshot_columns(snapshot: &TimeFrameSnapshot) -> (i64, Option<i64>) {
+    (
+        snapshot.get_calendar_week_as_int(),
+        snapshot.get_weekday_as_int_optional(),
+    )
+}
+
+/// E.g. `Poc(Tpo1m)` becomes `poc_tpo1m`
+fn stream_name(indicator: &str) -> String {
+    indicator.to_lowercase().replace('(', "_").replace(')', "")
+}
+
+fn to_export_error(e: impl ToString) -> ChapatyErrorKind {
+    ChapatyErrorKind::FailedToExportDataset(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::time_frame_snapshot::TimeFrameSnapshotBuilder;
+    use polars::{
+        df,
+        prelude::{ParquetReader, SerReader},
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_concatenate_df_map() {
+        let df_map = HashMap::from([
+            (
+                TimeFrameSnapshotBuilder::new(2).with_weekday(1).build(),
+                df!("ots" => &[3_i64], "cw" => &[2_i64], "weekday" => &[1_i64]).unwrap(),
+            ),
+            (
+                TimeFrameSnapshotBuilder::new(1).with_weekday(5).build(),
+                df!("ots" => &[1_i64, 2], "cw" => &[1_i64, 1], "weekday" => &[5_i64, 5]).unwrap(),
+            ),
+        ]);
+
+        let target = df!(
+            "cw" => &[1_i64, 1, 2],
+            "weekday" => &[5_i64, 5, 1],
+            "ots" => &[1_i64, 2, 3],
+        )
+        .unwrap();
+        assert_eq!(target, concatenate_df_map(&df_map).unwrap());
+    }
+
+    #[test]
+    fn test_export_stream() {
+        let dataset_dir = tempfile::tempdir().unwrap();
+        let df_map = HashMap::from([(
+            TimeFrameSnapshotBuilder::new(1).with_weekday(5).build(),
+            df!("ots" => &[1_i64, 2], "close" => &[1.1, 1.2]).unwrap(),
+        )]);
+
+        let stream =
+            export_stream(dataset_dir.path(), "market_sim_data".to_string(), &df_map).unwrap();
+
+        assert_eq!("market_sim_data.parquet", stream.file);
+        assert_eq!(2, stream.rows);
+        let file = fs::File::open(dataset_dir.path().join(&stream.file)).unwrap();
+        let exported = ParquetReader::new(file).finish().unwrap();
+        assert_eq!(concatenate_df_map(&df_map).unwrap(), exported);
+    }
+
+    #[test]
+    fn test_stream_name() {
+        assert_eq!("poc_tpo1m", stream_name("Poc(Tpo1m)"));
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// A half-open range [start, end) of **UTC** timestamps in **milliseconds**, e.g. an exchange
+/// maintenance window or a known data outage, that is excluded from the backtest of a market.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExcludedTimeRange {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl ExcludedTimeRange {
+    pub fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    pub fn contains(&self, utc_ts_in_milliseconds: i64) -> bool {
+        self.start <= utc_ts_in_milliseconds && utc_ts_in_milliseconds < self.end
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.start < self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let range = ExcludedTimeRange::new(60_000, 120_000);
+        assert!(!range.contains(59_999));
+        assert!(range.contains(60_000));
+        assert!(range.contains(119_999));
+        assert!(!range.contains(120_000));
+        assert!(range.is_valid());
+        assert!(!ExcludedTimeRange::new(120_000, 60_000).is_valid());
+    }
+}
@@ -5,4 +5,5 @@ use std::collections::HashMap;
 pub struct ExecutionData {
     pub market_sim_data: chapaty::types::DataFrameMap,
     pub trading_indicators: HashMap<TradingIndicatorKind, chapaty::types::DataFrameMap>,
+    pub trades: Option<chapaty::types::DataFrameMap>,
 }
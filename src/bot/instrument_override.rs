@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+
+/// Overrides the hard-coded contract specification of a market, e.g. to backtest a micro contract
+/// on the data of the full-size contract.
+/// * `tick_size` - the minimum price increment
+/// * `tick_value` - the dollar value of one tick
+/// * `fee` - the fee in dollar that is charged for every trade, i.e. entry and exit
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentOverride {
+    pub tick_size: f64,
+    pub tick_value: f64,
+    pub fee: f64,
+}
+
+impl InstrumentOverride {
+    pub fn new(tick_size: f64, tick_value: f64, fee: f64) -> Self {
+        Self {
+            tick_size,
+            tick_value,
+            fee,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.tick_size > 0.0 && self.tick_value > 0.0 && self.fee >= 0.0
+    }
+}
+
+/// Valid instrument overrides never contain `NaN`, hence the equality is total.
+impl Eq for InstrumentOverride {}
+
+impl Hash for InstrumentOverride {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tick_size.to_bits().hash(state);
+        self.tick_value.to_bits().hash(state);
+        self.fee.to_bits().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instrument_override_is_valid() {
+        assert!(InstrumentOverride::new(0.25, 1.25, 0.62).is_valid());
+        assert!(InstrumentOverride::new(0.25, 1.25, 0.0).is_valid());
+        assert!(!InstrumentOverride::new(0.0, 1.25, 0.62).is_valid());
+        assert!(!InstrumentOverride::new(0.25, -1.25, 0.62).is_valid());
+        assert!(!InstrumentOverride::new(0.25, 1.25, -0.62).is_valid());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::enums::indicator::TradingIndicatorKind;
+use crate::{
+    enums::indicator::TradingIndicatorKind,
+    trading_indicator::{
+        candle_pattern::Candle, gap::GapStatistics, higher_time_frame_levels::HigherTimeFrameLevels,
+    },
+};
 use polars::prelude::DataFrame;
 use std::collections::HashMap;
 
@@ -6,11 +11,19 @@ use std::collections::HashMap;
 pub struct PreTradeData {
     pub market_sim_data: DataFrame,
     pub indicators: HashMap<TradingIndicatorKind, DataFrame>,
+    pub gap_statistics: Option<GapStatistics>,
+    pub higher_time_frame_levels: Option<HigherTimeFrameLevels>,
+    pub trades: Option<DataFrame>,
+    pub session_candles: Vec<Candle>,
 }
 
 pub struct PreTradeDataBuilder {
     market_sim_data: Option<DataFrame>,
     indicators: Option<HashMap<TradingIndicatorKind, DataFrame>>,
+    gap_statistics: Option<GapStatistics>,
+    higher_time_frame_levels: Option<HigherTimeFrameLevels>,
+    trades: Option<DataFrame>,
+    session_candles: Vec<Candle>,
 }
 
 impl PreTradeDataBuilder {
@@ -18,6 +31,10 @@ impl PreTradeDataBuilder {
         Self {
             market_sim_data: None,
             indicators: None,
+            gap_statistics: None,
+            higher_time_frame_levels: None,
+            trades: None,
+            session_candles: Vec::new(),
         }
     }
 
@@ -35,10 +52,42 @@ impl PreTradeDataBuilder {
         }
     }
 
+    pub fn with_gap_statistics(self, gap_statistics: Option<GapStatistics>) -> Self {
+        Self {
+            gap_statistics,
+            ..self
+        }
+    }
+
+    pub fn with_higher_time_frame_levels(
+        self,
+        higher_time_frame_levels: Option<HigherTimeFrameLevels>,
+    ) -> Self {
+        Self {
+            higher_time_frame_levels,
+            ..self
+        }
+    }
+
+    pub fn with_trades(self, trades: Option<DataFrame>) -> Self {
+        Self { trades, ..self }
+    }
+
+    pub fn with_session_candles(self, session_candles: Vec<Candle>) -> Self {
+        Self {
+            session_candles,
+            ..self
+        }
+    }
+
     pub fn build(self) -> PreTradeData {
         PreTradeData {
             market_sim_data: self.market_sim_data.unwrap(),
             indicators: self.indicators.unwrap(),
+            gap_statistics: self.gap_statistics,
+            higher_time_frame_levels: self.higher_time_frame_levels,
+            trades: self.trades,
+            session_candles: self.session_candles,
         }
     }
 }
@@ -18,6 +18,10 @@ impl TimeFrameSnapshot {
         self.weekday.unwrap()
     }
 
+    pub fn get_weekday_as_int_optional(&self) -> Option<i64> {
+        self.weekday
+    }
+
     pub fn get_hour(&self) -> i64 {
         self.hour.unwrap()
     }
@@ -1,5 +1,5 @@
 use crate::enums::bot::TimeFrameKind;
-use chrono::{Datelike, NaiveDateTime, Timelike};
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Timelike, Weekday};
 use polars::prelude::{BooleanChunked, IntoSeries, Series};
 use std::fmt;
 
@@ -46,7 +46,7 @@ pub trait InInterval {
 ///     end_h: 23,
 /// };
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TimeInterval {
     pub start_day: chrono::Weekday,
     pub start_h: u32,
@@ -71,6 +71,42 @@ impl InInterval for TimeInterval {
 }
 
 impl TimeInterval {
+    /// Returns the **UTC** timestamp in **milliseconds** when the session of the given timestamp
+    /// ends. The session starts at the last `start_h` (daily) or `start_day:start_h` (weekly) before
+    /// the timestamp and ends at the first `end_h` or `end_day:end_h` after its start. Hence,
+    /// sessions may span midnight, e.g. `22h-6h`, or the weekend, e.g. `Sun-Fri`. Returns `None`
+    /// if the timestamp is not before the end of its session.
+    pub fn session_end_ts(
+        &self,
+        utc_ts_in_milliseconds: i64,
+        time_frame: &TimeFrameKind,
+    ) -> Option<i64> {
+        let ts = DateTime::from_timestamp_millis(utc_ts_in_milliseconds)?.naive_utc();
+        let (session_days, days_since_start_day, days_until_end_day) = match time_frame {
+            TimeFrameKind::Daily => (1, 0, 0),
+            TimeFrameKind::Weekly => (
+                7,
+                days_between(self.start_day, ts.weekday()),
+                days_between(self.start_day, self.end_day),
+            ),
+        };
+        let session_days = Duration::days(session_days);
+
+        let mut session_start = (ts.date() - Duration::days(days_since_start_day))
+            .and_hms_opt(self.start_h, 0, 0)?;
+        if session_start > ts {
+            session_start -= session_days;
+        }
+        let mut session_end = (session_start.date() + Duration::days(days_until_end_day))
+            .and_hms_opt(self.end_h, 0, 0)?;
+        if session_end <= session_start {
+            session_end += session_days;
+        }
+        let session_end = session_end.and_utc().timestamp_millis();
+
+        (session_end > utc_ts_in_milliseconds).then_some(session_end)
+    }
+
     fn in_weekly_time_interval(&self, utc_ts_in_milliseconds: i64) -> bool {
         let ts = NaiveDateTime::from_timestamp_opt(utc_ts_in_milliseconds / 1000, 0).unwrap();
         let weekend = ts.weekday() == chrono::Weekday::Sat || ts.weekday() == chrono::Weekday::Sun;
@@ -100,6 +136,12 @@ impl fmt::Display for TimeInterval {
     }
 }
 
+/// Returns the number of days from `from` to the next `to`, i.e. `0` if both are equal.
+fn days_between(from: Weekday, to: Weekday) -> i64 {
+    let days = to.num_days_from_monday() as i64 - from.num_days_from_monday() as i64;
+    days.rem_euclid(7)
+}
+
 pub fn timestamp_in_milli_to_string(ts: i64) -> String {
     NaiveDateTime::from_timestamp_opt(ts / 1000, 0)
         .unwrap()
@@ -161,6 +203,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_session_end_ts() {
+        let time_interval = TimeInterval {
+            start_day: chrono::Weekday::Mon,
+            start_h: 1,
+            end_day: chrono::Weekday::Fri,
+            end_h: 23,
+        };
+        // Wednesday, 2022-03-02 10:00:00 UTC
+        let ts = 1646215200000;
+
+        // Wednesday, 2022-03-02 23:00:00 UTC
+        let daily = time_interval.session_end_ts(ts, &TimeFrameKind::Daily);
+        assert_eq!(Some(1646262000000), daily);
+
+        // Friday, 2022-03-04 23:00:00 UTC
+        let weekly = time_interval.session_end_ts(ts, &TimeFrameKind::Weekly);
+        assert_eq!(Some(1646434800000), weekly);
+
+        assert_eq!(None, time_interval.session_end_ts(1646262000000, &TimeFrameKind::Daily));
+    }
+
+    #[test]
+    fn test_session_end_ts_of_sunday_entry() {
+        let time_interval = TimeInterval {
+            start_day: chrono::Weekday::Sun,
+            start_h: 22,
+            end_day: chrono::Weekday::Fri,
+            end_h: 21,
+        };
+        // Sunday, 2022-03-06 23:00:00 UTC, which is in the ISO week before the session end
+        let ts = 1646607600000;
+
+        // Friday, 2022-03-11 21:00:00 UTC
+        let weekly = time_interval.session_end_ts(ts, &TimeFrameKind::Weekly);
+        assert_eq!(Some(1647032400000), weekly);
+
+        // Wednesday, 2022-03-09 10:00:00 UTC
+        let weekly = time_interval.session_end_ts(1646820000000, &TimeFrameKind::Weekly);
+        assert_eq!(Some(1647032400000), weekly);
+    }
+
+    #[test]
+    fn test_session_end_ts_of_overnight_session() {
+        let time_interval = TimeInterval {
+            start_day: chrono::Weekday::Mon,
+            start_h: 22,
+            end_day: chrono::Weekday::Fri,
+            end_h: 6,
+        };
+        // Thursday, 2022-03-03 06:00:00 UTC
+        let session_end = Some(1646287200000);
+
+        // Wednesday, 2022-03-02 23:00:00 UTC
+        let daily = time_interval.session_end_ts(1646262000000, &TimeFrameKind::Daily);
+        assert_eq!(session_end, daily);
+
+        // Thursday, 2022-03-03 03:00:00 UTC
+        let daily = time_interval.session_end_ts(1646276400000, &TimeFrameKind::Daily);
+        assert_eq!(session_end, daily);
+
+        // Thursday, 2022-03-03 10:00:00 UTC is after the session end
+        assert_eq!(None, time_interval.session_end_ts(1646301600000, &TimeFrameKind::Daily));
+    }
+
     /// This unit test checks for the DataFrame
     ///
     /// ```
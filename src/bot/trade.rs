@@ -1,4 +1,7 @@
-use crate::enums::trade_and_pre_trade::TradeDirectionKind;
+use crate::{
+    converter::fixed_point::price_difference_to_ticks,
+    enums::trade_and_pre_trade::TradeDirectionKind, strategy::MaxHoldingPeriod,
+};
 
 #[derive(Debug, Clone)]
 pub struct Trade {
@@ -6,6 +9,7 @@ pub struct Trade {
     pub stop_loss: Option<f64>,
     pub take_profit: Option<f64>,
     pub trade_kind: TradeDirectionKind,
+    pub max_holding_period: Option<MaxHoldingPeriod>,
 }
 
 impl Trade {
@@ -17,6 +21,16 @@ impl Trade {
             TradeDirectionKind::None => 0.0,
         }
     }
+
+    /// Returns the profit of exiting at `exit_px` in ticks of size `tick_size`.
+    pub fn profit_in_ticks(&self, exit_px: f64, tick_size: f64) -> f64 {
+        let entry_px = self.entry_price;
+        match self.trade_kind {
+            TradeDirectionKind::Short => price_difference_to_ticks(exit_px, entry_px, tick_size),
+            TradeDirectionKind::Long => price_difference_to_ticks(entry_px, exit_px, tick_size),
+            TradeDirectionKind::None => 0.0,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -31,6 +45,7 @@ mod test {
             stop_loss: Some(-1.0),
             take_profit: Some(-1.0),
             trade_kind: TradeDirectionKind::Long,
+            max_holding_period: None,
         };
 
         assert_eq!(1.0, trade_long.profit(101.0));
@@ -42,10 +57,31 @@ mod test {
             stop_loss: Some(-1.0),
             take_profit: Some(-1.0),
             trade_kind: TradeDirectionKind::Short,
+            max_holding_period: None,
         };
 
         assert_eq!(-1.0, trade_short.profit(101.0));
         assert_eq!(0.0, trade_short.profit(100.0));
         assert_eq!(1.0, trade_short.profit(99.0));
     }
+
+    #[test]
+    fn test_profit_in_ticks() {
+        let trade_long = Trade {
+            entry_price: 100.0,
+            stop_loss: None,
+            take_profit: None,
+            trade_kind: TradeDirectionKind::Long,
+            max_holding_period: None,
+        };
+        assert_eq!(4.0, trade_long.profit_in_ticks(101.0, 0.25));
+        assert_eq!(-4.0, trade_long.profit_in_ticks(99.0, 0.25));
+
+        let trade_short = Trade {
+            trade_kind: TradeDirectionKind::Short,
+            ..trade_long
+        };
+        assert_eq!(-4.0, trade_short.profit_in_ticks(101.0, 0.25));
+        assert_eq!(4.0, trade_short.profit_in_ticks(99.0, 0.25));
+    }
 }
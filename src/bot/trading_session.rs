@@ -12,22 +12,40 @@ use crate::{
     },
     chapaty,
     cloud_api::{
-        cloud_storage_wrapper::CloudStorageClientBuilder,
+        cloud_storage_wrapper::{CloudStorageClient, CloudStorageClientBuilder},
         file_name_resolver::FileNameResolver,
-        path_finder::{PathFinder, PathFinderBuilder},
+        file_path_with_fallback::FilePathWithFallback,
+        path_finder::{fingerprint, PathFinder, PathFinderBuilder},
     },
     enums::{
-        bot::TimeFrameKind, error::ChapatyErrorKind, indicator::TradingIndicatorKind,
+        bot::{ExecutionModeKind, TimeFrameKind},
+        data::HdbSourceDirKind,
+        error::ChapatyErrorKind,
+        indicator::TradingIndicatorKind,
         markets::MarketKind,
-    }, MarketSimulationDataKind,
+        trade_and_pre_trade::PreTradeDataKind,
+    },
+    instrumentation,
+    notification::{trade_signals_from_pnl_report, TradeSignal},
+    pnl::pnl_report::{pnl_report_from_segments, pnl_report_segments},
+    trading_indicator::{
+        candle_pattern::Candle,
+        gap::{gap_statistics, GapStatistics},
+        higher_time_frame_levels::{
+            is_higher_time_frame_level, HigherTimeFrameLevels, PeriodLevels,
+        },
+    },
+    DataProviderColumnKind,
+    MarketSimulationDataKind,
 };
-use polars::prelude::DataFrame;
+use polars::prelude::{ChunkAgg, DataFrame};
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use regex::Regex;
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
 };
-use tokio::task::JoinHandle;
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
 
 #[derive(Clone)]
 pub struct TradingSession {
@@ -38,10 +56,15 @@ pub struct TradingSession {
     pub data: ExecutionData,
     pub market_sim_data_kind: MarketSimulationDataKind,
     pub cache_computations: bool,
+    pub signal_sender: Option<UnboundedSender<TradeSignal>>,
 }
 
 impl TradingSession {
     pub async fn compute_pnl_report(self) -> DataFrame {
+        if self.bot.incremental_backtest {
+            return self.compute_pnl_report_incrementally().await;
+        }
+
         let (tx, rx) = tokio::sync::oneshot::channel();
         rayon::spawn(move || {
             let _ = tx.send(self.run_backtesting());
@@ -56,16 +79,104 @@ impl TradingSession {
         }
     }
 
+    /// Only computes the calendar weeks that are not part of the cached pnl report segments of a
+    /// previous run. The latest cached calendar week is always recomputed, since its data might
+    /// have been incomplete. Afterwards, the merged segments are cached again, if the bot caches
+    /// its computations.
+    async fn compute_pnl_report_incrementally(self) -> DataFrame {
+        let cloud_storage_client = self.pnl_report_segments_client();
+        let mut segments = cloud_storage_client
+            .download_cached_df_map()
+            .await
+            .unwrap_or_default();
+        let latest_cached_week = segments.keys().max_by_key(|s| s.get_calendar_week_as_int());
+        if let Some(snapshot) = latest_cached_week.copied() {
+            segments.remove(&snapshot);
+        }
+        let cached_weeks: HashSet<_> = segments
+            .keys()
+            .map(|s| s.get_calendar_week_as_int())
+            .collect();
+
+        let cache_computations = self.cache_computations;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        rayon::spawn(move || {
+            let _ = tx.send(self.compute_daily_pnl_data_rows(&cached_weeks));
+        });
+        segments.extend(pnl_report_segments(rx.await.unwrap()));
+
+        if cache_computations {
+            cloud_storage_client.upload_df_map_to_cache(&segments).await;
+        }
+        pnl_report_from_segments(&segments)
+    }
+
+    fn pnl_report_segments_client(&self) -> CloudStorageClient {
+        let file_name = format!(
+            "{}_pnl_report_segments_{:016x}",
+            self.bot.name,
+            self.pnl_report_fingerprint()
+        );
+        let abs_file_path =
+            build_path_finder(&self.bot, self.market, self.year).get_absolute_file_path(file_name);
+        let fallback = Regex::new(&regex::escape(&abs_file_path)).unwrap();
+        let file_path_with_fallback = FilePathWithFallback::new(abs_file_path, fallback);
+
+        CloudStorageClientBuilder::new(self.bot.clone())
+            .with_simulation_data(self.bot.market_simulation_data.into())
+            .with_market(self.market)
+            .with_year(self.year)
+            .with_file_path_with_fallback(file_path_with_fallback)
+            .build()
+    }
+
+    /// Fingerprint of every setting that changes the pnl report, but is not part of the path of
+    /// the cached data, such that changing any of them never reuses stale pnl report segments.
+    fn pnl_report_fingerprint(&self) -> u64 {
+        let bot = &self.bot;
+        let config = format!(
+            "{}|{:?}|{:?}|{:?}|{:?}|{}|{:?}|{:?}|{}",
+            bot.strategy.get_parameters(),
+            bot.market_simulation_data,
+            bot.get_instrument_override(&self.market),
+            bot.capital,
+            bot.execution_mode,
+            bot.flat_at_session_end,
+            bot.poc_selection_rule,
+            bot.value_area_rule,
+            bot.value_area_percent,
+        );
+        fingerprint(config.bytes())
+    }
+
     fn run_backtesting_daily(&self) -> DataFrame {
-        let pnl_report_data_rows: Vec<_> = (1..=52_i64)
+        self.compute_daily_pnl_data_rows(&HashSet::new())
+            .into_iter()
+            .collect()
+    }
+
+    fn compute_daily_pnl_data_rows(&self, skipped_weeks: &HashSet<i64>) -> Vec<PnLReportDataRow> {
+        let gap_statistics = self.gap_statistics();
+        let higher_time_frame_levels = self.higher_time_frame_levels();
+        (1..=52_i64)
             .into_par_iter()
+            .filter(|cw| !skipped_weeks.contains(cw))
             .flat_map(|cw| (1..=7).into_par_iter().map(move |wd| (cw, wd)))
             .map(|(cw, wd)| build_time_frame_snapshot(cw, Some(wd), None, None))
-            .filter_map(|snapshot| self.get_daily_backtesting_batch_data(snapshot).ok())
-            .map(|batch| self.compute_pnl_data_row(batch))
-            .collect();
-
-        pnl_report_data_rows.into_iter().collect()
+            .filter_map(|snapshot| {
+                let levels = higher_time_frame_levels
+                    .as_ref()
+                    .and_then(|levels| levels.get(&snapshot).copied());
+                self.get_daily_backtesting_batch_data(
+                    snapshot,
+                    gap_statistics.get(&snapshot),
+                    levels,
+                )
+                .ok()
+            })
+            .inspect(|_| instrumentation::record_time_frame_snapshot())
+            .filter_map(|batch| self.compute_pnl_data_row(batch))
+            .collect()
     }
 
     fn run_backtesting_weekly(&self) -> DataFrame {
@@ -75,33 +186,117 @@ impl TradingSession {
     fn get_daily_backtesting_batch_data(
         &self,
         snapshot: TimeFrameSnapshot,
+        gap_statistics: Option<&GapStatistics>,
+        higher_time_frame_levels: Option<HigherTimeFrameLevels>,
     ) -> Result<BacktestingBatchData, ChapatyErrorKind> {
         Ok(BacktestingBatchData {
             time_frame_snapshot: snapshot,
             market_sim_data: self.get_market_sim_data_data(&snapshot)?,
-            pre_trade_data: self.get_pre_trade_data(&snapshot)?,
+            pre_trade_data: self.get_pre_trade_data(
+                &snapshot,
+                gap_statistics.copied(),
+                higher_time_frame_levels,
+            )?,
+            trades: self.get_trades(&snapshot),
         })
     }
 
+    fn get_trades(&self, snapshot: &TimeFrameSnapshot) -> Option<DataFrame> {
+        self.data
+            .trades
+            .as_ref()
+            .and_then(|trades| trades.get(snapshot))
+            .cloned()
+    }
+
     fn get_pre_trade_data(
         &self,
         snapshot: &TimeFrameSnapshot,
+        gap_statistics: Option<GapStatistics>,
+        higher_time_frame_levels: Option<HigherTimeFrameLevels>,
     ) -> Result<PreTradeData, ChapatyErrorKind> {
-        let mut builder = PreTradeDataBuilder::new();
+        let prior_session = prior_session_snapshot(snapshot);
+
+        Ok(PreTradeDataBuilder::new()
+            .with_market_sim_data(self.get_market_sim_data_data(&prior_session)?)
+            .with_indicators(self.get_trading_indicator(&prior_session)?)
+            .with_gap_statistics(gap_statistics)
+            .with_higher_time_frame_levels(higher_time_frame_levels)
+            .with_trades(self.get_trades(&prior_session))
+            .with_session_candles(self.session_candles(&prior_session))
+            .build())
+    }
 
-        builder = if is_on_monday(snapshot) {
-            let last_friday = snapshot.last_friday();
-            builder
-                .with_market_sim_data(self.get_market_sim_data_data(&last_friday)?)
-                .with_indicators(self.get_trading_indicator(&last_friday)?)
-        } else {
-            let yesterday = snapshot.shift_back_by_n_weekdays(1);
-            builder
-                .with_market_sim_data(self.get_market_sim_data_data(&yesterday)?)
-                .with_indicators(self.get_trading_indicator(&yesterday)?)
+    /// Aggregates the prior session and the session before into one candle each, in
+    /// chronological order. Session candles are only computed if the strategy requires a candle
+    /// pattern.
+    fn session_candles(&self, prior_session: &TimeFrameSnapshot) -> Vec<Candle> {
+        let market_values = self.bot.strategy.get_required_pre_trade_vales().market_values;
+        let requires_session_candles = market_values
+            .iter()
+            .any(|value| matches!(value, PreTradeDataKind::CandlePattern(_)));
+        if !requires_session_candles {
+            return Vec::new();
+        }
+
+        [prior_session_snapshot(prior_session), *prior_session]
+            .iter()
+            .filter_map(|snapshot| self.data.market_sim_data.get(snapshot))
+            .filter_map(Candle::from_session)
+            .collect()
+    }
+
+    /// Computes the gap of every session in chronological order, see `gap::gap_statistics`. Gap
+    /// statistics are only computed if the strategy requires them.
+    fn gap_statistics(&self) -> HashMap<TimeFrameSnapshot, GapStatistics> {
+        let market_values = self.bot.strategy.get_required_pre_trade_vales().market_values;
+        let requires_gap_statistics = market_values.iter().any(|value| {
+            matches!(
+                value,
+                PreTradeDataKind::Gap | PreTradeDataKind::GapFillProbability
+            )
+        });
+        if !requires_gap_statistics {
+            return HashMap::new();
+        }
+
+        gap_statistics(&self.data.market_sim_data)
+    }
+
+    /// Computes the higher time frame levels of every session from the candles of the whole year,
+    /// such that they do not depend on the time frame of the backtest. Returns `None` if the
+    /// strategy does not require any higher time frame level.
+    fn higher_time_frame_levels(
+        &self,
+    ) -> Option<HashMap<TimeFrameSnapshot, HigherTimeFrameLevels>> {
+        let market_values = self.bot.strategy.get_required_pre_trade_vales().market_values;
+        if !market_values.iter().any(is_higher_time_frame_level) {
+            return None;
+        }
+
+        let Some(candles) = self
+            .data
+            .market_sim_data
+            .values()
+            .cloned()
+            .reduce(|acc, df| acc.vstack(&df).unwrap())
+        else {
+            return Some(HashMap::new());
         };
+        let period_levels = PeriodLevels::new(&candles);
+        let ots = DataProviderColumnKind::OpenTime.to_string();
 
-        Ok(builder.build())
+        let higher_time_frame_levels = self
+            .data
+            .market_sim_data
+            .iter()
+            .filter_map(|(snapshot, df)| {
+                let session_start = df.column(&ots).unwrap().i64().unwrap().min()?;
+                Some((*snapshot, period_levels.higher_time_frame_levels(session_start)))
+            })
+            .collect();
+
+        Some(higher_time_frame_levels)
     }
 
     fn get_market_sim_data_data(
@@ -138,7 +333,21 @@ impl TradingSession {
             .collect()
     }
 
-    fn compute_pnl_data_row(&self, batch: BacktestingBatchData) -> PnLReportDataRow {
+    /// Returns `None` if the session lacks a pre-trade value required by the strategy. The trade
+    /// signal of the session is sent to the `signal_sender`, if there is one.
+    fn compute_pnl_data_row(&self, batch: BacktestingBatchData) -> Option<PnLReportDataRow> {
+        let row = self.build_pnl_data_row(batch)?;
+        if let Some(signal_sender) = &self.signal_sender {
+            for signal in trade_signals_from_pnl_report(&row.clone().into()) {
+                // The dispatcher only stops receiving after a sink failed, which is reported
+                // once the backtest unit has finished
+                let _ = signal_sender.send(signal);
+            }
+        }
+        Some(row)
+    }
+
+    fn build_pnl_data_row(&self, batch: BacktestingBatchData) -> Option<PnLReportDataRow> {
         PnLReportDataRowCalculatorBuilder::new()
             .with_data_provider(self.bot.data_provider.clone())
             .with_market_sim_data(batch.market_sim_data)
@@ -148,10 +357,45 @@ impl TradingSession {
             .with_market(self.market)
             .with_time_frame_snapshot(batch.time_frame_snapshot)
             .with_market_sim_data_kind(self.market_sim_data_kind)
+            .with_trades(batch.trades)
+            .with_poc_selection_rule(self.bot.poc_selection_rule)
+            .with_value_area_rule(self.bot.value_area_rule)
+            .with_value_area_percent(self.bot.value_area_percent)
+            .with_time_interval(self.bot.time_interval)
+            .with_pre_trade_values_cache(self.bot.pre_trade_values_cache.clone())
+            .with_time_frame(self.bot.time_frame)
+            .with_flat_at_session_end(self.bot.flat_at_session_end)
+            .with_instrument_override(self.bot.get_instrument_override(&self.market))
+            .with_excluded_time_ranges(self.bot.get_excluded_time_ranges(&self.market).to_vec())
+            .with_period(self.bot.period)
+            .with_holidays(self.bot.holidays.clone())
             .build_and_compute()
     }
 }
 
+/// Trades are required to replay them, or to compute the order flow imbalance of the prior
+/// session.
+fn requires_trades(bot: &Bot) -> bool {
+    let market_values = bot.strategy.get_required_pre_trade_vales().market_values;
+    bot.execution_mode == ExecutionModeKind::TradeReplay
+        || market_values
+            .iter()
+            .any(|value| matches!(value, PreTradeDataKind::OrderFlowImbalance { .. }))
+}
+
+fn build_path_finder(bot: &Bot, market: MarketKind, year: u32) -> PathFinder {
+    PathFinderBuilder::new()
+        .with_data_provider(bot.data_provider.get_name())
+        .with_strategy_name(bot.strategy.get_name())
+        .with_market(market)
+        .with_year(year)
+        .with_time_interval(bot.time_interval)
+        .with_time_frame(bot.time_frame.to_string())
+        .with_excluded_time_ranges(bot.get_excluded_time_ranges(&market).to_vec())
+        .with_period(bot.period, bot.holidays.clone())
+        .build()
+}
+
 fn build_time_frame_snapshot(
     cw: i64,
     wd: Option<i64>,
@@ -166,6 +410,15 @@ fn build_time_frame_snapshot(
     builder.build()
 }
 
+/// The prior session of monday is last friday, otherwise it is the day before.
+fn prior_session_snapshot(snapshot: &TimeFrameSnapshot) -> TimeFrameSnapshot {
+    if is_on_monday(snapshot) {
+        snapshot.last_friday()
+    } else {
+        snapshot.shift_back_by_n_weekdays(1)
+    }
+}
+
 fn is_on_monday(snapshot: &TimeFrameSnapshot) -> bool {
     snapshot.get_weekday_as_int() == 1
 }
@@ -186,6 +439,7 @@ pub struct TradingSessionBuilder {
     year: Option<u32>,
     market_sim_data_kind: Option<MarketSimulationDataKind>,
     cache_computations: bool,
+    signal_sender: Option<UnboundedSender<TradeSignal>>,
 }
 
 impl TradingSessionBuilder {
@@ -197,6 +451,7 @@ impl TradingSessionBuilder {
             year: None,
     market_sim_data_kind: None,
             cache_computations: false,
+            signal_sender: None,
         }
     }
 
@@ -242,6 +497,13 @@ impl TradingSessionBuilder {
         }
     }
 
+    pub fn with_signal_sender(self, signal_sender: Option<UnboundedSender<TradeSignal>>) -> Self {
+        Self {
+            signal_sender,
+            ..self
+        }
+    }
+
     pub async fn build(self) -> TradingSession {
         let data = self.populate_trading_session_data().await;
         TradingSession {
@@ -252,6 +514,7 @@ impl TradingSessionBuilder {
             market_sim_data_kind: self.market_sim_data_kind.unwrap(),
             data,
             cache_computations: self.cache_computations,
+            signal_sender: self.signal_sender,
         }
     }
 
@@ -260,21 +523,20 @@ impl TradingSessionBuilder {
         let market = self.market.unwrap();
         let year = self.year.unwrap();
 
-        let path_finder = PathFinderBuilder::new()
-            .with_data_provider(bot.data_provider.get_name())
-            .with_strategy_name(bot.strategy.get_name())
-            .with_market(market)
-            .with_year(year)
-            .with_time_interval(bot.time_interval)
-            .with_time_frame(bot.time_frame.to_string())
-            .build();
+        let path_finder = build_path_finder(&bot, market, year);
 
         let trading_indicators_df_map = self.get_trading_indicators_df_map(&path_finder).await;
         let market_simulation_df_map = self.get_market_simulation_df_map(&path_finder).await;
+        let trades_df_map = if requires_trades(&bot) {
+            Some(self.get_trades_df_map(&path_finder).await)
+        } else {
+            None
+        };
 
         ExecutionData {
             market_sim_data: market_simulation_df_map,
             trading_indicators: trading_indicators_df_map,
+            trades: trades_df_map,
         }
     }
 
@@ -350,6 +612,20 @@ impl TradingSessionBuilder {
         cloud_storage_client.download_df_map().await
     }
 
+    async fn get_trades_df_map(&self, path_finder: &PathFinder) -> chapaty::types::DataFrameMap {
+        let trades = HdbSourceDirKind::AggTrades;
+        let file_name = FileNameResolver::new(trades).get_filename();
+        let file_path_with_fallback = path_finder.get_file_path_with_fallback(file_name, &trades);
+
+        let cloud_storage_client = self
+            .initalize_cloud_storage_client_builder()
+            .with_simulation_data(trades)
+            .with_file_path_with_fallback(file_path_with_fallback)
+            .build();
+
+        cloud_storage_client.download_df_map().await
+    }
+
     fn initalize_cloud_storage_client_builder(&self) -> CloudStorageClientBuilder {
         let bot = self.bot.clone().unwrap();
         CloudStorageClientBuilder::new(bot.clone())
@@ -569,7 +845,7 @@ mod test {
         // Test Evaluation "trading_indicators"
         let trading_indicators = execution_data.trading_indicators;
         let path = "ppp/btcusdt/2022/Mon1h0m-Fri23h0m/1d/target_vol-aggTrades.json";
-        let target = download_df_map(path.to_string()).await;
+        let target = download_df_map(path.to_string()).await.unwrap();
         assert_eq!(
             &target,
             trading_indicators
@@ -656,7 +932,7 @@ mod test {
         // Test Evaluation "trading_indicators"
         let trading_indicators = execution_data.trading_indicators;
         let path = "ppp/btcusdt/2022/Mon1h0m-Fri23h0m/1w/target_vol-aggTrades.json";
-        let target = download_df_map(path.to_string()).await;
+        let target = download_df_map(path.to_string()).await.unwrap();
         assert_eq!(
             &target,
             trading_indicators
@@ -5,7 +5,8 @@ use crate::{
     chapaty,
     converter::any_value::AnyValueConverter,
     enums::{
-        bot::TimeFrameKind,
+        bot::{PeriodKind, TimeFrameKind},
+        column_names::DataProviderColumnKind,
         data::HdbSourceDirKind,
         indicator::{PriceHistogramKind, TradingIndicatorKind},
         markets::MarketKind,
@@ -57,12 +58,23 @@ impl Transformer {
         let time_interval = self.bot.time_interval;
         let time_frame = self.bot.time_frame;
 
-        let ts_col = &self.get_ts_col();
-        let mut ldf = lazy_df.add_cw_col(&ts_col).add_weekday_col(&ts_col);
+        let ts_col = self.get_ts_col();
+        let mut ldf = match self.bot.period {
+            PeriodKind::CalendarDay => lazy_df.add_cw_col(&ts_col).add_weekday_col(&ts_col),
+            PeriodKind::BusinessDay => {
+                lazy_df.add_business_day_cw_and_weekday_cols(&ts_col, &self.bot.holidays)
+            }
+        };
 
         if time_interval.is_some() {
             ldf = ldf.filter_ts_col_by_time_interval(&ts_col, time_interval.unwrap(), time_frame);
         }
+        let excluded_time_ranges = self.bot.get_excluded_time_ranges(&self.market);
+        ldf = ldf.drop_rows_in_excluded_time_ranges(
+            &ts_col,
+            &self.get_close_ts_col(),
+            excluded_time_ranges,
+        );
 
         let dfs = ldf
             .collect()
@@ -77,12 +89,18 @@ impl Transformer {
         let time_interval = self.bot.time_interval;
         let time_frame = self.bot.time_frame;
 
-        let ts_col = &self.get_ts_col();
+        let ts_col = self.get_ts_col();
         let mut ldf = lazy_df.add_cw_col(&ts_col);
 
         if time_interval.is_some() {
             ldf = ldf.filter_ts_col_by_time_interval(&ts_col, time_interval.unwrap(), time_frame);
         }
+        let excluded_time_ranges = self.bot.get_excluded_time_ranges(&self.market);
+        ldf = ldf.drop_rows_in_excluded_time_ranges(
+            &ts_col,
+            &self.get_close_ts_col(),
+            excluded_time_ranges,
+        );
 
         let dfs: Vec<DataFrame> = ldf.collect().unwrap().partition_by(["cw"], true).unwrap();
 
@@ -159,6 +177,17 @@ impl Transformer {
             |v| v.data.get_ts_col_as_str(),
         )
     }
+
+    /// Candles span from their open to their close timestamp, whereas trades have a single
+    /// timestamp.
+    fn get_close_ts_col(&self) -> String {
+        let ts_col = self.get_ts_col();
+        if ts_col == DataProviderColumnKind::OpenTime.to_string() {
+            DataProviderColumnKind::CloseTime.to_string()
+        } else {
+            ts_col
+        }
+    }
 }
 
 fn handle_daily_update(df: DataFrame, df_map: &mut chapaty::types::DataFrameMap) {
@@ -1,4 +1,5 @@
 pub mod pnl_report_data_row_calculator;
+pub mod pre_trade_values_cache;
 pub mod pre_trade_values_calculator;
 pub mod trade_pnl_calculator;
 pub mod trade_values_calculator;
@@ -1,4 +1,5 @@
 use super::{
+    pre_trade_values_cache::{PreTradeValuesCache, PreTradeWindow},
     pre_trade_values_calculator::{
         PreTradeValuesCalculatorBuilder, RequiredPreTradeValuesWithData,
     },
@@ -6,13 +7,23 @@ use super::{
     trade_values_calculator::{TradeValuesCalculatorBuilder, TradeValuesWithData},
 };
 use crate::{
-    bot::{pre_trade_data::PreTradeData, time_frame_snapshot::TimeFrameSnapshot, trade::Trade},
+    bot::{
+        excluded_time_range::ExcludedTimeRange, instrument_override::InstrumentOverride,
+        pre_trade_data::PreTradeData, time_frame_snapshot::TimeFrameSnapshot,
+        time_interval::TimeInterval, trade::Trade,
+    },
     data_provider::DataProvider,
-    enums::markets::MarketKind,
+    enums::{
+        bot::{PeriodKind, TimeFrameKind},
+        indicator::{PocSelectionRule, ValueAreaRule},
+        markets::MarketKind,
+    },
     lazy_frame_operations::trait_extensions::MyLazyFrameOperations,
     strategy::{Strategy, TradeRequestObject},
+    trading_indicator::price_histogram::DEFAULT_VALUE_AREA_PERCENT,
     MarketSimulationDataKind,
 };
+use chrono::NaiveDate;
 use polars::prelude::{DataFrame, IntoLazy, LazyFrame};
 use std::sync::Arc;
 
@@ -24,6 +35,7 @@ pub struct PnLReportDataRow {
     pub time_frame_snapshot: TimeFrameSnapshot,
     pub trade: Trade,
     pub trade_pnl: Option<TradePnL>,
+    pub instrument_override: Option<InstrumentOverride>,
 }
 
 pub struct PnLReportDataRowCalculator {
@@ -35,6 +47,18 @@ pub struct PnLReportDataRowCalculator {
     pub year: u32,
     pub time_frame_snapshot: TimeFrameSnapshot,
     pub market_sim_data_kind: MarketSimulationDataKind,
+    pub trades: Option<DataFrame>,
+    pub poc_selection_rule: Option<PocSelectionRule>,
+    pub value_area_rule: ValueAreaRule,
+    pub value_area_percent: f64,
+    pub time_interval: Option<TimeInterval>,
+    pub pre_trade_values_cache: Option<PreTradeValuesCache>,
+    pub time_frame: TimeFrameKind,
+    pub flat_at_session_end: bool,
+    pub instrument_override: Option<InstrumentOverride>,
+    pub excluded_time_ranges: Vec<ExcludedTimeRange>,
+    pub period: PeriodKind,
+    pub holidays: Vec<NaiveDate>,
 }
 
 #[derive(Clone)]
@@ -44,12 +68,15 @@ pub struct TradeAndPreTradeValuesWithData {
 }
 
 impl PnLReportDataRowCalculator {
-    pub fn compute(&self) -> PnLReportDataRow {
-        let data = self.get_trade_and_pre_trade_values_with_data();
-        match data.trade {
+    /// Returns `None` if a pre-trade value required by the strategy is not available for the
+    /// session, see `PreTradeValuesCalculator::compute`.
+    pub fn compute(&self) -> Option<PnLReportDataRow> {
+        let data = self.get_trade_and_pre_trade_values_with_data()?;
+        let row = match data.trade {
             Some(_) => self.handle_trade(data),
             None => self.handle_no_entry(data),
-        }
+        };
+        Some(row)
     }
 
     fn handle_no_entry(&self, values: TradeAndPreTradeValuesWithData) -> PnLReportDataRow {
@@ -61,6 +88,7 @@ impl PnLReportDataRowCalculator {
             time_frame_snapshot: self.time_frame_snapshot,
             trade: self.strategy.get_trade(&request),
             trade_pnl: None,
+            instrument_override: self.instrument_override,
         }
     }
 
@@ -72,6 +100,8 @@ impl PnLReportDataRowCalculator {
             .with_entry_ts(entry_ts)
             .with_trade(trade.clone())
             .with_market_sim_data_since_entry(self.market_sim_data_since_entry_ts(entry_ts))
+            .with_trades_since_entry(self.trades_since_entry_ts(entry_ts))
+            .with_session_end_ts(self.session_end_ts(entry_ts))
             .with_trade_and_pre_trade_values(values)
             .build_and_compute();
 
@@ -81,7 +111,8 @@ impl PnLReportDataRowCalculator {
             strategy_name: self.strategy.get_name(),
             time_frame_snapshot: self.time_frame_snapshot,
             trade,
-            trade_pnl: Some(trade_pnl),
+            trade_pnl,
+            instrument_override: self.instrument_override,
         }
     }
 
@@ -94,6 +125,7 @@ impl PnLReportDataRowCalculator {
             pre_trade_values: values.pre_trade.clone(),
             initial_balance,
             market: self.market,
+            instrument_override: self.instrument_override,
         }
     }
 
@@ -104,7 +136,36 @@ impl PnLReportDataRowCalculator {
             .drop_rows_before_entry_ts(entry_ts)
     }
 
-    fn compute_pre_trade_values(&self) -> RequiredPreTradeValuesWithData {
+    fn session_end_ts(&self, entry_ts: i64) -> Option<i64> {
+        if !self.flat_at_session_end {
+            return None;
+        }
+        self.time_interval
+            .and_then(|time_interval| time_interval.session_end_ts(entry_ts, &self.time_frame))
+    }
+
+    fn trades_since_entry_ts(&self, entry_ts: i64) -> Option<LazyFrame> {
+        self.trades
+            .clone()
+            .map(|trades| trades.lazy().drop_trades_before_ts(entry_ts))
+    }
+
+    pub fn pre_trade_window(&self) -> PreTradeWindow {
+        PreTradeWindow {
+            data_provider: self.data_provider.get_name(),
+            market_sim_data_kind: self.market_sim_data_kind,
+            time_interval: self.time_interval,
+            market: self.market,
+            year: self.year,
+            time_frame_snapshot: self.time_frame_snapshot,
+            excluded_time_ranges: self.excluded_time_ranges.clone(),
+            period: self.period,
+            holidays: self.holidays.clone(),
+            instrument_override: self.instrument_override,
+        }
+    }
+
+    fn compute_pre_trade_values(&self) -> Option<RequiredPreTradeValuesWithData> {
         let calculator_builder: PreTradeValuesCalculatorBuilder = self.into();
         calculator_builder
             .with_required_pre_trade_values(self.strategy.get_required_pre_trade_vales())
@@ -121,10 +182,10 @@ impl PnLReportDataRowCalculator {
             .build_and_compute()
     }
 
-    fn get_trade_and_pre_trade_values_with_data(&self) -> TradeAndPreTradeValuesWithData {
-        let pre_trade = self.compute_pre_trade_values();
+    fn get_trade_and_pre_trade_values_with_data(&self) -> Option<TradeAndPreTradeValuesWithData> {
+        let pre_trade = self.compute_pre_trade_values()?;
         let trade = self.compute_trade_values(&pre_trade);
-        TradeAndPreTradeValuesWithData { trade, pre_trade }
+        Some(TradeAndPreTradeValuesWithData { trade, pre_trade })
     }
 }
 
@@ -137,6 +198,18 @@ pub struct PnLReportDataRowCalculatorBuilder {
     year: Option<u32>,
     time_frame_snapshot: Option<TimeFrameSnapshot>,
     market_sim_data_kind: Option<MarketSimulationDataKind>,
+    trades: Option<DataFrame>,
+    poc_selection_rule: Option<PocSelectionRule>,
+    value_area_rule: ValueAreaRule,
+    value_area_percent: f64,
+    time_interval: Option<TimeInterval>,
+    pre_trade_values_cache: Option<PreTradeValuesCache>,
+    time_frame: TimeFrameKind,
+    flat_at_session_end: bool,
+    instrument_override: Option<InstrumentOverride>,
+    excluded_time_ranges: Vec<ExcludedTimeRange>,
+    period: PeriodKind,
+    holidays: Vec<NaiveDate>,
 }
 
 impl PnLReportDataRowCalculatorBuilder {
@@ -150,6 +223,18 @@ impl PnLReportDataRowCalculatorBuilder {
             year: None,
             time_frame_snapshot: None,
             market_sim_data_kind: None,
+            trades: None,
+            poc_selection_rule: None,
+            value_area_rule: ValueAreaRule::default(),
+            value_area_percent: DEFAULT_VALUE_AREA_PERCENT,
+            time_interval: None,
+            pre_trade_values_cache: None,
+            time_frame: TimeFrameKind::Daily,
+            flat_at_session_end: false,
+            instrument_override: None,
+            excluded_time_ranges: Vec::new(),
+            period: PeriodKind::default(),
+            holidays: Vec::new(),
         }
     }
 
@@ -209,6 +294,84 @@ impl PnLReportDataRowCalculatorBuilder {
         }
     }
 
+    pub fn with_trades(self, trades: Option<DataFrame>) -> Self {
+        Self { trades, ..self }
+    }
+
+    pub fn with_poc_selection_rule(self, poc_selection_rule: Option<PocSelectionRule>) -> Self {
+        Self {
+            poc_selection_rule,
+            ..self
+        }
+    }
+
+    pub fn with_value_area_rule(self, value_area_rule: ValueAreaRule) -> Self {
+        Self {
+            value_area_rule,
+            ..self
+        }
+    }
+
+    pub fn with_value_area_percent(self, value_area_percent: f64) -> Self {
+        Self {
+            value_area_percent,
+            ..self
+        }
+    }
+
+    pub fn with_time_interval(self, time_interval: Option<TimeInterval>) -> Self {
+        Self {
+            time_interval,
+            ..self
+        }
+    }
+
+    pub fn with_pre_trade_values_cache(
+        self,
+        pre_trade_values_cache: Option<PreTradeValuesCache>,
+    ) -> Self {
+        Self {
+            pre_trade_values_cache,
+            ..self
+        }
+    }
+
+    pub fn with_time_frame(self, time_frame: TimeFrameKind) -> Self {
+        Self { time_frame, ..self }
+    }
+
+    pub fn with_flat_at_session_end(self, flat_at_session_end: bool) -> Self {
+        Self {
+            flat_at_session_end,
+            ..self
+        }
+    }
+
+    pub fn with_instrument_override(
+        self,
+        instrument_override: Option<InstrumentOverride>,
+    ) -> Self {
+        Self {
+            instrument_override,
+            ..self
+        }
+    }
+
+    pub fn with_excluded_time_ranges(self, excluded_time_ranges: Vec<ExcludedTimeRange>) -> Self {
+        Self {
+            excluded_time_ranges,
+            ..self
+        }
+    }
+
+    pub fn with_period(self, period: PeriodKind) -> Self {
+        Self { period, ..self }
+    }
+
+    pub fn with_holidays(self, holidays: Vec<NaiveDate>) -> Self {
+        Self { holidays, ..self }
+    }
+
     pub fn build(self) -> PnLReportDataRowCalculator {
         PnLReportDataRowCalculator {
             data_provider: self.data_provider.unwrap(),
@@ -219,10 +382,22 @@ impl PnLReportDataRowCalculatorBuilder {
             year: self.year.unwrap(),
             time_frame_snapshot: self.time_frame_snapshot.unwrap(),
             market_sim_data_kind: self.market_sim_data_kind.unwrap(),
+            trades: self.trades,
+            poc_selection_rule: self.poc_selection_rule,
+            value_area_rule: self.value_area_rule,
+            value_area_percent: self.value_area_percent,
+            time_interval: self.time_interval,
+            pre_trade_values_cache: self.pre_trade_values_cache,
+            time_frame: self.time_frame,
+            flat_at_session_end: self.flat_at_session_end,
+            instrument_override: self.instrument_override,
+            excluded_time_ranges: self.excluded_time_ranges,
+            period: self.period,
+            holidays: self.holidays,
         }
     }
 
-    pub fn build_and_compute(self) -> PnLReportDataRow {
+    pub fn build_and_compute(self) -> Option<PnLReportDataRow> {
         self.build().compute()
     }
 }
@@ -0,0 +1,191 @@
+use crate::{
+    bot::{
+        excluded_time_range::ExcludedTimeRange, instrument_override::InstrumentOverride,
+        time_frame_snapshot::TimeFrameSnapshot, time_interval::TimeInterval,
+    },
+    enums::{
+        bot::PeriodKind,
+        indicator::{PocSelectionRule, TradingIndicatorKind, ValueAreaRule},
+        markets::MarketKind,
+        trade_and_pre_trade::PreTradeDataKind,
+    },
+    MarketSimulationDataKind,
+};
+use chrono::NaiveDate;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// Memoizes pre-trade values, e.g. the POC or the previous day high and low, such that bots
+/// sharing the same cache do not recompute values for the same market and time window. This is
+/// useful when several strategies are compared on the same markets.
+///
+/// # Example
+/// ```
+/// use chapaty::PreTradeValuesCache;
+///
+/// let cache = PreTradeValuesCache::new();
+/// // Pass a clone of the cache to every bot via `BotBuilder::with_pre_trade_values_cache`
+/// let shared_cache = cache.clone();
+/// assert!(cache.is_empty());
+/// ```
+#[derive(Clone, Default)]
+pub struct PreTradeValuesCache {
+    values: Arc<RwLock<HashMap<PreTradeValuesCacheKey, f64>>>,
+}
+
+/// The market data window the pre-trade values are computed from, together with every setting of
+/// the bot that changes the data in this window. Hence, bots with different settings never share
+/// cached values.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PreTradeWindow {
+    pub data_provider: String,
+    pub market_sim_data_kind: MarketSimulationDataKind,
+    pub time_interval: Option<TimeInterval>,
+    pub market: MarketKind,
+    pub year: u32,
+    pub time_frame_snapshot: TimeFrameSnapshot,
+    pub excluded_time_ranges: Vec<ExcludedTimeRange>,
+    pub period: PeriodKind,
+    pub holidays: Vec<NaiveDate>,
+    pub instrument_override: Option<InstrumentOverride>,
+}
+
+/// Pre-trade values of a price histogram depend on the rules used to resolve the POC and to
+/// expand the value area, and on the value area percent. Hence, all of them are part of the key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PreTradeValueKind {
+    Market(PreTradeDataKind),
+    Indicator {
+        indicator: TradingIndicatorKind,
+        poc_selection_rule: Option<PocSelectionRule>,
+        value_area_rule: ValueAreaRule,
+        /// The bits of the value area percent, since `f64` is not `Hash`
+        value_area_percent: u64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PreTradeValuesCacheKey {
+    pub window: PreTradeWindow,
+    pub value: PreTradeValueKind,
+}
+
+impl PreTradeValuesCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &PreTradeValuesCacheKey) -> Option<f64> {
+        self.values.read().unwrap().get(key).copied()
+    }
+
+    pub fn insert(&self, key: PreTradeValuesCacheKey, value: f64) {
+        self.values.write().unwrap().insert(key, value);
+    }
+
+    /// Returns the cached value or computes and caches it. The value is computed without holding
+    /// the lock, hence concurrent callers might compute the same value more than once.
+    pub fn get_or_compute(
+        &self,
+        key: PreTradeValuesCacheKey,
+        compute: impl FnOnce() -> f64,
+    ) -> f64 {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+
+        let value = compute();
+        self.insert(key, value);
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::time_frame_snapshot::TimeFrameSnapshotBuilder;
+
+    fn window(market: MarketKind) -> PreTradeWindow {
+        PreTradeWindow {
+            data_provider: "cme".to_string(),
+            market_sim_data_kind: MarketSimulationDataKind::Ohlc1m,
+            time_interval: None,
+            market,
+            year: 2022,
+            time_frame_snapshot: TimeFrameSnapshotBuilder::new(8).with_weekday(1).build(),
+            excluded_time_ranges: Vec::new(),
+            period: PeriodKind::CalendarDay,
+            holidays: Vec::new(),
+            instrument_override: None,
+        }
+    }
+
+    fn key(market: MarketKind) -> PreTradeValuesCacheKey {
+        PreTradeValuesCacheKey {
+            window: window(market),
+            value: PreTradeValueKind::Market(PreTradeDataKind::LastTradePrice),
+        }
+    }
+
+    #[test]
+    fn test_get_or_compute() {
+        let cache = PreTradeValuesCache::new();
+        let shared_cache = cache.clone();
+
+        assert_eq!(
+            1.0,
+            cache.get_or_compute(key(MarketKind::EurUsdFuture), || 1.0)
+        );
+        assert_eq!(
+            1.0,
+            shared_cache.get_or_compute(key(MarketKind::EurUsdFuture), || unreachable!())
+        );
+        assert_eq!(
+            2.0,
+            shared_cache.get_or_compute(key(MarketKind::GbpUsdFuture), || 2.0)
+        );
+        assert_eq!(2, cache.len());
+    }
+
+    #[test]
+    fn test_bot_settings_are_part_of_the_key() {
+        let cache = PreTradeValuesCache::new();
+        cache.insert(key(MarketKind::EurUsdFuture), 1.0);
+
+        let windows = [
+            PreTradeWindow {
+                excluded_time_ranges: vec![ExcludedTimeRange::new(0, 60_000)],
+                ..window(MarketKind::EurUsdFuture)
+            },
+            PreTradeWindow {
+                period: PeriodKind::BusinessDay,
+                ..window(MarketKind::EurUsdFuture)
+            },
+            PreTradeWindow {
+                holidays: vec![NaiveDate::from_ymd_opt(2022, 12, 26).unwrap()],
+                ..window(MarketKind::EurUsdFuture)
+            },
+            PreTradeWindow {
+                instrument_override: Some(InstrumentOverride::new(0.0001, 1.25, 0.62)),
+                ..window(MarketKind::EurUsdFuture)
+            },
+        ];
+        for window in windows {
+            let key = PreTradeValuesCacheKey {
+                window,
+                value: PreTradeValueKind::Market(PreTradeDataKind::LastTradePrice),
+            };
+            assert_eq!(None, cache.get(&key));
+        }
+    }
+}
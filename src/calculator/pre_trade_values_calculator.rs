@@ -1,16 +1,35 @@
-use super::pnl_report_data_row_calculator::PnLReportDataRowCalculator;
+use super::{
+    pnl_report_data_row_calculator::PnLReportDataRowCalculator,
+    pre_trade_values_cache::{
+        PreTradeValueKind, PreTradeValuesCache, PreTradeValuesCacheKey, PreTradeWindow,
+    },
+};
 use crate::{
-    bot::pre_trade_data::PreTradeData,
+    bot::{pre_trade_data::PreTradeData, time_interval::TimeInterval},
     converter::any_value::AnyValueConverter,
     enums::{
-        column_names::DataProviderColumnKind, indicator::TradingIndicatorKind,
+        column_names::DataProviderColumnKind,
+        bot::TimeFrameKind,
+        indicator::{
+            CandlePatternKind, DayTypeKind, ExecutionDepthKind, PivotLevelKind, PivotPointKind,
+            PocSelectionRule, TradingIndicatorKind, ValueAreaRule,
+        },
         trade_and_pre_trade::PreTradeDataKind,
     },
     strategy::RequriedPreTradeValues,
-    trading_indicator::price_histogram::PriceHistogram,
+    trading_indicator::{
+        candle_pattern::is_candle_pattern,
+        day_type::classify_day_type,
+        gap::GapStatistics,
+        higher_time_frame_levels::{HigherTimeFrameLevels, Levels},
+        order_flow_imbalance::order_flow_imbalance,
+        pivot_points::pivot_point,
+        price_histogram::PriceHistogram,
+    },
     PriceHistogramKind,
 };
-use polars::prelude::{col, IntoLazy};
+use chrono::Weekday;
+use polars::prelude::{col, ChunkAgg, IntoLazy};
 use std::collections::HashMap;
 
 #[derive(Clone)]
@@ -38,6 +57,90 @@ impl RequiredPreTradeValuesWithData {
             .get(&PreTradeDataKind::LastTradePrice)
             .unwrap()
     }
+    pub fn gap(&self) -> f64 {
+        *self.market_valeus.get(&PreTradeDataKind::Gap).unwrap()
+    }
+    pub fn gap_fill_probability(&self) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::GapFillProbability)
+            .unwrap()
+    }
+    pub fn day_type(&self) -> DayTypeKind {
+        let code = *self.market_valeus.get(&PreTradeDataKind::DayType).unwrap();
+        DayTypeKind::from_repr(code as u8).unwrap()
+    }
+    pub fn candle_pattern(&self, pattern: CandlePatternKind) -> bool {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::CandlePattern(pattern))
+            .unwrap()
+            == 1.0
+    }
+    pub fn previous_week_high(&self) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::PreviousWeekHigh)
+            .unwrap()
+    }
+    pub fn previous_week_low(&self) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::PreviousWeekLow)
+            .unwrap()
+    }
+    pub fn previous_week_close(&self) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::PreviousWeekClose)
+            .unwrap()
+    }
+    pub fn previous_month_high(&self) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::PreviousMonthHigh)
+            .unwrap()
+    }
+    pub fn previous_month_low(&self) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::PreviousMonthLow)
+            .unwrap()
+    }
+    pub fn previous_month_close(&self) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::PreviousMonthClose)
+            .unwrap()
+    }
+    pub fn pivot_point(
+        &self,
+        time_frame: TimeFrameKind,
+        kind: PivotPointKind,
+        level: PivotLevelKind,
+    ) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::PivotPoint {
+                time_frame,
+                kind,
+                level,
+            })
+            .unwrap()
+    }
+    pub fn order_flow_imbalance(
+        &self,
+        window_minutes: Option<u32>,
+        execution_depth: ExecutionDepthKind,
+    ) -> f64 {
+        *self
+            .market_valeus
+            .get(&PreTradeDataKind::OrderFlowImbalance {
+                window_minutes,
+                execution_depth,
+            })
+            .unwrap()
+    }
     pub fn value_area_high(&self, ph: PriceHistogramKind) -> f64 {
         *self
             .indicator_values
@@ -61,21 +164,31 @@ impl RequiredPreTradeValuesWithData {
 pub struct PreTradeValuesCalculator {
     pre_trade_data: PreTradeData,
     required_pre_trade_values: RequriedPreTradeValues,
+    poc_selection_rule: Option<PocSelectionRule>,
+    value_area_rule: ValueAreaRule,
+    value_area_percent: f64,
+    pre_trade_values_cache: Option<PreTradeValuesCache>,
+    pre_trade_window: Option<PreTradeWindow>,
+    time_interval: Option<TimeInterval>,
+    time_frame: TimeFrameKind,
 }
 
 impl PreTradeValuesCalculator {
-    pub fn compute(&self) -> RequiredPreTradeValuesWithData {
-        RequiredPreTradeValuesWithData {
-            market_valeus: self.compute_market_values(),
+    /// Returns `None` if a required market value is not available for the session, e.g. the `Gap`
+    /// of the first session of the year, which has no prior session, or the `PreviousMonthHigh` of
+    /// a session in January. Such sessions are not backtested.
+    pub fn compute(&self) -> Option<RequiredPreTradeValuesWithData> {
+        Some(RequiredPreTradeValuesWithData {
+            market_valeus: self.compute_market_values()?,
             indicator_values: self.compute_indicator_values(),
-        }
+        })
     }
 
-    fn compute_market_values(&self) -> HashMap<PreTradeDataKind, f64> {
+    fn compute_market_values(&self) -> Option<HashMap<PreTradeDataKind, f64>> {
         self.required_pre_trade_values
             .market_values
             .iter()
-            .fold(HashMap::new(), |acc, val| {
+            .try_fold(HashMap::new(), |acc, val| {
                 self.update_market_value_map(acc, val)
             })
     }
@@ -84,23 +197,91 @@ impl PreTradeValuesCalculator {
         &self,
         mut map: HashMap<PreTradeDataKind, f64>,
         val: &PreTradeDataKind,
-    ) -> HashMap<PreTradeDataKind, f64> {
+    ) -> Option<HashMap<PreTradeDataKind, f64>> {
+        let value = PreTradeValueKind::Market(*val);
         match val {
             PreTradeDataKind::LastTradePrice => {
-                let res = self.compute_last_trade_price();
+                let res = self.cached(value, || self.compute_last_trade_price());
                 map.insert(PreTradeDataKind::LastTradePrice, res);
             }
             PreTradeDataKind::LowestTradePrice => {
-                let res = self.compute_lowest_trade_price();
+                let res = self.cached(value, || self.compute_lowest_trade_price());
                 map.insert(PreTradeDataKind::LowestTradePrice, res);
             }
             PreTradeDataKind::HighestTradePrice => {
-                let res = self.compute_highest_trade_price();
+                let res = self.cached(value, || self.compute_highest_trade_price());
                 map.insert(PreTradeDataKind::HighestTradePrice, res);
             }
+            PreTradeDataKind::Gap => {
+                map.insert(PreTradeDataKind::Gap, self.gap_statistics()?.gap.size);
+            }
+            PreTradeDataKind::GapFillProbability => {
+                let res = self.gap_statistics()?.gap_fill_probability;
+                map.insert(PreTradeDataKind::GapFillProbability, res);
+            }
+            PreTradeDataKind::DayType => {
+                let res = self.cached(value, || {
+                    classify_day_type(&self.pre_trade_data.market_sim_data) as u8 as f64
+                });
+                map.insert(PreTradeDataKind::DayType, res);
+            }
+            PreTradeDataKind::PreviousWeekHigh => {
+                map.insert(*val, self.previous_week()?.high);
+            }
+            PreTradeDataKind::PreviousWeekLow => {
+                map.insert(*val, self.previous_week()?.low);
+            }
+            PreTradeDataKind::PreviousWeekClose => {
+                map.insert(*val, self.previous_week()?.close);
+            }
+            PreTradeDataKind::PreviousMonthHigh => {
+                map.insert(*val, self.previous_month()?.high);
+            }
+            PreTradeDataKind::PreviousMonthLow => {
+                map.insert(*val, self.previous_month()?.low);
+            }
+            PreTradeDataKind::PreviousMonthClose => {
+                map.insert(*val, self.previous_month()?.close);
+            }
+            PreTradeDataKind::PivotPoint {
+                time_frame,
+                kind,
+                level,
+            } => {
+                // Daily pivot points use the prior session, weekly ones the previous calendar week
+                let previous_week = match time_frame {
+                    TimeFrameKind::Daily => None,
+                    TimeFrameKind::Weekly => Some(self.previous_week()?),
+                };
+                let res = self.cached(value, || {
+                    let levels = previous_week.unwrap_or_else(|| self.prior_session_levels());
+                    pivot_point(*kind, *level, &levels)
+                });
+                map.insert(*val, res);
+            }
+            PreTradeDataKind::CandlePattern(pattern) => {
+                let res = self.cached(value, || {
+                    let candles = &self.pre_trade_data.session_candles;
+                    if is_candle_pattern(candles, *pattern) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                });
+                map.insert(*val, res);
+            }
+            PreTradeDataKind::OrderFlowImbalance {
+                window_minutes,
+                execution_depth,
+            } => {
+                let res = self.cached(value, || {
+                    self.compute_order_flow_imbalance(*window_minutes, *execution_depth)
+                });
+                map.insert(*val, res);
+            }
         };
 
-        map
+        Some(map)
     }
 
     fn compute_indicator_values(&self) -> HashMap<TradingIndicatorKind, f64> {
@@ -122,7 +303,7 @@ impl PreTradeValuesCalculator {
                 map.insert(TradingIndicatorKind::Poc(*ph), self.get_poc(val));
             }
             TradingIndicatorKind::ValueAreaHigh(ph) | TradingIndicatorKind::ValueAreaLow(ph) => {
-                let (value_area_low, value_area_high) = self.get_value_area(val);
+                let (value_area_low, value_area_high) = self.get_value_area(val, *ph);
                 map.insert(TradingIndicatorKind::ValueAreaHigh(*ph), value_area_low);
                 map.insert(TradingIndicatorKind::ValueAreaLow(*ph), value_area_high);
             }
@@ -132,25 +313,122 @@ impl PreTradeValuesCalculator {
     }
 
     fn get_poc(&self, indicator: &TradingIndicatorKind) -> f64 {
+        self.cached(self.indicator_value_kind(*indicator), || {
+            self.get_price_histogram(indicator).poc()
+        })
+    }
+
+    fn get_value_area(
+        &self,
+        indicator: &TradingIndicatorKind,
+        ph: PriceHistogramKind,
+    ) -> (f64, f64) {
+        let mut value_area = None;
+        let mut compute = || {
+            *value_area.get_or_insert_with(|| {
+                self.get_price_histogram(indicator)
+                    .value_area(self.value_area_percent)
+            })
+        };
+        let low = self.indicator_value_kind(TradingIndicatorKind::ValueAreaLow(ph));
+        let high = self.indicator_value_kind(TradingIndicatorKind::ValueAreaHigh(ph));
+        let value_area_low = self.cached(low, || compute().0);
+        let value_area_high = self.cached(high, || compute().1);
+
+        (value_area_low, value_area_high)
+    }
+
+    fn indicator_value_kind(&self, indicator: TradingIndicatorKind) -> PreTradeValueKind {
+        PreTradeValueKind::Indicator {
+            indicator,
+            poc_selection_rule: self.poc_selection_rule,
+            value_area_rule: self.value_area_rule,
+            value_area_percent: self.value_area_percent.to_bits(),
+        }
+    }
+
+    /// Looks up the value in the `PreTradeValuesCache` if the bot shares one, otherwise computes
+    /// it.
+    fn cached(&self, value: PreTradeValueKind, compute: impl FnOnce() -> f64) -> f64 {
+        match (&self.pre_trade_values_cache, &self.pre_trade_window) {
+            (Some(cache), Some(window)) => {
+                let key = PreTradeValuesCacheKey {
+                    window: window.clone(),
+                    value,
+                };
+                cache.get_or_compute(key, compute)
+            }
+            _ => compute(),
+        }
+    }
+
+    fn get_price_histogram(&self, indicator: &TradingIndicatorKind) -> PriceHistogram {
         let df = self
             .pre_trade_data
             .indicators
             .get(&indicator)
             .unwrap()
             .clone();
-        let ph = PriceHistogram::new(df);
-        ph.poc()
+        let ph = PriceHistogram::new(df).with_value_area_rule(self.value_area_rule);
+        match self.poc_selection_rule {
+            Some(rule) => {
+                ph.with_poc_selection_rule(rule, self.pre_trade_data.market_sim_data.clone())
+            }
+            None => ph,
+        }
     }
 
-    fn get_value_area(&self, indicator: &TradingIndicatorKind) -> (f64, f64) {
-        let df = self
-            .pre_trade_data
-            .indicators
-            .get(&indicator)
+    fn gap_statistics(&self) -> Option<GapStatistics> {
+        self.pre_trade_data.gap_statistics
+    }
+
+    fn higher_time_frame_levels(&self) -> Option<HigherTimeFrameLevels> {
+        self.pre_trade_data.higher_time_frame_levels
+    }
+
+    fn previous_week(&self) -> Option<Levels> {
+        self.higher_time_frame_levels()?.previous_week
+    }
+
+    fn previous_month(&self) -> Option<Levels> {
+        self.higher_time_frame_levels()?.previous_month
+    }
+
+    fn prior_session_levels(&self) -> Levels {
+        Levels {
+            high: self.compute_highest_trade_price(),
+            low: self.compute_lowest_trade_price(),
+            close: self.compute_last_trade_price(),
+        }
+    }
+
+    /// The trades of the prior session are always loaded if the strategy requires the order flow
+    /// imbalance. The window is anchored at the end of the prior session, which is the end of the
+    /// UTC day or calendar week if the bot has no `TimeInterval`.
+    fn compute_order_flow_imbalance(
+        &self,
+        window_minutes: Option<u32>,
+        execution_depth: ExecutionDepthKind,
+    ) -> f64 {
+        let trades = self.pre_trade_data.trades.as_ref().unwrap();
+        let ts = DataProviderColumnKind::Timestamp.to_string();
+        let time_interval = self.time_interval.unwrap_or(TimeInterval {
+            start_day: Weekday::Mon,
+            start_h: 0,
+            end_day: Weekday::Mon,
+            end_h: 0,
+        });
+        let session_end_ts = trades
+            .column(&ts)
             .unwrap()
-            .clone();
-        let ph = PriceHistogram::new(df);
-        ph.value_area(0.63)
+            .i64()
+            .unwrap()
+            .min()
+            .and_then(|first_ts| time_interval.session_end_ts(first_ts, &self.time_frame));
+
+        session_end_ts.map_or(0.0, |session_end_ts| {
+            order_flow_imbalance(trades, window_minutes, execution_depth, session_end_ts)
+        })
     }
 
     fn compute_last_trade_price(&self) -> f64 {
@@ -191,6 +469,13 @@ pub struct PreTradeValuesCalculatorBuilder {
     pre_trade_data: Option<PreTradeData>,
 
     required_pre_trade_values: Option<RequriedPreTradeValues>,
+    poc_selection_rule: Option<PocSelectionRule>,
+    value_area_rule: ValueAreaRule,
+    value_area_percent: f64,
+    pre_trade_values_cache: Option<PreTradeValuesCache>,
+    pre_trade_window: Option<PreTradeWindow>,
+    time_interval: Option<TimeInterval>,
+    time_frame: TimeFrameKind,
 }
 
 impl From<&PnLReportDataRowCalculator> for PreTradeValuesCalculatorBuilder {
@@ -198,6 +483,13 @@ impl From<&PnLReportDataRowCalculator> for PreTradeValuesCalculatorBuilder {
         Self {
             pre_trade_data: Some(value.pre_trade_data.clone()),
             required_pre_trade_values: None,
+            poc_selection_rule: value.poc_selection_rule,
+            value_area_rule: value.value_area_rule,
+            value_area_percent: value.value_area_percent,
+            pre_trade_values_cache: value.pre_trade_values_cache.clone(),
+            pre_trade_window: Some(value.pre_trade_window()),
+            time_interval: value.time_interval,
+            time_frame: value.time_frame,
         }
     }
 }
@@ -217,10 +509,17 @@ impl PreTradeValuesCalculatorBuilder {
         PreTradeValuesCalculator {
             pre_trade_data: self.pre_trade_data.unwrap(),
             required_pre_trade_values: self.required_pre_trade_values.unwrap(),
+            poc_selection_rule: self.poc_selection_rule,
+            value_area_rule: self.value_area_rule,
+            value_area_percent: self.value_area_percent,
+            pre_trade_values_cache: self.pre_trade_values_cache,
+            pre_trade_window: self.pre_trade_window,
+            time_interval: self.time_interval,
+            time_frame: self.time_frame,
         }
     }
 
-    pub fn build_and_compute(self) -> RequiredPreTradeValuesWithData {
+    pub fn build_and_compute(self) -> Option<RequiredPreTradeValuesWithData> {
         self.build().compute()
     }
 }
@@ -231,7 +530,9 @@ mod test {
     use crate::{
         calculator::pre_trade_values_calculator::PreTradeData,
         cloud_api::api_for_unit_tests::download_df,
+        trading_indicator::price_histogram::DEFAULT_VALUE_AREA_PERCENT,
     };
+    use polars::prelude::DataFrame;
     use std::collections::HashMap;
 
     #[tokio::test]
@@ -245,6 +546,10 @@ mod test {
         let pre_trade_data = PreTradeData {
             market_sim_data: df,
             indicators: HashMap::new(),
+            gap_statistics: None,
+            higher_time_frame_levels: None,
+            trades: None,
+            session_candles: Vec::new(),
         };
 
         let required_pre_trade_values = RequriedPreTradeValues {
@@ -255,6 +560,13 @@ mod test {
         let caclulator = PreTradeValuesCalculator {
             pre_trade_data,
             required_pre_trade_values,
+            poc_selection_rule: None,
+            value_area_rule: ValueAreaRule::default(),
+            value_area_percent: DEFAULT_VALUE_AREA_PERCENT,
+            pre_trade_values_cache: None,
+            pre_trade_window: None,
+            time_interval: None,
+            time_frame: TimeFrameKind::Daily,
         };
 
         assert_eq!(43_578.87, caclulator.compute_last_trade_price());
@@ -271,6 +583,10 @@ mod test {
         let pre_trade_data = PreTradeData {
             market_sim_data: df,
             indicators: HashMap::new(),
+            gap_statistics: None,
+            higher_time_frame_levels: None,
+            trades: None,
+            session_candles: Vec::new(),
         };
 
         let required_pre_trade_values = RequriedPreTradeValues {
@@ -281,6 +597,13 @@ mod test {
         let caclulator = PreTradeValuesCalculator {
             pre_trade_data,
             required_pre_trade_values,
+            poc_selection_rule: None,
+            value_area_rule: ValueAreaRule::default(),
+            value_area_percent: DEFAULT_VALUE_AREA_PERCENT,
+            pre_trade_values_cache: None,
+            pre_trade_window: None,
+            time_interval: None,
+            time_frame: TimeFrameKind::Daily,
         };
 
         assert_eq!(37_934.89, caclulator.compute_lowest_trade_price());
@@ -297,6 +620,10 @@ mod test {
         let pre_trade_data = PreTradeData {
             market_sim_data: df,
             indicators: HashMap::new(),
+            gap_statistics: None,
+            higher_time_frame_levels: None,
+            trades: None,
+            session_candles: Vec::new(),
         };
 
         let required_pre_trade_values = RequriedPreTradeValues {
@@ -307,8 +634,57 @@ mod test {
         let caclulator = PreTradeValuesCalculator {
             pre_trade_data,
             required_pre_trade_values,
+            poc_selection_rule: None,
+            value_area_rule: ValueAreaRule::default(),
+            value_area_percent: DEFAULT_VALUE_AREA_PERCENT,
+            pre_trade_values_cache: None,
+            pre_trade_window: None,
+            time_interval: None,
+            time_frame: TimeFrameKind::Daily,
         };
 
         assert_eq!(44_225.84, caclulator.compute_highest_trade_price());
     }
+
+    #[test]
+    fn test_compute_without_required_values() {
+        let calculator = |value: PreTradeDataKind| PreTradeValuesCalculator {
+            pre_trade_data: PreTradeData {
+                market_sim_data: DataFrame::default(),
+                indicators: HashMap::new(),
+                gap_statistics: None,
+                higher_time_frame_levels: Some(HigherTimeFrameLevels {
+                    previous_week: None,
+                    previous_month: None,
+                }),
+                trades: None,
+                session_candles: Vec::new(),
+            },
+            required_pre_trade_values: RequriedPreTradeValues {
+                market_values: vec![value],
+                trading_indicators: Vec::new(),
+            },
+            poc_selection_rule: None,
+            value_area_rule: ValueAreaRule::default(),
+            value_area_percent: DEFAULT_VALUE_AREA_PERCENT,
+            pre_trade_values_cache: None,
+            pre_trade_window: None,
+            time_interval: None,
+            time_frame: TimeFrameKind::Daily,
+        };
+
+        assert!(calculator(PreTradeDataKind::Gap).compute().is_none());
+        assert!(calculator(PreTradeDataKind::PreviousWeekHigh)
+            .compute()
+            .is_none());
+        assert!(calculator(PreTradeDataKind::PreviousMonthClose)
+            .compute()
+            .is_none());
+        let weekly_pivot_point = PreTradeDataKind::PivotPoint {
+            time_frame: TimeFrameKind::Weekly,
+            kind: PivotPointKind::Classic,
+            level: PivotLevelKind::Pp,
+        };
+        assert!(calculator(weekly_pivot_point).compute().is_none());
+    }
 }
@@ -1,5 +1,10 @@
 use super::pnl_report_data_row_calculator::TradeAndPreTradeValuesWithData;
-use crate::{bot::trade::Trade, lazy_frame_operations::trait_extensions::MyLazyFrameOperations};
+use crate::{
+    bot::trade::Trade,
+    enums::trade_and_pre_trade::{TerminationReason, TradeDirectionKind},
+    lazy_frame_operations::trait_extensions::MyLazyFrameOperations,
+    strategy::MaxHoldingPeriod,
+};
 use polars::prelude::LazyFrame;
 use std::convert::identity;
 
@@ -7,7 +12,9 @@ pub struct TradePnLCalculator {
     entry_ts: i64,
     trade: Trade,
     market_sim_data_since_entry: LazyFrame,
+    trades_since_entry: Option<LazyFrame>,
     trade_and_pre_trade_values: TradeAndPreTradeValuesWithData,
+    session_end_ts: Option<i64>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -16,6 +23,8 @@ pub struct TradePnL {
     pub stop_loss: Option<PnL>,
     pub take_profit: Option<PnL>,
     pub timeout: Option<PnL>,
+    /// Reason why the trade was closed, if neither stop loss nor take profit were reached
+    pub timeout_reason: TerminationReason,
 }
 
 impl TradePnL {
@@ -36,6 +45,16 @@ impl TradePnL {
         }
     }
 
+    pub fn termination_reason(&self) -> TerminationReason {
+        if self.is_trade_timeout() {
+            self.timeout_reason
+        } else if self.is_regular_trade_winner() {
+            TerminationReason::TakeProfit
+        } else {
+            TerminationReason::StopLoss
+        }
+    }
+
     fn handle_regular_trade_exit(&self) -> f64 {
         if self.is_regular_trade_loser() {
             self.stop_loss.clone().unwrap().price
@@ -96,6 +115,18 @@ pub struct PnL {
 }
 
 impl PnL {
+    /// Keeps the order open, if it was filled at or after the given timestamp.
+    fn open_if_filled_after(self, ts: i64) -> Self {
+        match self.ts {
+            Some(fill_ts) if fill_ts >= ts => Self {
+                ts: None,
+                profit: None,
+                ..self
+            },
+            _ => self,
+        }
+    }
+
     fn or_none(self) -> Option<Self> {
         if is_order_open(self.ts) {
             None
@@ -106,28 +137,114 @@ impl PnL {
 }
 
 impl TradePnLCalculator {
-    pub fn compute(&self) -> TradePnL {
-        let stop_loss = self.try_handle_exit(self.trade.stop_loss);
-        let take_profit = self.try_handle_exit(self.trade.take_profit);
+    /// Returns `None` if the trades are replayed and no trade reaches the entry price, i.e. the
+    /// trade has no entry. Candle and replay results are never mixed.
+    pub fn compute(&self) -> Option<TradePnL> {
+        let entry_fill_ts = self.entry_fill_ts();
+        if self.trades_since_entry.is_some() && entry_fill_ts.is_none() {
+            return None;
+        }
+        let trade_entry_ts = entry_fill_ts.unwrap_or(self.entry_ts);
+        let forced_exit_ts = self.forced_exit_ts(trade_entry_ts);
+        let mut stop_loss = self.try_handle_exit(self.trade.stop_loss, entry_fill_ts);
+        let mut take_profit = self.try_handle_exit(self.trade.take_profit, entry_fill_ts);
+        if let Some((ts, _)) = forced_exit_ts {
+            stop_loss = stop_loss.map(|pnl| pnl.open_if_filled_after(ts));
+            take_profit = take_profit.map(|pnl| pnl.open_if_filled_after(ts));
+        }
+
         let mut timeout = None;
+        let mut timeout_reason = TerminationReason::Timeout;
         if is_sl_and_tp_valid(&stop_loss, &take_profit)
             && is_limit_order_open(stop_loss.clone().unwrap(), take_profit.clone().unwrap())
         {
-            timeout = Some(self.handle_timeout())
+            let forced_exit = forced_exit_ts.and_then(|(ts, reason)| {
+                self.handle_forced_exit(ts, reason)
+                    .map(|pnl| (pnl, reason))
+            });
+            timeout = match forced_exit {
+                Some((pnl, reason)) => {
+                    timeout_reason = reason;
+                    Some(pnl)
+                }
+                None => Some(self.handle_timeout()),
+            };
         }
-        TradePnL {
-            trade_entry_ts: self.entry_ts,
+        Some(TradePnL {
+            trade_entry_ts,
             stop_loss: stop_loss.and_then(PnL::or_none),
             take_profit: take_profit.and_then(PnL::or_none),
             timeout,
+            timeout_reason,
+        })
+    }
+
+    /// Returns the earliest timestamp from which on the trade is force-closed, together with the
+    /// reason.
+    fn forced_exit_ts(&self, trade_entry_ts: i64) -> Option<(i64, TerminationReason)> {
+        let max_holding_period_ts = self
+            .max_holding_period_ts(trade_entry_ts)
+            .map(|ts| (ts, TerminationReason::MaxHoldingPeriod));
+        let session_end_ts = self
+            .session_end_ts
+            .map(|ts| (ts, TerminationReason::SessionEnd));
+
+        [max_holding_period_ts, session_end_ts]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(ts, _)| *ts)
+    }
+
+    fn max_holding_period_ts(&self, trade_entry_ts: i64) -> Option<i64> {
+        match self.trade.max_holding_period? {
+            MaxHoldingPeriod::Candles(n) => self
+                .market_sim_data_since_entry
+                .clone()
+                .find_open_time_of_nth_candle(n),
+            MaxHoldingPeriod::Minutes(minutes) => Some(trade_entry_ts + minutes * 60_000),
+        }
+    }
+
+    /// Closes the trade at the close of the last candle before `ts`. If the trades are replayed,
+    /// the trade is closed at the last trade before `ts` instead, i.e. never after `ts`. If the
+    /// market simulation data ends before the maximum holding period is over, the trade is not
+    /// force-closed. At the end of a session, the trade is always closed.
+    fn handle_forced_exit(&self, ts: i64, reason: TerminationReason) -> Option<PnL> {
+        let market_sim_data = self.market_sim_data_since_entry.clone();
+        let has_data_after_ts = market_sim_data
+            .clone()
+            .drop_rows_before_entry_ts(ts)
+            .find_open_time_of_nth_candle(0)
+            .is_some();
+        if reason == TerminationReason::MaxHoldingPeriod && !has_data_after_ts {
+            return None;
         }
+
+        let (exit_ts, exit_px) = match &self.trades_since_entry {
+            Some(trades) => trades.clone().find_last_trade_before_ts(ts)?,
+            None => market_sim_data.find_last_candle_before_ts(ts)?,
+        };
+        Some(PnL {
+            price: exit_px,
+            ts: Some(exit_ts),
+            profit: Some(self.trade.profit(exit_px)),
+        })
     }
 
-    fn try_handle_exit(&self, exit_px: Option<f64>) -> Option<PnL> {
-        exit_px.and_then(|px| Some(self.then_handle_exit(px)))
+    /// If raw trades are available, the entry is filled by the first trade that reaches the entry
+    /// price. A long entry is reached from above, a short entry from below.
+    fn entry_fill_ts(&self) -> Option<i64> {
+        let is_long = self.trade.trade_kind == TradeDirectionKind::Long;
+        self.trades_since_entry.clone().and_then(|trades| {
+            trades.find_trade_timestamp_when_price_reached(self.trade.entry_price, is_long)
+        })
     }
-    fn then_handle_exit(&self, exit_px: f64) -> PnL {
-        let ts = self.trade_exit_ts(exit_px);
+
+    fn try_handle_exit(&self, exit_px: Option<f64>, entry_fill_ts: Option<i64>) -> Option<PnL> {
+        exit_px.map(|px| self.then_handle_exit(px, entry_fill_ts))
+    }
+    fn then_handle_exit(&self, exit_px: f64, entry_fill_ts: Option<i64>) -> PnL {
+        let ts = self.trade_exit_ts(exit_px, entry_fill_ts);
         let profit = ts.and_then(|_| Some(self.trade.profit(exit_px)));
 
         PnL {
@@ -148,10 +265,19 @@ impl TradePnLCalculator {
         }
     }
 
-    fn trade_exit_ts(&self, exit_px: f64) -> Option<i64> {
-        self.market_sim_data_since_entry
-            .clone()
-            .find_timestamp_when_price_reached(exit_px)
+    /// Replays the raw trades after the entry fill to find the exact exit. Without raw trades, the
+    /// exit is resolved on the candles of the market simulation data.
+    fn trade_exit_ts(&self, exit_px: f64, entry_fill_ts: Option<i64>) -> Option<i64> {
+        match (&self.trades_since_entry, entry_fill_ts) {
+            (Some(trades), Some(entry_fill_ts)) => trades
+                .clone()
+                .drop_trades_before_ts(entry_fill_ts)
+                .find_trade_timestamp_when_price_reached(exit_px, exit_px < self.trade.entry_price),
+            _ => self
+                .market_sim_data_since_entry
+                .clone()
+                .find_timestamp_when_price_reached(exit_px),
+        }
     }
 }
 
@@ -170,11 +296,14 @@ fn is_order_open(timestamp: Option<i64>) -> bool {
     timestamp.is_none()
 }
 
+#[derive(Clone)]
 pub struct TradePnLCalculatorBuilder {
     entry_ts: Option<i64>,
     trade: Option<Trade>,
     market_sim_data_since_entry: Option<LazyFrame>,
+    trades_since_entry: Option<LazyFrame>,
     trade_and_pre_trade_values: Option<TradeAndPreTradeValuesWithData>,
+    session_end_ts: Option<i64>,
 }
 
 impl TradePnLCalculatorBuilder {
@@ -183,7 +312,9 @@ impl TradePnLCalculatorBuilder {
             entry_ts: None,
             trade: None,
             market_sim_data_since_entry: None,
+            trades_since_entry: None,
             trade_and_pre_trade_values: None,
+            session_end_ts: None,
         }
     }
 
@@ -194,6 +325,24 @@ impl TradePnLCalculatorBuilder {
         }
     }
 
+    /// Sets the raw trades since the entry candle. If set, the trades are replayed to resolve the
+    /// exact entry fill and exit instead of using the candles of the market simulation data.
+    pub fn with_trades_since_entry(self, trades_since_entry: Option<LazyFrame>) -> Self {
+        Self {
+            trades_since_entry,
+            ..self
+        }
+    }
+
+    /// Sets the timestamp when the trading session ends. Open trades are closed at the end of the
+    /// session.
+    pub fn with_session_end_ts(self, session_end_ts: Option<i64>) -> Self {
+        Self {
+            session_end_ts,
+            ..self
+        }
+    }
+
     pub fn with_entry_ts(self, ts: i64) -> Self {
         Self {
             entry_ts: Some(ts),
@@ -223,11 +372,13 @@ impl TradePnLCalculatorBuilder {
             entry_ts: self.entry_ts.clone().unwrap(),
             trade: self.trade.clone().unwrap(),
             market_sim_data_since_entry: self.market_sim_data_since_entry.clone().unwrap(),
+            trades_since_entry: self.trades_since_entry.clone(),
             trade_and_pre_trade_values: self.trade_and_pre_trade_values.clone().unwrap(),
+            session_end_ts: self.session_end_ts,
         }
     }
 
-    pub fn build_and_compute(self) -> TradePnL {
+    pub fn build_and_compute(self) -> Option<TradePnL> {
         self.build().compute()
     }
 }
@@ -335,7 +486,10 @@ mod test {
             trade_and_pre_trade::{PreTradeDataKind, TradeDataKind, TradeDirectionKind},
         },
     };
-    use polars::prelude::IntoLazy;
+    use polars::{
+        df,
+        prelude::{IntoLazy, NamedFrom},
+    };
     use std::collections::HashMap;
 
     fn set_up_pre_trade_indicator_values_ppp_long() -> HashMap<TradingIndicatorKind, f64> {
@@ -397,6 +551,7 @@ mod test {
             stop_loss: Some(stop_loss),
             take_profit: Some(take_profit),
             trade_kind: TradeDirectionKind::Long,
+            max_holding_period: None,
         }
     }
 
@@ -418,7 +573,9 @@ mod test {
             entry_ts,
             trade: set_up_trade_ppp_long(entry_price, stop_loss, take_profit),
             market_sim_data_since_entry,
+            trades_since_entry: None,
             trade_and_pre_trade_values: set_up_trade_and_pre_trade_values_ppp_long(entry_ts),
+            session_end_ts: None,
         }
     }
 
@@ -438,6 +595,7 @@ mod test {
             stop_loss,
             take_profit,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -457,6 +615,7 @@ mod test {
             stop_loss,
             take_profit,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -477,6 +636,7 @@ mod test {
             stop_loss,
             take_profit: None,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -491,6 +651,7 @@ mod test {
             stop_loss: None,
             take_profit,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -505,6 +666,7 @@ mod test {
             stop_loss: None,
             take_profit: None,
             timeout,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -550,7 +712,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_long_case_1a(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
 
         let price_upon_entry = poc - 648.44; // triggered
         let prev_close = 39_424.14 - 100.0; // triggered
@@ -563,7 +725,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_long_case_1b(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 1
 
         // BEGIN: Case 2
@@ -578,7 +740,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_long_case_2(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 2
 
         // BEGIN: Case 3
@@ -593,7 +755,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_long_case_3(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 3
 
         // BEGIN: Case 4
@@ -608,7 +770,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_long_case_4(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 4
     }
 
@@ -681,6 +843,7 @@ mod test {
             stop_loss: Some(stop_loss),
             take_profit: Some(take_profit),
             trade_kind: TradeDirectionKind::Short,
+            max_holding_period: None,
         }
     }
 
@@ -704,7 +867,9 @@ mod test {
             entry_ts,
             trade: set_up_trade_ppp_short(entry_price, stop_loss, take_profit),
             market_sim_data_since_entry,
+            trades_since_entry: None,
             trade_and_pre_trade_values: set_up_trade_and_pre_trade_values_ppp_short(entry_ts),
+            session_end_ts: None,
         }
     }
 
@@ -724,6 +889,7 @@ mod test {
             stop_loss,
             take_profit,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -743,6 +909,7 @@ mod test {
             stop_loss,
             take_profit,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -763,6 +930,7 @@ mod test {
             stop_loss,
             take_profit: None,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -777,6 +945,7 @@ mod test {
             stop_loss: None,
             take_profit,
             timeout: None,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -791,6 +960,7 @@ mod test {
             stop_loss: None,
             take_profit: None,
             timeout,
+            timeout_reason: TerminationReason::Timeout,
         }
     }
 
@@ -836,7 +1006,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_short_case_1a(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
 
         let price_upon_entry = poc + 494.06; // triggered
         let prev_close = 39_004.73 + 100.0; // triggered
@@ -849,7 +1019,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_short_case_1b(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 1
 
         // BEGIN: Case 2
@@ -865,7 +1035,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_short_case_2(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 2
 
         // BEGIN: Case 3
@@ -880,7 +1050,7 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_short_case_3(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 3
 
         // BEGIN: Case 4
@@ -895,7 +1065,214 @@ mod test {
             market_sim_data_since_entry.clone(),
         );
         let target = set_up_target_trade_pnl_ppp_short_case_4(entry_ts);
-        assert_eq!(target, calculator.compute());
+        assert_eq!(Some(target), calculator.compute());
         // END: Case 4
     }
+
+    #[test]
+    fn test_trade_pnl_with_trade_replay() {
+        let market_sim_data_since_entry = df!(
+            "ots" => &[0_i64],
+            "high" => &[103.0],
+            "low" => &[98.0],
+        )
+        .unwrap()
+        .lazy();
+        let trades = df!(
+            "ts" => &[1_i64, 2, 3, 4],
+            "px" => &[101.0, 100.0, 102.5, 98.0],
+        )
+        .unwrap()
+        .lazy();
+        let calculator = TradePnLCalculatorBuilder::new()
+            .with_entry_ts(0)
+            .with_trade(set_up_trade_ppp_long(100.0, 99.0, 102.0))
+            .with_market_sim_data_since_entry(market_sim_data_since_entry.clone())
+            .with_trade_and_pre_trade_values(set_up_trade_and_pre_trade_values_ppp_long(0));
+
+        // Stop loss and take profit are reached in the same candle => conservative loser
+        let candle_pnl = calculator
+            .clone()
+            .with_trades_since_entry(None)
+            .build_and_compute()
+            .unwrap();
+        assert_eq!(0, candle_pnl.trade_entry_ts);
+        assert_eq!(99.0, candle_pnl.exit_price());
+
+        // Replaying the trades shows that the take profit was reached first
+        let replay_pnl = calculator
+            .with_trades_since_entry(Some(trades))
+            .build_and_compute()
+            .unwrap();
+        let target = TradePnL {
+            trade_entry_ts: 2,
+            stop_loss: Some(PnL {
+                price: 99.0,
+                ts: Some(4),
+                profit: Some(-1.0),
+            }),
+            take_profit: Some(PnL {
+                price: 102.0,
+                ts: Some(3),
+                profit: Some(2.0),
+            }),
+            timeout: None,
+            timeout_reason: TerminationReason::Timeout,
+        };
+        assert_eq!(target, replay_pnl);
+        assert_eq!(102.0, replay_pnl.exit_price());
+    }
+
+    #[test]
+    fn test_trade_pnl_with_trade_replay_without_entry_fill() {
+        let market_sim_data_since_entry = df!(
+            "ots" => &[0_i64],
+            "high" => &[103.0],
+            "low" => &[98.0],
+        )
+        .unwrap()
+        .lazy();
+        let trades = df!(
+            "ts" => &[1_i64, 2],
+            "px" => &[101.0, 102.5],
+        )
+        .unwrap()
+        .lazy();
+
+        // The candle reaches the entry, but no trade does => no entry instead of candle fallback
+        let replay_pnl = TradePnLCalculatorBuilder::new()
+            .with_entry_ts(0)
+            .with_trade(set_up_trade_ppp_long(100.0, 99.0, 102.0))
+            .with_market_sim_data_since_entry(market_sim_data_since_entry)
+            .with_trades_since_entry(Some(trades))
+            .with_trade_and_pre_trade_values(set_up_trade_and_pre_trade_values_ppp_long(0))
+            .build_and_compute();
+        assert_eq!(None, replay_pnl);
+    }
+
+    #[test]
+    fn test_trade_pnl_with_max_holding_period() {
+        let market_sim_data_since_entry = df!(
+            "ots" => &[0_i64, 60_000, 120_000, 180_000],
+            "high" => &[100.5, 101.0, 101.5, 103.0],
+            "low" => &[99.5, 100.0, 100.5, 101.0],
+            "close" => &[100.0, 100.5, 101.2, 102.5],
+        )
+        .unwrap()
+        .lazy();
+        let trade = Trade {
+            max_holding_period: Some(MaxHoldingPeriod::Candles(3)),
+            ..set_up_trade_ppp_long(100.0, 99.0, 102.0)
+        };
+        let calculator = TradePnLCalculatorBuilder::new()
+            .with_entry_ts(0)
+            .with_trade(trade.clone())
+            .with_market_sim_data_since_entry(market_sim_data_since_entry)
+            .with_trade_and_pre_trade_values(set_up_trade_and_pre_trade_values_ppp_long(0));
+
+        // The take profit is reached in the fourth candle, after the holding period of 3 candles
+        let target = TradePnL {
+            trade_entry_ts: 0,
+            stop_loss: None,
+            take_profit: None,
+            timeout: Some(PnL {
+                price: 101.2,
+                ts: Some(120_000),
+                profit: Some(101.2 - 100.0),
+            }),
+            timeout_reason: TerminationReason::MaxHoldingPeriod,
+        };
+        let pnl = calculator.clone().build_and_compute().unwrap();
+        assert_eq!(target, pnl);
+        assert_eq!(TerminationReason::MaxHoldingPeriod, pnl.termination_reason());
+
+        // A holding period of 4 minutes includes the fourth candle
+        let trade = Trade {
+            max_holding_period: Some(MaxHoldingPeriod::Minutes(4)),
+            ..trade
+        };
+        let pnl = calculator.with_trade(trade).build_and_compute().unwrap();
+        assert_eq!(102.0, pnl.exit_price());
+        assert_eq!(TerminationReason::TakeProfit, pnl.termination_reason());
+    }
+
+    #[test]
+    fn test_trade_pnl_with_max_holding_period_and_trade_replay() {
+        let market_sim_data_since_entry = df!(
+            "ots" => &[0_i64, 60_000, 120_000],
+            "high" => &[100.5, 101.5, 103.0],
+            "low" => &[99.5, 100.0, 101.0],
+            "close" => &[100.0, 101.2, 102.5],
+        )
+        .unwrap()
+        .lazy();
+        let trades = df!(
+            "ts" => &[10_000_i64, 40_000, 70_000, 110_000, 130_000],
+            "px" => &[100.0, 100.6, 100.8, 101.4, 102.0],
+        )
+        .unwrap()
+        .lazy();
+        let trade = Trade {
+            max_holding_period: Some(MaxHoldingPeriod::Minutes(1)),
+            ..set_up_trade_ppp_long(100.0, 99.0, 102.0)
+        };
+
+        // The holding period ends at 70_000 within the second candle, hence the trade is closed
+        // at the last trade before the limit instead of the close of the second candle
+        let pnl = TradePnLCalculatorBuilder::new()
+            .with_entry_ts(0)
+            .with_trade(trade)
+            .with_market_sim_data_since_entry(market_sim_data_since_entry)
+            .with_trades_since_entry(Some(trades))
+            .with_trade_and_pre_trade_values(set_up_trade_and_pre_trade_values_ppp_long(0))
+            .build_and_compute()
+            .unwrap();
+        let target = PnL {
+            price: 100.6,
+            ts: Some(40_000),
+            profit: Some(100.6 - 100.0),
+        };
+        assert_eq!(Some(target), pnl.timeout);
+        assert_eq!(TerminationReason::MaxHoldingPeriod, pnl.termination_reason());
+    }
+
+    #[test]
+    fn test_trade_pnl_with_session_end() {
+        let market_sim_data_since_entry = df!(
+            "ots" => &[0_i64, 60_000, 120_000, 180_000],
+            "high" => &[100.5, 101.0, 101.5, 103.0],
+            "low" => &[99.5, 100.0, 100.5, 101.0],
+            "close" => &[100.0, 100.5, 101.2, 102.5],
+        )
+        .unwrap()
+        .lazy();
+        let trade = set_up_trade_ppp_long(100.0, 99.0, 102.0);
+        let calculator = TradePnLCalculatorBuilder::new()
+            .with_entry_ts(0)
+            .with_trade(trade.clone())
+            .with_market_sim_data_since_entry(market_sim_data_since_entry)
+            .with_trade_and_pre_trade_values(set_up_trade_and_pre_trade_values_ppp_long(0));
+
+        // The session ends before the take profit is reached in the fourth candle
+        let pnl = calculator
+            .clone()
+            .with_session_end_ts(Some(180_000))
+            .build_and_compute()
+            .unwrap();
+        assert_eq!(101.2, pnl.exit_price());
+        assert_eq!(TerminationReason::SessionEnd, pnl.termination_reason());
+
+        // The earlier of the session end and the maximum holding period closes the trade
+        let trade = Trade {
+            max_holding_period: Some(MaxHoldingPeriod::Candles(2)),
+            ..trade
+        };
+        let pnl = calculator
+            .with_trade(trade)
+            .with_session_end_ts(Some(180_000))
+            .build_and_compute()
+            .unwrap();
+        assert_eq!(100.5, pnl.exit_price());
+        assert_eq!(TerminationReason::MaxHoldingPeriod, pnl.termination_reason());
+    }
 }
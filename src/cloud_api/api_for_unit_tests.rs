@@ -1,4 +1,6 @@
-use crate::{chapaty, config, serde::deserialize::deserialize_data_frame_map};
+use crate::{
+    chapaty, config, enums::error::ChapatyErrorKind, serde::deserialize::deserialize_data_frame_map,
+};
 use google_cloud_storage::http::objects::{download::Range, get::GetObjectRequest};
 use polars::prelude::{CsvReader, DataFrame, SerReader};
 use std::io::Cursor;
@@ -38,7 +40,9 @@ pub async fn download_df_as_bytes(bucket: String, abs_file_path: String) -> Vec<
 }
 
 #[allow(dead_code)]
-pub async fn download_df_map(abs_file_path: String) -> chapaty::types::DataFrameMap {
+pub async fn download_df_map(
+    abs_file_path: String,
+) -> Result<chapaty::types::DataFrameMap, ChapatyErrorKind> {
     let client = config::get_google_cloud_storage_client().await;
     let req = GetObjectRequest {
         bucket: "chapaty-ai-test".to_string(),
@@ -49,6 +53,6 @@ pub async fn download_df_map(abs_file_path: String) -> chapaty::types::DataFrame
     let bytes = client
         .download_object(&req, &Range::default())
         .await
-        .unwrap();
+        .map_err(|e| ChapatyErrorKind::UnknownGoogleCloudStorageError(e.to_string()))?;
     deserialize_data_frame_map(bytes)
 }
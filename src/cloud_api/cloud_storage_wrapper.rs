@@ -5,7 +5,12 @@ use super::{
 use crate::{
     bot::{indicator_data_pair::IndicatorDataPair, transformer::TransformerBuilder, Bot},
     chapaty,
-    enums::{data::HdbSourceDirKind, error::ChapatyErrorKind, markets::MarketKind},
+    instrumentation,
+    enums::{
+        data::{CompressionKind, HdbSourceDirKind},
+        error::ChapatyErrorKind,
+        markets::MarketKind,
+    },
     serde::{deserialize::deserialize_data_frame_map, serialize::serialize_data_frame_map}, data_provider::BytesToDataFrameRequest,
 };
 use google_cloud_storage::{
@@ -23,7 +28,7 @@ use google_cloud_storage::{
 };
 use polars::prelude::DataFrame;
 use rayon::{iter::ParallelIterator, prelude::IntoParallelIterator};
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use tokio::task::JoinHandle;
 
 #[derive(Clone)]
@@ -40,15 +45,44 @@ pub struct CloudStorageClient {
 impl CloudStorageClient {
     pub async fn download_df_map(&self) -> chapaty::types::DataFrameMap {
         let bucket = self.bot.get_cached_data_bucket_name_ref();
-        match self.try_download(bucket).await {
-            Ok(v) => deserialize_data_frame_map(v),
+        match self.try_download(bucket).await.and_then(deserialize_data_frame_map) {
+            Ok(v) => {
+                instrumentation::record_cache_hit();
+                v
+            }
             Err(e) => self.handle_chapaty_error(e).await,
         }
     }
 
+    /// Downloads a df map that was previously uploaded with `upload_df_map_to_cache`. Returns
+    /// `None` if the df map was not cached yet.
+    pub async fn download_cached_df_map(&self) -> Option<chapaty::types::DataFrameMap> {
+        let bucket = self.bot.get_cached_data_bucket_name_ref();
+        match self.try_download(bucket).await.and_then(deserialize_data_frame_map) {
+            Ok(v) => Some(v),
+            Err(ChapatyErrorKind::FileNotFound(_))
+            | Err(ChapatyErrorKind::IncompatibleSerializationVersion(_))
+            | Err(ChapatyErrorKind::CorruptCachedData(_)) => None,
+            Err(e) => {
+                panic!("Cannot download cached df map. Execution is stopped, caused by: {e:?}")
+            },
+        }
+    }
+
+    pub async fn upload_df_map_to_cache(&self, df_map: &chapaty::types::DataFrameMap) {
+        let file_name = self.file_path_with_fallback.get_file_owned();
+        self.cache_df_map_with_file_name(df_map, file_name).await;
+    }
+
     async fn handle_chapaty_error(&self, error: ChapatyErrorKind) -> chapaty::types::DataFrameMap {
         match error {
-            ChapatyErrorKind::FileNotFound(_) => self.compute_df_map_from_hdb().await,
+            // A df map with an incompatible version or corrupt data is recomputed and cached again
+            ChapatyErrorKind::FileNotFound(_)
+            | ChapatyErrorKind::IncompatibleSerializationVersion(_)
+            | ChapatyErrorKind::CorruptCachedData(_) => {
+                instrumentation::record_cache_miss();
+                self.compute_df_map_from_hdb().await
+            }
             _ => panic!("Cannot download df map. Execution is stopped, caused by: {error:?}"),
         }
     }
@@ -91,6 +125,8 @@ impl CloudStorageClient {
                 .with_year(year)
                 .with_time_interval(*bot.get_time_interval_optional_ref())
                 .with_time_frame(bot.get_time_frame_ref().to_string())
+                .with_excluded_time_ranges(bot.get_excluded_time_ranges(&market).to_vec())
+                .with_period(bot.get_period(), bot.get_holidays_ref().to_vec())
                 .build();
 
             let file_name = _self.get_file_name_resolver().get_filename();
@@ -116,8 +152,11 @@ impl CloudStorageClient {
         df_map: &chapaty::types::DataFrameMap,
         file_name: String,
     ) {
-        let bytes = serialize_data_frame_map(df_map).into_bytes();
-        self.upload_to_cloud_storage(bytes, file_name).await;
+        // Caching is an optimization, hence a df map that cannot be serialized is not cached and
+        // recomputed by the next backtest
+        if let Ok(bytes) = serialize_data_frame_map(df_map, self.bot.get_cache_compression()) {
+            self.upload_to_cloud_storage(bytes, file_name).await;
+        }
     }
 
     async fn upload_to_cloud_storage(&self, bytes: Vec<u8>, file_name: String) {
@@ -127,7 +166,7 @@ impl CloudStorageClient {
         };
         let upload_type = UploadType::Simple(Media {
             name: file_name.into(),
-            content_type: std::borrow::Cow::Borrowed("application/json"),
+            content_type: std::borrow::Cow::Borrowed(self.content_type()),
             content_length: None,
         });
 
@@ -138,6 +177,13 @@ impl CloudStorageClient {
             .unwrap();
     }
 
+    fn content_type(&self) -> &'static str {
+        match self.bot.get_cache_compression() {
+            CompressionKind::None => "application/json",
+            _ => "application/octet-stream",
+        }
+    }
+
     async fn dfs_from_hdb_files(&self, files: Vec<String>) -> Vec<DataFrame> {
         let bucket = self.bot.get_historical_data_bucket_name_owned();
         let client_builder: CloudStorageClientBuilder = self.clone().into();
@@ -200,11 +246,14 @@ impl CloudStorageClient {
             ..Default::default()
         };
 
-        self.bot
+        let start = Instant::now();
+        let bytes = self
+            .bot
             .get_client_ref()
             .download_object(&get_request, &Range::default())
-            .await
-            .map_err(|e| handle_google_cloud_error(e, object, bucket))
+            .await;
+        instrumentation::record_fetch(start.elapsed());
+        bytes.map_err(|e| handle_google_cloud_error(e, object, bucket))
     }
 }
 
@@ -1,8 +1,9 @@
 use super::file_path_with_fallback::FilePathWithFallback;
 use crate::{
-    bot::time_interval::TimeInterval,
-    enums::{data::HdbSourceDirKind, markets::MarketKind},
+    bot::{excluded_time_range::ExcludedTimeRange, time_interval::TimeInterval},
+    enums::{bot::PeriodKind, data::HdbSourceDirKind, markets::MarketKind},
 };
+use chrono::{Datelike, NaiveDate};
 use regex::Regex;
 use std::path::PathBuf;
 
@@ -13,6 +14,9 @@ pub struct PathFinder {
     year: u32,
     time_interval: Option<TimeInterval>,
     time_frame: String,
+    excluded_time_ranges: Vec<ExcludedTimeRange>,
+    period: PeriodKind,
+    holidays: Vec<NaiveDate>,
 }
 
 impl PathFinder {
@@ -41,9 +45,36 @@ impl PathFinder {
         file_path.push(self.year.to_string());
         file_path.push(time_interval);
         file_path.push(self.time_frame.clone());
+        if let Some(period) = self.get_period_dir() {
+            file_path.push(period);
+        }
+        if !self.excluded_time_ranges.is_empty() {
+            let mut ranges = self.excluded_time_ranges.clone();
+            ranges.sort_by_key(|range| (range.start, range.end));
+            let values = ranges.iter().flat_map(|range| [range.start, range.end]);
+            let bytes = values.flat_map(i64::to_le_bytes);
+            file_path.push(format!("excluded-{:016x}", fingerprint(bytes)));
+        }
         file_path
     }
 
+    /// The calendar day is the default period and hence has no directory, such that existing
+    /// cached data remains valid.
+    fn get_period_dir(&self) -> Option<String> {
+        match self.period {
+            PeriodKind::CalendarDay => None,
+            PeriodKind::BusinessDay if self.holidays.is_empty() => Some(self.period.to_string()),
+            PeriodKind::BusinessDay => {
+                let mut holidays = self.holidays.clone();
+                holidays.sort();
+                let bytes = holidays
+                    .iter()
+                    .flat_map(|day| i64::from(day.num_days_from_ce()).to_le_bytes());
+                Some(format!("{}-{:016x}", self.period, fingerprint(bytes)))
+            }
+        }
+    }
+
     fn get_fallback_file_name(&self, leaf_dir_kind: &HdbSourceDirKind) -> String {
         let data_provider = self.data_provider.clone();
         let market = self.market;
@@ -67,6 +98,14 @@ impl PathFinder {
     }
 }
 
+/// Computes a FNV-1a hash of the bytes that, unlike the `DefaultHasher`, is stable across Rust
+/// releases and hence can be part of the path to cached data.
+pub(crate) fn fingerprint(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 pub struct PathFinderBuilder {
     data_provider: Option<String>,
     strategy_name: Option<String>,
@@ -74,6 +113,9 @@ pub struct PathFinderBuilder {
     year: Option<u32>,
     time_interval: Option<TimeInterval>,
     time_frame: Option<String>,
+    excluded_time_ranges: Vec<ExcludedTimeRange>,
+    period: PeriodKind,
+    holidays: Vec<NaiveDate>,
 }
 
 impl PathFinderBuilder {
@@ -85,6 +127,9 @@ impl PathFinderBuilder {
             year: None,
             time_interval: None,
             time_frame: None,
+            excluded_time_ranges: Vec::new(),
+            period: PeriodKind::CalendarDay,
+            holidays: Vec::new(),
         }
     }
 
@@ -125,6 +170,25 @@ impl PathFinderBuilder {
         }
     }
 
+    /// Cached data of a market with excluded time ranges is stored in a subdirectory that is
+    /// unique for these ranges.
+    pub fn with_excluded_time_ranges(self, excluded_time_ranges: Vec<ExcludedTimeRange>) -> Self {
+        Self {
+            excluded_time_ranges,
+            ..self
+        }
+    }
+
+    /// Cached data grouped by `PeriodKind::BusinessDay` is stored in a subdirectory that is unique
+    /// for the holidays.
+    pub fn with_period(self, period: PeriodKind, holidays: Vec<NaiveDate>) -> Self {
+        Self {
+            period,
+            holidays,
+            ..self
+        }
+    }
+
     pub fn build(self) -> PathFinder {
         PathFinder {
             data_provider: self.data_provider.unwrap(),
@@ -133,6 +197,73 @@ impl PathFinderBuilder {
             year: self.year.unwrap(),
             time_interval: self.time_interval,
             time_frame: self.time_frame.unwrap(),
+            excluded_time_ranges: self.excluded_time_ranges,
+            period: self.period,
+            holidays: self.holidays,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path_finder(excluded_time_ranges: Vec<ExcludedTimeRange>) -> PathFinder {
+        PathFinderBuilder::new()
+            .with_data_provider("binance".to_string())
+            .with_strategy_name("ppp".to_string())
+            .with_market(MarketKind::BtcUsdt)
+            .with_year(2022)
+            .with_time_frame("daily".to_string())
+            .with_excluded_time_ranges(excluded_time_ranges)
+            .build()
+    }
+
+    #[test]
+    fn test_excluded_time_ranges_are_part_of_the_path() {
+        let first = ExcludedTimeRange::new(0, 60_000);
+        let second = ExcludedTimeRange::new(120_000, 180_000);
+
+        assert_eq!(
+            "ppp/btcusdt/2022/none/daily/vol.json",
+            path_finder(Vec::new()).get_absolute_file_path("vol".to_string())
+        );
+        let path = path_finder(vec![first, second]).get_absolute_file_path("vol".to_string());
+        assert!(path.starts_with("ppp/btcusdt/2022/none/daily/excluded-"));
+        assert_eq!(
+            path,
+            path_finder(vec![second, first]).get_absolute_file_path("vol".to_string())
+        );
+        assert_ne!(
+            path,
+            path_finder(vec![first]).get_absolute_file_path("vol".to_string())
+        );
+    }
+
+    #[test]
+    fn test_period_and_holidays_are_part_of_the_path() {
+        let christmas = NaiveDate::from_ymd_opt(2022, 12, 26).unwrap();
+        let path_finder = |period, holidays| {
+            PathFinderBuilder::new()
+                .with_data_provider("cme".to_string())
+                .with_strategy_name("ppp".to_string())
+                .with_market(MarketKind::EurUsdFuture)
+                .with_year(2022)
+                .with_time_frame("daily".to_string())
+                .with_period(period, holidays)
+                .build()
+                .get_absolute_file_path("tpo".to_string())
+        };
+
+        assert_eq!(
+            "ppp/6e/2022/none/daily/tpo.json",
+            path_finder(PeriodKind::CalendarDay, vec![christmas])
+        );
+        assert_eq!(
+            "ppp/6e/2022/none/daily/business-day/tpo.json",
+            path_finder(PeriodKind::BusinessDay, Vec::new())
+        );
+        let path = path_finder(PeriodKind::BusinessDay, vec![christmas]);
+        assert!(path.starts_with("ppp/6e/2022/none/daily/business-day-"));
+    }
+}
@@ -1,12 +1,532 @@
+use crate::{
+    bot::{
+        excluded_time_range::ExcludedTimeRange, instrument_override::InstrumentOverride,
+        time_interval::TimeInterval, Bot, BotBuilder,
+    },
+    converter::report_format::ReportFormat,
+    data_provider::{binance::Binance, cme::Cme, DataProvider},
+    enums::{
+        bot::{
+            DataProviderKind, ExecutionModeKind, PeriodKind, StopLossKind, TakeProfitKind,
+            TimeFrameKind,
+        },
+        data::{CompressionKind, MarketSimulationDataKind},
+        error::ChapatyErrorKind,
+        indicator::{PocSelectionRule, TradingIndicatorKind, ValueAreaRule},
+        markets::MarketKind,
+    },
+    equity_curve::Capital,
+    strategy::{ppp::PppBuilder, MaxHoldingPeriod, StopLoss, Strategy, TakeProfit},
+};
+use chrono::NaiveDate;
 use google_cloud_storage::client::{Client, ClientConfig};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
 pub async fn get_google_cloud_storage_client() -> Client {
     let config = ClientConfig::default().with_auth().await.unwrap();
     Client::new(config)
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GoogleCloudBucket {
     pub historical_market_data_bucket_name: String,
     pub cached_bot_data_bucket_name: String,
 }
+
+/// Declarative definition of a `Bot`, which is loaded from JSON, TOML or YAML, see
+/// `BotConfig::from_file`. TOML requires the `toml` feature and YAML the `yaml` feature. Enum
+/// values use the same string representation as their `FromStr` implementation, e.g. `"6e"` for
+/// `MarketKind::EurUsdFuture` or `"ohlc-1m"` for `MarketSimulationDataKind::Ohlc1m`.
+///
+/// # Example
+/// ```
+/// use chapaty::config::BotConfig;
+///
+/// let config = BotConfig::from_json(
+///     r#"{
+///         "name": "chapaty",
+///         "historical_market_data_bucket_name": "chapaty-ai-hdb",
+///         "cached_bot_data_bucket_name": "chapaty-ai-cache",
+///         "data_provider": "cme",
+///         "strategy": {
+///             "name": "ppp",
+///             "entry": { "Poc": "Tpo1m" },
+///             "stop_loss": { "kind": "PrevHighOrLow", "offset": 0.0 },
+///             "take_profit": { "kind": "PrevClose", "offset": 0.0 }
+///         },
+///         "markets": ["6e"],
+///         "years": [2022],
+///         "market_simulation_data": "ohlc-1m",
+///         "time_frame": "1d",
+///         "time_interval": { "start_day": "Mon", "start_h": 1, "end_day": "Fri", "end_h": 23 }
+///     }"#,
+/// )
+/// .unwrap();
+/// let bot_builder = config.into_bot_builder().unwrap();
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BotConfig {
+    #[serde(default = "default_bot_name")]
+    pub name: String,
+    #[serde(flatten)]
+    pub bucket: GoogleCloudBucket,
+    pub data_provider: String,
+    pub strategy: StrategyConfig,
+    pub markets: Vec<String>,
+    pub years: Vec<u32>,
+    pub market_simulation_data: String,
+    pub time_frame: String,
+    /// E.g. `"calendar-day"` or `"business-day"`
+    #[serde(default)]
+    pub period: Option<String>,
+    /// Exchange holidays skipped by the business-day period, e.g. `["2022-02-21"]`
+    #[serde(default)]
+    pub holidays: Vec<String>,
+    #[serde(default)]
+    pub time_interval: Option<TimeIntervalConfig>,
+    /// Time ranges per market that are excluded from the backtest, e.g.
+    /// `{ "btcusdt": [{ "start": 1646010000000, "end": 1646013600000 }] }`
+    #[serde(default)]
+    pub excluded_time_ranges: HashMap<String, Vec<ExcludedTimeRange>>,
+    /// Contract specification per market, e.g.
+    /// `{ "6e": { "tick_size": 0.0001, "tick_value": 1.25, "fee": 0.5 } }`
+    #[serde(default)]
+    pub instrument_overrides: HashMap<String, InstrumentOverride>,
+    #[serde(default)]
+    pub save_result_as_csv: bool,
+    #[serde(default)]
+    pub cache_computations: bool,
+    #[serde(default)]
+    pub cache_compression: CompressionKind,
+    #[serde(default)]
+    pub execution_mode: Option<String>,
+    #[serde(default)]
+    pub poc_selection_rule: Option<String>,
+    #[serde(default)]
+    pub value_area_rule: Option<String>,
+    #[serde(default)]
+    pub value_area_percent: Option<f64>,
+    #[serde(default)]
+    pub max_parallel_backtest_units: Option<usize>,
+    #[serde(default)]
+    pub incremental_backtest: bool,
+    #[serde(default)]
+    pub flat_at_session_end: bool,
+    #[serde(default)]
+    pub dataset_export_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub streaming_report_file: Option<PathBuf>,
+    /// E.g. `{ "initial": 10000.0, "is_compounding": true }`
+    #[serde(default)]
+    pub capital: Capital,
+    /// E.g. `{ "default_decimal_places": 2, "currency_symbol": "$" }`
+    #[serde(default)]
+    pub report_format: ReportFormat,
+}
+
+/// Declarative definition of the `Strategy` of a `Bot`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    pub name: String,
+    pub entry: TradingIndicatorKind,
+    pub stop_loss: ExitConfig,
+    pub take_profit: ExitConfig,
+    /// E.g. `{ "Candles": 60 }` or `{ "Minutes": 90 }`
+    #[serde(default)]
+    pub max_holding_period: Option<MaxHoldingPeriod>,
+}
+
+/// Kind and offset of a stop loss or take profit, e.g. `{ "kind": "PrevHighOrLow", "offset": 0.0 }`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExitConfig {
+    pub kind: String,
+    pub offset: f64,
+}
+
+/// Declarative definition of a `TimeInterval`. Weekdays are given as e.g. `"Mon"` or `"Monday"`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimeIntervalConfig {
+    pub start_day: String,
+    pub start_h: u32,
+    pub end_day: String,
+    pub end_h: u32,
+}
+
+fn default_bot_name() -> String {
+    "chapaty".to_string()
+}
+
+fn parse<T: FromStr>(s: &str) -> Result<T, ChapatyErrorKind> {
+    T::from_str(s).map_err(|_| ChapatyErrorKind::ParseBotError(format!("Unknown value <{s}>")))
+}
+
+impl BotConfig {
+    /// Reads the config from a file, whose format is determined by its extension, i.e. `.json`,
+    /// `.toml` with the `toml` feature, or `.yaml` and `.yml` with the `yaml` feature.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ChapatyErrorKind> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| ChapatyErrorKind::FileNotFound(format!("{}: {e}", path.display())))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json(&content),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml(&content),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&content),
+            _ => Err(ChapatyErrorKind::ParseBotConfigError(format!(
+                "Unsupported config file <{}>, TOML and YAML require the `toml` and `yaml` features",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, ChapatyErrorKind> {
+        serde_json::from_str(s).map_err(|e| ChapatyErrorKind::ParseBotConfigError(e.to_string()))
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml(s: &str) -> Result<Self, ChapatyErrorKind> {
+        toml::from_str(s).map_err(|e| ChapatyErrorKind::ParseBotConfigError(e.to_string()))
+    }
+
+    /// Enum variants with a value are written as YAML tags, e.g. `entry: !Poc Tpo1m`.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(s: &str) -> Result<Self, ChapatyErrorKind> {
+        serde_yaml::from_str(s).map_err(|e| ChapatyErrorKind::ParseBotConfigError(e.to_string()))
+    }
+
+    /// Returns a `BotBuilder` that is fully configured, except for the Google Cloud Storage client.
+    pub fn into_bot_builder(self) -> Result<BotBuilder, ChapatyErrorKind> {
+        let builder = BotBuilder::new(self.strategy.build()?, self.data_provider()?)
+            .with_name(self.name.clone())
+            .with_years(self.years.clone())
+            .with_markets(self.markets()?)
+            .with_market_simulation_data(parse::<MarketSimulationDataKind>(
+                &self.market_simulation_data,
+            )?)
+            .with_time_frame(parse::<TimeFrameKind>(&self.time_frame)?)
+            .with_holidays(self.holidays()?)
+            .with_save_result_as_csv(self.save_result_as_csv)
+            .with_cache_computations(self.cache_computations)
+            .with_cache_compression(self.cache_compression)
+            .with_incremental_backtest(self.incremental_backtest)
+            .with_flat_at_session_end(self.flat_at_session_end)
+            .with_capital(self.capital)
+            .with_report_format(self.report_format.clone())
+            .with_google_cloud_bucket(self.bucket.clone());
+
+        let builder = match &self.execution_mode {
+            Some(execution_mode) => {
+                builder.with_execution_mode(parse::<ExecutionModeKind>(execution_mode)?)
+            }
+            None => builder,
+        };
+
+        let builder = match &self.period {
+            Some(period) => builder.with_period(parse::<PeriodKind>(period)?),
+            None => builder,
+        };
+
+        let builder = match &self.poc_selection_rule {
+            Some(rule) => builder.with_poc_selection_rule(parse::<PocSelectionRule>(rule)?),
+            None => builder,
+        };
+
+        let builder = match &self.value_area_rule {
+            Some(rule) => builder.with_value_area_rule(parse::<ValueAreaRule>(rule)?),
+            None => builder,
+        };
+
+        let builder = match self.value_area_percent {
+            Some(value_area_percent) => builder.with_value_area_percent(value_area_percent),
+            None => builder,
+        };
+
+        let builder = self.excluded_time_ranges.iter().try_fold(
+            builder,
+            |builder, (market, ranges)| {
+                Ok::<_, ChapatyErrorKind>(
+                    builder.with_excluded_time_ranges(parse(market)?, ranges.clone()),
+                )
+            },
+        )?;
+
+        let builder = self.instrument_overrides.iter().try_fold(
+            builder,
+            |builder, (market, instrument)| {
+                Ok::<_, ChapatyErrorKind>(builder.with_instrument_override(
+                    parse(market)?,
+                    instrument.tick_size,
+                    instrument.tick_value,
+                    instrument.fee,
+                ))
+            },
+        )?;
+
+        let builder = match &self.dataset_export_dir {
+            Some(dir) => builder.with_dataset_export_dir(dir.clone()),
+            None => builder,
+        };
+
+        let builder = match &self.streaming_report_file {
+            Some(file) => builder.with_streaming_report_file(file.clone()),
+            None => builder,
+        };
+
+        let builder = match self.max_parallel_backtest_units {
+            Some(max_units) => builder.with_max_parallel_backtest_units(max_units),
+            None => builder,
+        };
+
+        match &self.time_interval {
+            Some(time_interval) => Ok(builder.with_time_interval(time_interval.build()?)),
+            None => Ok(builder),
+        }
+    }
+
+    /// Builds the `Bot` with the default Google Cloud Storage client.
+    pub async fn build(self) -> Result<Bot, ChapatyErrorKind> {
+        self.into_bot_builder()?
+            .with_google_cloud_storage_client(get_google_cloud_storage_client().await)
+            .build()
+    }
+
+    fn data_provider(&self) -> Result<Arc<dyn DataProvider + Send + Sync>, ChapatyErrorKind> {
+        match DataProviderKind::from_str(&self.data_provider) {
+            Ok(DataProviderKind::Cme) => Ok(Arc::new(Cme)),
+            Ok(DataProviderKind::Binance) => Ok(Arc::new(Binance)),
+            Err(_) => Err(ChapatyErrorKind::ParseDataProducerError(format!(
+                "Data Producer <{}> does not Exists",
+                self.data_provider
+            ))),
+        }
+    }
+
+    fn markets(&self) -> Result<Vec<MarketKind>, ChapatyErrorKind> {
+        self.markets.iter().map(|market| parse(market)).collect()
+    }
+
+    fn holidays(&self) -> Result<Vec<NaiveDate>, ChapatyErrorKind> {
+        self.holidays.iter().map(|holiday| parse(holiday)).collect()
+    }
+}
+
+impl StrategyConfig {
+    pub fn build(&self) -> Result<Arc<dyn Strategy + Send + Sync>, ChapatyErrorKind> {
+        let builder = PppBuilder::from_str(&self.name)?
+            .with_entry(self.entry)
+            .with_stop_loss(self.stop_loss()?)
+            .with_take_profit(self.take_profit()?);
+        let strategy = match self.max_holding_period {
+            Some(max_holding_period) => builder.with_max_holding_period(max_holding_period),
+            None => builder,
+        };
+        Ok(Arc::new(strategy.try_build()?))
+    }
+
+    fn stop_loss(&self) -> Result<StopLoss, ChapatyErrorKind> {
+        Ok(StopLoss {
+            kind: parse::<StopLossKind>(&self.stop_loss.kind)?,
+            offset: self.stop_loss.offset,
+        })
+    }
+
+    fn take_profit(&self) -> Result<TakeProfit, ChapatyErrorKind> {
+        Ok(TakeProfit {
+            kind: parse::<TakeProfitKind>(&self.take_profit.kind)?,
+            offset: self.take_profit.offset,
+        })
+    }
+}
+
+impl TimeIntervalConfig {
+    pub fn build(&self) -> Result<TimeInterval, ChapatyErrorKind> {
+        Ok(TimeInterval {
+            start_day: parse(&self.start_day)?,
+            start_h: self.start_h,
+            end_day: parse(&self.end_day)?,
+            end_h: self.end_h,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bot_config() -> BotConfig {
+        BotConfig::from_json(
+            r#"{
+                "historical_market_data_bucket_name": "hdb",
+                "cached_bot_data_bucket_name": "cache",
+                "data_provider": "cme",
+                "strategy": {
+                    "name": "ppp",
+                    "entry": { "Poc": "Tpo1m" },
+                    "stop_loss": { "kind": "PrevHighOrLow", "offset": 0.0 },
+                    "take_profit": { "kind": "PrevClose", "offset": 0.0 }
+                },
+                "markets": ["6e", "6b"],
+                "years": [2022],
+                "market_simulation_data": "ohlc-1m",
+                "time_frame": "1d",
+                "time_interval": { "start_day": "Mon", "start_h": 1, "end_day": "Fri", "end_h": 23 }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_bot_config() {
+        let config = bot_config();
+
+        assert_eq!("chapaty", config.name);
+        assert_eq!("hdb", config.bucket.historical_market_data_bucket_name);
+        assert_eq!(
+            vec![MarketKind::EurUsdFuture, MarketKind::GbpUsdFuture],
+            config.markets().unwrap()
+        );
+        assert!(!config.cache_computations);
+    }
+
+    #[test]
+    fn test_time_interval_config() {
+        let time_interval = bot_config().time_interval.unwrap().build().unwrap();
+
+        assert_eq!(chrono::Weekday::Mon, time_interval.start_day);
+        assert_eq!(1, time_interval.start_h);
+        assert_eq!(chrono::Weekday::Fri, time_interval.end_day);
+        assert_eq!(23, time_interval.end_h);
+    }
+
+    #[test]
+    fn test_invalid_bot_config() {
+        let mut config = bot_config();
+        config.markets = vec!["eurusd".to_string()];
+        assert!(config.clone().into_bot_builder().is_err());
+
+        config.markets = vec!["6e".to_string()];
+        config.excluded_time_ranges = HashMap::from([(
+            "eurusd".to_string(),
+            vec![ExcludedTimeRange::new(0, 60_000)],
+        )]);
+        assert!(config.clone().into_bot_builder().is_err());
+
+        config.excluded_time_ranges = HashMap::new();
+        config.holidays = vec!["21.02.2022".to_string()];
+        assert!(config.clone().into_bot_builder().is_err());
+
+        config.holidays = vec!["2022-02-21".to_string()];
+        config.period = Some("business-day".to_string());
+        assert!(config.clone().into_bot_builder().is_ok());
+
+        config.strategy.stop_loss.kind = "Unknown".to_string();
+        assert!(config.into_bot_builder().is_err());
+    }
+
+    fn assert_eq_bot_config(expected: &BotConfig, actual: &BotConfig) {
+        assert_eq!(
+            serde_json::to_value(expected).unwrap(),
+            serde_json::to_value(actual).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bot_config_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("bot.json");
+        fs::write(&json, serde_json::to_string(&bot_config()).unwrap()).unwrap();
+
+        assert_eq_bot_config(&bot_config(), &BotConfig::from_file(&json).unwrap());
+        assert!(matches!(
+            BotConfig::from_file(dir.path().join("missing.json")),
+            Err(ChapatyErrorKind::FileNotFound(_))
+        ));
+
+        let ini = dir.path().join("bot.ini");
+        fs::write(&ini, "name = chapaty").unwrap();
+        assert!(matches!(
+            BotConfig::from_file(&ini),
+            Err(ChapatyErrorKind::ParseBotConfigError(_))
+        ));
+        assert!(matches!(
+            BotConfig::from_json(r#"{"markets": "#),
+            Err(ChapatyErrorKind::ParseBotConfigError(_))
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_bot_config() {
+        let config = BotConfig::from_toml(
+            r#"
+            historical_market_data_bucket_name = "hdb"
+            cached_bot_data_bucket_name = "cache"
+            data_provider = "cme"
+            markets = ["6e", "6b"]
+            years = [2022]
+            market_simulation_data = "ohlc-1m"
+            time_frame = "1d"
+
+            [strategy]
+            name = "ppp"
+            entry = { Poc = "Tpo1m" }
+            stop_loss = { kind = "PrevHighOrLow", offset = 0.0 }
+            take_profit = { kind = "PrevClose", offset = 0.0 }
+
+            [time_interval]
+            start_day = "Mon"
+            start_h = 1
+            end_day = "Fri"
+            end_h = 23
+            "#,
+        )
+        .unwrap();
+        assert_eq_bot_config(&bot_config(), &config);
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("bot.toml");
+        fs::write(&file, toml::to_string(&bot_config()).unwrap()).unwrap();
+        assert_eq_bot_config(&bot_config(), &BotConfig::from_file(&file).unwrap());
+        assert!(matches!(
+            BotConfig::from_toml("markets = "),
+            Err(ChapatyErrorKind::ParseBotConfigError(_))
+        ));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_bot_config() {
+        let config = BotConfig::from_yaml(
+            r#"
+            historical_market_data_bucket_name: hdb
+            cached_bot_data_bucket_name: cache
+            data_provider: cme
+            strategy:
+              name: ppp
+              entry: !Poc Tpo1m
+              stop_loss: { kind: PrevHighOrLow, offset: 0.0 }
+              take_profit: { kind: PrevClose, offset: 0.0 }
+            markets: ["6e", "6b"]
+            years: [2022]
+            market_simulation_data: ohlc-1m
+            time_frame: 1d
+            time_interval: { start_day: Mon, start_h: 1, end_day: Fri, end_h: 23 }
+            "#,
+        )
+        .unwrap();
+        assert_eq_bot_config(&bot_config(), &config);
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("bot.yml");
+        fs::write(&file, serde_yaml::to_string(&bot_config()).unwrap()).unwrap();
+        assert_eq_bot_config(&bot_config(), &BotConfig::from_file(&file).unwrap());
+    }
+}
@@ -1,3 +1,5 @@
 pub mod any_value;
+pub mod fixed_point;
 pub mod market_decimal_places;
-pub mod pnl_to_report;
\ No newline at end of file
+pub mod pnl_to_report;
+pub mod report_format;
\ No newline at end of file
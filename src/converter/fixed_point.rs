@@ -0,0 +1,87 @@
+use std::ops::Sub;
+
+/// Number of fixed-point units per price unit, i.e. a `FixedPointPrice` is exact up to `1e-9`.
+const UNITS_PER_PRICE: f64 = 1e9;
+
+/// A price in integer units of `1e-9`. Prices are converted before they are subtracted or scaled,
+/// such that differences and tick conversions are free of floating point artifacts, e.g.
+/// `1.102 - 1.1` is exactly `20` ticks of `0.0001`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedPointPrice(i64);
+
+impl FixedPointPrice {
+    pub fn from_f64(price: f64) -> Self {
+        Self((price * UNITS_PER_PRICE).round() as i64)
+    }
+
+    /// Returns the price of `ticks` ticks of size `tick_size`, snapped to the fixed-point grid.
+    pub fn from_ticks(ticks: f64, tick_size: Self) -> Self {
+        Self((ticks * tick_size.0 as f64).round() as i64)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / UNITS_PER_PRICE
+    }
+
+    /// Returns the number of ticks of size `tick_size` in this price.
+    pub fn to_ticks(self, tick_size: Self) -> f64 {
+        self.0 as f64 / tick_size.0 as f64
+    }
+}
+
+impl Sub for FixedPointPrice {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+/// Returns the price difference `to - from` in ticks of size `tick_size`. With the `fixed-point`
+/// feature, the prices are converted into `FixedPointPrice` before they are subtracted.
+pub fn price_difference_to_ticks(from: f64, to: f64, tick_size: f64) -> f64 {
+    if cfg!(feature = "fixed-point") {
+        let difference = FixedPointPrice::from_f64(to) - FixedPointPrice::from_f64(from);
+        difference.to_ticks(FixedPointPrice::from_f64(tick_size))
+    } else {
+        (to - from) / tick_size
+    }
+}
+
+/// Returns the price of `ticks` ticks of size `tick_size`. With the `fixed-point` feature, the
+/// tick size is converted into a `FixedPointPrice` before it is multiplied.
+pub fn ticks_to_price(ticks: f64, tick_size: f64) -> f64 {
+    if cfg!(feature = "fixed-point") {
+        FixedPointPrice::from_ticks(ticks, FixedPointPrice::from_f64(tick_size)).to_f64()
+    } else {
+        ticks * tick_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_point_price() {
+        let tick_size = FixedPointPrice::from_f64(0.0001);
+        let profit = FixedPointPrice::from_f64(1.102) - FixedPointPrice::from_f64(1.1);
+        assert_ne!(20.0, (1.102 - 1.1) / 0.0001);
+        assert_eq!(20.0, profit.to_ticks(tick_size));
+        assert_eq!(0.002, profit.to_f64());
+        assert_eq!(profit, FixedPointPrice::from_ticks(20.0, tick_size));
+        let yen_tick_size = FixedPointPrice::from_f64(0.0000005);
+        assert_eq!(
+            20.0,
+            FixedPointPrice::from_f64(0.00001).to_ticks(yen_tick_size)
+        );
+    }
+
+    #[cfg(feature = "fixed-point")]
+    #[test]
+    fn test_tick_conversion_with_fixed_point() {
+        assert_eq!(20.0, price_difference_to_ticks(1.1, 1.102, 0.0001));
+        assert_eq!(-20.0, price_difference_to_ticks(1.102, 1.1, 0.0001));
+        assert_eq!(0.0003, ticks_to_price(3.0, 0.0001));
+    }
+}
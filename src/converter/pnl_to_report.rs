@@ -1,18 +1,26 @@
 use crate::{
-    enums::column_names::{
-        PerformanceReportColumnKind, PnLReportColumnKind, TradeBreakDownReportColumnKind,
+    enums::{
+        column_names::{
+            PerformanceReportColumnKind, PnLReportColumnKind, TradeBreakDownReportColumnKind,
+        },
+        trade_and_pre_trade::TerminationReason,
     },
     pnl::metrics::{
-        accumulated_profit, avg_loss, avg_trade, avg_win, avg_win_by_avg_loose, max_draw_down_abs,
-        max_draw_down_rel, net_profit, number_loser_trades, number_no_entry,
-        number_timeout_loser_trades, number_timeout_trades, number_timeout_winner_trades,
-        number_winner_trades, percent_profitability, profit_factor, timeout_loss, timeout_win,
-        total_loss, total_number_loser_trades, total_number_trades, total_number_winner_trades,
-        total_win,
+        accumulated_profit, avg_loss, avg_r_multiple, avg_trade, avg_win, avg_win_by_avg_loose,
+        compounded_equity, expectancy, expectancy_std_error, kelly_fraction, max_draw_down_abs,
+        max_draw_down_rel, net_profit, number_exits_by_reason, number_loser_trades,
+        number_no_entry, number_timeout_loser_trades, number_timeout_trades,
+        number_timeout_winner_trades, number_winner_trades, percent_profitability,
+        percent_trades_above_r_multiple, pnl_by_exit_reason, profit_factor, r_expectancy,
+        timeout_loss, timeout_win, total_loss, total_number_loser_trades, total_number_trades,
+        total_number_winner_trades, total_win,
     },
+    equity_curve::Capital,
+    report_column::{append_period_columns, ReportColumn},
     MarketKind, lazy_frame_operations::trait_extensions::MyLazyFrameOperations,
 };
 use polars::prelude::{col, df, DataFrame, IntoLazy, NamedFrom};
+use std::sync::Arc;
 
 pub struct PnLToReportRequest {
     pnl: DataFrame,
@@ -21,6 +29,8 @@ pub struct PnLToReportRequest {
     strategy: String,
     agg_years: bool,
     agg_markets: bool,
+    capital: Capital,
+    report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
 }
 
 impl PnLToReportRequest {
@@ -48,7 +58,7 @@ impl PnLToReportRequest {
             self.market.unwrap().to_string()
         };
 
-        df!(
+        let trade_breakdown = df!(
             &TradeBreakDownReportColumnKind::Year.to_string() => &vec![year],
             &TradeBreakDownReportColumnKind::Market.to_string() => &vec![market],
             &TradeBreakDownReportColumnKind::Strategy.to_string() => &vec![self.strategy.clone()],
@@ -67,24 +77,40 @@ impl PnLToReportRequest {
             &TradeBreakDownReportColumnKind::NumberTimeoutLoserTrades.to_string() => &vec![number_timeout_loser_trades(pl.clone())],
             &TradeBreakDownReportColumnKind::NumberTimeoutTrades.to_string() => &vec![number_timeout_trades(pl.clone())],
             &TradeBreakDownReportColumnKind::NumberNoEntry.to_string() => &vec![number_no_entry(pl.clone())],
-        ).unwrap()
+            &TradeBreakDownReportColumnKind::NumberTakeProfitExits.to_string() => &vec![number_exits_by_reason(pl.clone(), TerminationReason::TakeProfit)],
+            &TradeBreakDownReportColumnKind::TakeProfitExitPnL.to_string() => &vec![pnl_by_exit_reason(pl.clone(), TerminationReason::TakeProfit)],
+            &TradeBreakDownReportColumnKind::NumberStopLossExits.to_string() => &vec![number_exits_by_reason(pl.clone(), TerminationReason::StopLoss)],
+            &TradeBreakDownReportColumnKind::StopLossExitPnL.to_string() => &vec![pnl_by_exit_reason(pl.clone(), TerminationReason::StopLoss)],
+            &TradeBreakDownReportColumnKind::NumberTimeoutExits.to_string() => &vec![number_exits_by_reason(pl.clone(), TerminationReason::Timeout)],
+            &TradeBreakDownReportColumnKind::TimeoutExitPnL.to_string() => &vec![pnl_by_exit_reason(pl.clone(), TerminationReason::Timeout)],
+            &TradeBreakDownReportColumnKind::NumberSessionEndExits.to_string() => &vec![number_exits_by_reason(pl.clone(), TerminationReason::SessionEnd)],
+            &TradeBreakDownReportColumnKind::SessionEndExitPnL.to_string() => &vec![pnl_by_exit_reason(pl.clone(), TerminationReason::SessionEnd)],
+            &TradeBreakDownReportColumnKind::NumberMaxHoldingPeriodExits.to_string() => &vec![number_exits_by_reason(pl.clone(), TerminationReason::MaxHoldingPeriod)],
+            &TradeBreakDownReportColumnKind::MaxHoldingPeriodExitPnL.to_string() => &vec![pnl_by_exit_reason(pl.clone(), TerminationReason::MaxHoldingPeriod)],
+            &TradeBreakDownReportColumnKind::AvgRMultiple.to_string() => &vec![avg_r_multiple(pl.clone())],
+            &TradeBreakDownReportColumnKind::RExpectancy.to_string() => &vec![r_expectancy(pl.clone())],
+            &TradeBreakDownReportColumnKind::PercentageTradesAbove2R.to_string() => &vec![percent_trades_above_r_multiple(pl.clone(), 2.0)],
+        ).unwrap();
+
+        append_period_columns(trade_breakdown, &pl, &self.report_columns)
     }
 
     pub fn as_performance_report_df(&self) -> DataFrame {
         let pl = self.pnl.clone();
-        let net_profit = net_profit(pl.clone());
         let total_number_of_trades = total_number_trades(pl.clone());
-        let accumulated_profit = if self.agg_markets {
-            let agg_pnl = pnl_aggregated_by_date(&pl);
-            accumulated_profit(agg_pnl, 0.0)
+        let accumulated_profit = as_equity_curve(&pl, self.agg_markets, &self.capital);
+        let net_profit = if self.capital.is_compounding {
+            accumulated_profit.last().unwrap() - self.capital.initial
         } else {
-            accumulated_profit(pl.clone(), 0.0)
+            net_profit(pl.clone())
         };
         let total_number_winner = total_number_winner_trades(pl.clone());
         let avg_win = avg_win(pl.clone());
         let avg_loss = avg_loss(pl.clone());
         let total_win = total_win(pl.clone());
         let total_loss = total_loss(pl.clone());
+        let percent_profitability = percent_profitability(total_number_winner, total_number_of_trades);
+        let avg_win_by_avg_loss = avg_win_by_avg_loose(avg_win, avg_loss);
 
         let year = if self.agg_years {
             "All Years".to_string()
@@ -106,21 +132,29 @@ impl PnLToReportRequest {
             &PerformanceReportColumnKind::AvgWinnByTrade.to_string() => &vec![avg_trade(net_profit, total_number_of_trades)],
             &PerformanceReportColumnKind::MaxDrawDownAbs.to_string() => &vec![max_draw_down_abs(&accumulated_profit)],
             &PerformanceReportColumnKind::MaxDrawDownRel.to_string() => &vec![max_draw_down_rel(&accumulated_profit)],
-            &PerformanceReportColumnKind::PercentageProfitability.to_string() => &vec![percent_profitability(total_number_winner, total_number_of_trades)],
-            &PerformanceReportColumnKind::RatioAvgWinByAvgLoss.to_string() => &vec![avg_win_by_avg_loose(avg_win, avg_loss)],
+            &PerformanceReportColumnKind::PercentageProfitability.to_string() => &vec![percent_profitability],
+            &PerformanceReportColumnKind::RatioAvgWinByAvgLoss.to_string() => &vec![avg_win_by_avg_loss],
             &PerformanceReportColumnKind::AvgWin.to_string() => &vec![avg_win],
             &PerformanceReportColumnKind::AvgLoss.to_string() => &vec![avg_loss],
             &PerformanceReportColumnKind::ProfitFactor.to_string() => &vec![profit_factor(total_win, total_loss)],
+            &PerformanceReportColumnKind::Expectancy.to_string() => &vec![expectancy(percent_profitability, avg_win, avg_loss)],
+            &PerformanceReportColumnKind::ExpectancyStdError.to_string() => &vec![expectancy_std_error(pl.clone())],
+            &PerformanceReportColumnKind::KellyFraction.to_string() => &vec![kelly_fraction(percent_profitability, avg_win_by_avg_loss)],
         ).unwrap()
     }
 }
 
-pub fn as_equity_curve(pnl: &DataFrame, agg_markets: bool) -> Vec<f64> {
-    if agg_markets {
-        let agg_pnl = pnl_aggregated_by_date(pnl);
-        accumulated_profit(agg_pnl, 0.0)
+pub fn as_equity_curve(pnl: &DataFrame, agg_markets: bool, capital: &Capital) -> Vec<f64> {
+    let pnl = if agg_markets {
+        pnl_aggregated_by_date(pnl)
     } else {
-        accumulated_profit(pnl.clone(), 0.0)
+        pnl.clone()
+    };
+
+    if capital.is_compounding {
+        compounded_equity(pnl, capital.initial)
+    } else {
+        accumulated_profit(pnl, capital.initial)
     }
 }
 
@@ -144,6 +178,8 @@ pub struct PnLToReportRequestBuilder {
     strategy: Option<String>,
     agg_years: Option<bool>,
     agg_markets: Option<bool>,
+    capital: Capital,
+    report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
 }
 
 impl PnLToReportRequestBuilder {
@@ -155,6 +191,8 @@ impl PnLToReportRequestBuilder {
             strategy: None,
             agg_years: None,
             agg_markets: None,
+            capital: Capital::default(),
+            report_columns: Vec::new(),
         }
     }
     pub fn with_pnl(self, pnl: DataFrame) -> Self {
@@ -199,6 +237,20 @@ impl PnLToReportRequestBuilder {
         }
     }
 
+    pub fn with_capital(self, capital: Capital) -> Self {
+        Self { capital, ..self }
+    }
+
+    pub fn with_report_columns(
+        self,
+        report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
+    ) -> Self {
+        Self {
+            report_columns,
+            ..self
+        }
+    }
+
     pub fn build(self) -> PnLToReportRequest {
         PnLToReportRequest {
             pnl: self.pnl.unwrap(),
@@ -207,6 +259,53 @@ impl PnLToReportRequestBuilder {
             strategy: self.strategy.unwrap(),
             agg_years: self.agg_years.unwrap(),
             agg_markets: self.agg_markets.unwrap(),
+            capital: self.capital,
+            report_columns: self.report_columns,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pnl() -> DataFrame {
+        df!(
+            &PnLReportColumnKind::Status.to_string() => &["Winner", "Loser", "NoEntry"],
+            &PnLReportColumnKind::PlDollar.to_string() => &[20.0, -10.0, 0.0],
+            &PnLReportColumnKind::ExpectedLossDollar.to_string() => &[-10.0, -10.0, -10.0],
+            &PnLReportColumnKind::RMultiple.to_string() => &[2.0, -1.0, 0.0],
+            &PnLReportColumnKind::TakeProfitTimestamp.to_string() => &["2022-01-10 14:00:00", "Timeout", "NoEntry"],
+            &PnLReportColumnKind::StopLossTimestamp.to_string() => &["Timeout", "2022-01-11 11:00:00", "NoEntry"],
+            &PnLReportColumnKind::ExitReason.to_string() => &["TakeProfit", "StopLoss", "NoEntry"],
+        )
+        .unwrap()
+    }
+
+    fn request() -> PnLToReportRequest {
+        PnLToReportRequestBuilder::new()
+            .with_pnl(pnl())
+            .with_year(2022)
+            .with_market(MarketKind::EurUsdFuture)
+            .with_strategy("PPP".to_string())
+            .is_agg_markets(false)
+            .is_agg_years(false)
+            .build()
+    }
+
+    #[test]
+    fn test_reports_match_schema() {
+        let id = PnLReportColumnKind::Id.to_string();
+        let performance_report = request()
+            .as_performance_report_df()
+            .with_row_count(&id, Some(1))
+            .unwrap();
+        assert_eq!(PerformanceReportColumnKind::schema(), performance_report.schema());
+
+        let trade_breakdown_report = request()
+            .as_trade_breakdown_df()
+            .with_row_count(&id, Some(1))
+            .unwrap();
+        assert_eq!(TradeBreakDownReportColumnKind::schema(), trade_breakdown_report.schema());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::{
+    converter::market_decimal_places::MyDecimalPlaces,
+    enums::column_names::{PerformanceReportColumnKind, TradeBreakDownReportColumnKind},
+};
+use polars::prelude::{DataFrame, DataType, NamedFrom, Series};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+use strum::IntoEnumIterator;
+
+/// Determines how the floating point columns of the performance and trade breakdown reports are
+/// rounded and formatted. The default is the precise mode, which keeps the raw values.
+/// * `performance_decimal_places` - the number of decimal places by performance report column,
+///   e.g. `"NetProfit": 2`
+/// * `trade_breakdown_decimal_places` - the number of decimal places by trade breakdown report
+///   column, e.g. `"TotalWin": 2`
+/// * `default_decimal_places` - the number of decimal places of all other floating point columns
+/// * `currency_symbol` - if set, dollar columns are exported as strings, e.g. `-$1,234.50`
+///
+/// Rounding keeps the `Float64` columns of the reports. Currency symbols are only applied when the
+/// reports are exported, see [`ReportFormat::export`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportFormat {
+    #[serde(default)]
+    pub performance_decimal_places: HashMap<PerformanceReportColumnKind, i32>,
+    #[serde(default)]
+    pub trade_breakdown_decimal_places: HashMap<TradeBreakDownReportColumnKind, i32>,
+    #[serde(default)]
+    pub default_decimal_places: Option<i32>,
+    #[serde(default)]
+    pub currency_symbol: Option<String>,
+}
+
+/// The columns of a report that can be formatted with a `ReportFormat`.
+pub trait FormattedColumnKind: IntoEnumIterator + Display + Copy + Eq + Hash {
+    fn decimal_places(format: &ReportFormat) -> &HashMap<Self, i32>;
+    fn is_currency(&self) -> bool;
+}
+
+impl FormattedColumnKind for PerformanceReportColumnKind {
+    fn decimal_places(format: &ReportFormat) -> &HashMap<Self, i32> {
+        &format.performance_decimal_places
+    }
+
+    fn is_currency(&self) -> bool {
+        matches!(
+            self,
+            Self::NetProfit
+                | Self::AvgWinnByTrade
+                | Self::MaxDrawDownAbs
+                | Self::AvgWin
+                | Self::AvgLoss
+                | Self::Expectancy
+                | Self::ExpectancyStdError
+        )
+    }
+}
+
+impl FormattedColumnKind for TradeBreakDownReportColumnKind {
+    fn decimal_places(format: &ReportFormat) -> &HashMap<Self, i32> {
+        &format.trade_breakdown_decimal_places
+    }
+
+    fn is_currency(&self) -> bool {
+        matches!(
+            self,
+            Self::TotalWin
+                | Self::TotalLoss
+                | Self::CleanWin
+                | Self::TimeoutWin
+                | Self::CleanLoss
+                | Self::TimeoutLoss
+                | Self::TakeProfitExitPnL
+                | Self::StopLossExitPnL
+                | Self::TimeoutExitPnL
+                | Self::SessionEndExitPnL
+                | Self::MaxHoldingPeriodExitPnL
+        )
+    }
+}
+
+impl ReportFormat {
+    pub fn is_precise(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn with_performance_decimal_places(
+        mut self,
+        column: PerformanceReportColumnKind,
+        decimal_places: i32,
+    ) -> Self {
+        self.performance_decimal_places
+            .insert(column, decimal_places);
+        self
+    }
+
+    pub fn with_trade_breakdown_decimal_places(
+        mut self,
+        column: TradeBreakDownReportColumnKind,
+        decimal_places: i32,
+    ) -> Self {
+        self.trade_breakdown_decimal_places
+            .insert(column, decimal_places);
+        self
+    }
+
+    pub fn with_default_decimal_places(self, default_decimal_places: i32) -> Self {
+        Self {
+            default_decimal_places: Some(default_decimal_places),
+            ..self
+        }
+    }
+
+    pub fn with_currency_symbol(self, currency_symbol: &str) -> Self {
+        Self {
+            currency_symbol: Some(currency_symbol.to_string()),
+            ..self
+        }
+    }
+
+    /// Rounds every `Float64` column of `report`, whose columns are of kind `C`. The columns keep
+    /// their `Float64` dtype.
+    pub fn round<C: FormattedColumnKind>(&self, report: &DataFrame) -> DataFrame {
+        self.map_float_columns::<C>(report, |series, _, decimal_places| match decimal_places {
+            Some(n) => round_column(series, n),
+            None => series.clone(),
+        })
+    }
+
+    /// Rounds `report` like [`ReportFormat::round`] and, if a currency symbol is set, formats its
+    /// dollar columns as strings. Hence, use this only to export a report, e.g. as CSV.
+    pub fn export<C: FormattedColumnKind>(&self, report: &DataFrame) -> DataFrame {
+        self.map_float_columns::<C>(report, |series, column, decimal_places| {
+            match (&self.currency_symbol, column) {
+                (Some(symbol), Some(column)) if column.is_currency() => {
+                    currency_column(series, decimal_places.unwrap_or(2), symbol)
+                }
+                _ => match decimal_places {
+                    Some(n) => round_column(series, n),
+                    None => series.clone(),
+                },
+            }
+        })
+    }
+
+    fn map_float_columns<C: FormattedColumnKind>(
+        &self,
+        report: &DataFrame,
+        f: impl Fn(&Series, Option<C>, Option<i32>) -> Series,
+    ) -> DataFrame {
+        let columns = report
+            .get_columns()
+            .iter()
+            .map(|series| match series.dtype() {
+                DataType::Float64 => {
+                    let column = C::iter().find(|c| c.to_string() == series.name());
+                    let decimal_places = column
+                        .and_then(|c| C::decimal_places(self).get(&c).copied())
+                        .or(self.default_decimal_places);
+                    f(series, column, decimal_places)
+                }
+                _ => series.clone(),
+            })
+            .collect::<Vec<_>>();
+        DataFrame::new(columns).unwrap()
+    }
+}
+
+fn round_column(series: &Series, decimal_places: i32) -> Series {
+    let rounded: Vec<Option<f64>> = series
+        .f64()
+        .unwrap()
+        .into_iter()
+        .map(|v| v.map(|v| v.round_to_n_decimal_places(decimal_places)))
+        .collect();
+    Series::new(series.name(), rounded)
+}
+
+fn currency_column(series: &Series, decimal_places: i32, symbol: &str) -> Series {
+    let formatted: Vec<Option<String>> = series
+        .f64()
+        .unwrap()
+        .into_iter()
+        .map(|v| v.map(|v| format_currency(v, decimal_places, symbol)))
+        .collect();
+    Series::new(series.name(), formatted)
+}
+
+/// Formats `value` with `decimal_places` and thousands separators, e.g. `-$1,234.50`.
+fn format_currency(value: f64, decimal_places: i32, symbol: &str) -> String {
+    if !value.is_finite() {
+        return format!("{symbol}{value}");
+    }
+    let formatted = format!(
+        "{:.*}",
+        decimal_places.max(0) as usize,
+        value.round_to_n_decimal_places(decimal_places).abs()
+    );
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, format!(".{fraction}")),
+        None => (formatted.as_str(), String::new()),
+    };
+    let mut grouped = String::new();
+    for (idx, digit) in integer.chars().enumerate() {
+        if idx > 0 && (integer.len() - idx) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        "-"
+    } else {
+        ""
+    };
+    format!("{sign}{symbol}{grouped}{fraction}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::df;
+
+    #[test]
+    fn test_format_currency() {
+        assert_eq!("-$1,234.50", format_currency(-1234.4999999, 2, "$"));
+        assert_eq!("$123", format_currency(123.4, 0, "$"));
+        assert_eq!("$1,000,000.0", format_currency(999_999.99, 1, "$"));
+        assert_eq!("$0.00", format_currency(-0.001, 2, "$"));
+    }
+
+    #[test]
+    fn test_apply_report_format() {
+        let report = df!(
+            "Year" => &["2022"],
+            "NetProfit" => &[13475.004999],
+            "ProfitFactor" => &[1.5432],
+            "MaxDrawDownRel" => &[-0.41234],
+        )
+        .unwrap();
+
+        let round = |format: &ReportFormat| format.round::<PerformanceReportColumnKind>(&report);
+        assert_eq!(report, round(&ReportFormat::default()));
+
+        let format = ReportFormat::default()
+            .with_default_decimal_places(2)
+            .with_performance_decimal_places(PerformanceReportColumnKind::MaxDrawDownRel, 3)
+            .with_currency_symbol("$");
+        let rounded = df!(
+            "Year" => &["2022"],
+            "NetProfit" => &[13475.0],
+            "ProfitFactor" => &[1.54],
+            "MaxDrawDownRel" => &[-0.412],
+        )
+        .unwrap();
+        assert_eq!(rounded, round(&format));
+
+        let exported = df!(
+            "Year" => &["2022"],
+            "NetProfit" => &["$13,475.00"],
+            "ProfitFactor" => &[1.54],
+            "MaxDrawDownRel" => &[-0.412],
+        )
+        .unwrap();
+        assert_eq!(
+            exported,
+            format.export::<PerformanceReportColumnKind>(&report)
+        );
+        // The decimal places of a trade breakdown report are keyed by its own column kinds
+        let rounded = df!(
+            "Year" => &["2022"],
+            "NetProfit" => &[13475.0],
+            "ProfitFactor" => &[1.54],
+            "MaxDrawDownRel" => &[-0.41],
+        )
+        .unwrap();
+        assert_eq!(
+            rounded,
+            format.round::<TradeBreakDownReportColumnKind>(&report)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_report_format() {
+        let format: ReportFormat = serde_json::from_str(
+            r#"{ "performance_decimal_places": { "NetProfit": 2 }, "currency_symbol": "$" }"#,
+        )
+        .unwrap();
+        let target = ReportFormat::default()
+            .with_performance_decimal_places(PerformanceReportColumnKind::NetProfit, 2)
+            .with_currency_symbol("$");
+        assert_eq!(target, format);
+        assert!(serde_json::from_str::<ReportFormat>(
+            r#"{ "performance_decimal_places": { "Unknown": 2 } }"#
+        )
+        .is_err());
+    }
+}
@@ -8,7 +8,7 @@ pub enum DataProviderKind {
     Cme,
 }
 
-#[derive(Copy, Clone, Debug, EnumString, Display)]
+#[derive(Copy, Clone, Debug, EnumString, Display, PartialEq, Eq, Hash)]
 pub enum TimeFrameKind {
     #[strum(serialize = "1w")]
     Weekly,
@@ -16,6 +16,36 @@ pub enum TimeFrameKind {
     Daily,
 }
 
+/// Determines which trading day a timestamp belongs to, when the data is grouped into daily
+/// candles.
+/// * `CalendarDay` - the UTC calendar day of the timestamp
+/// * `BusinessDay` - the exchange trading day of the timestamp. Following the CME convention, the
+///   session opening in the evening belongs to the next trading day, e.g. the Sunday evening
+///   session belongs to Monday. Sessions on weekends and holidays roll forward to the next
+///   business day.
+#[derive(Copy, Clone, Debug, Default, Display, EnumString, PartialEq, Eq, Hash)]
+pub enum PeriodKind {
+    #[default]
+    #[strum(serialize = "calendar-day")]
+    CalendarDay,
+    #[strum(serialize = "business-day")]
+    BusinessDay,
+}
+
+/// Determines how stop loss and take profit hits are resolved during a backtest.
+/// * `Candle` - hits are resolved on the candles of the market simulation data. If stop loss and
+///   take profit are reached within the same candle, the trade is conservatively treated as loser.
+/// * `TradeReplay` - the raw aggregated trades of each trading day are replayed to determine the
+///   exact fill of the entry and the exact order of stop loss and take profit hits. If no trade
+///   reaches the entry price, the trade is reported as `NoEntry`.
+#[derive(Copy, Clone, Debug, Display, EnumString, PartialEq)]
+pub enum ExecutionModeKind {
+    #[strum(serialize = "candle")]
+    Candle,
+    #[strum(serialize = "trade-replay")]
+    TradeReplay,
+}
+
 #[derive(Debug, Copy, Clone, Display, EnumString)]
 pub enum StopLossKind {
     #[strum(serialize = "PriceUponTradeEntry")]
@@ -1,4 +1,7 @@
-use strum_macros::{Display, EnumString};
+use polars::prelude::{DataType, Field, Schema};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
 
 #[derive(Copy, Clone, Debug, Display, EnumString)]
 pub enum DataProviderColumnKind {
@@ -52,7 +55,19 @@ pub enum VolumeProfileColumnKind {
     Quantity = 1,
 }
 
-#[derive(Copy, Clone, Debug, Display)]
+#[derive(Copy, Clone, Debug, Display, EnumString)]
+pub enum SimulationArtifactsReportColumnKind {
+    Artifacts = 0,
+}
+
+#[derive(Copy, Clone, Debug, Display, EnumString)]
+pub enum RollCalendarColumnKind {
+    Date = 0,
+    Contract = 1,
+    Volume = 2,
+}
+
+#[derive(Copy, Clone, Debug, Display, EnumIter)]
 pub enum PnLReportColumnKind {
     Uid = 0,
     Id = 1,
@@ -76,14 +91,18 @@ pub enum PnLReportColumnKind {
     Status = 19,
     PlTick = 20,
     PlDollar = 21,
+    /// The `TerminationReason` of the trade or `NoEntry`. Added after `PlDollar`, hence the
+    /// position of all following columns changed.
+    ExitReason = 22,
+    RMultiple = 23,
 }
 
-#[derive(Copy, Clone, Debug, Display)]
+#[derive(Copy, Clone, Debug, Display, EnumIter, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PerformanceReportColumnKind {
     Id = 0,
     Year = 1,
-    Strategy = 2,
-    Market = 3,
+    Market = 2,
+    Strategy = 3,
     NetProfit = 4,
     AvgWinnByTrade = 5,
     MaxDrawDownAbs = 6,
@@ -93,14 +112,17 @@ pub enum PerformanceReportColumnKind {
     AvgWin = 10,
     AvgLoss = 11,
     ProfitFactor = 12,
+    Expectancy = 13,
+    ExpectancyStdError = 14,
+    KellyFraction = 15,
 }
 
-#[derive(Copy, Clone, Debug, Display)]
+#[derive(Copy, Clone, Debug, Display, EnumIter, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TradeBreakDownReportColumnKind {
     Id = 0,
     Year = 1,
-    Strategy = 2,
-    Market = 3,
+    Market = 2,
+    Strategy = 3,
     TotalWin = 4,
     TotalLoss = 5,
     CleanWin = 6,
@@ -116,4 +138,139 @@ pub enum TradeBreakDownReportColumnKind {
     NumberTimeoutLoserTrades = 16,
     NumberTimeoutTrades = 17,
     NumberNoEntry = 18,
+    NumberTakeProfitExits = 19,
+    TakeProfitExitPnL = 20,
+    NumberStopLossExits = 21,
+    StopLossExitPnL = 22,
+    NumberTimeoutExits = 23,
+    TimeoutExitPnL = 24,
+    NumberSessionEndExits = 25,
+    SessionEndExitPnL = 26,
+    NumberMaxHoldingPeriodExits = 27,
+    MaxHoldingPeriodExitPnL = 28,
+    AvgRMultiple = 29,
+    RExpectancy = 30,
+    PercentageTradesAbove2R = 31,
+}
+
+impl PnLReportColumnKind {
+    pub fn dtype(&self) -> DataType {
+        match self {
+            Self::Uid | Self::Id => DataType::UInt32,
+            Self::CalendarWeek => DataType::Int64,
+            Self::Date
+            | Self::Strategy
+            | Self::Market
+            | Self::TradeDirection
+            | Self::EntryTimestamp
+            | Self::TakeProfitTimestamp
+            | Self::StopLossTimestamp
+            | Self::Status
+            | Self::ExitReason => DataType::Utf8,
+            _ => DataType::Float64,
+        }
+    }
+
+    /// The ordered columns of a profit and loss report, without custom `ReportColumn`s.
+    pub fn schema() -> Schema {
+        Self::iter()
+            .map(|c| Field::new(&c.to_string(), c.dtype()))
+            .collect()
+    }
+}
+
+impl PerformanceReportColumnKind {
+    pub fn dtype(&self) -> DataType {
+        match self {
+            Self::Id => DataType::UInt32,
+            Self::Year | Self::Market | Self::Strategy => DataType::Utf8,
+            _ => DataType::Float64,
+        }
+    }
+
+    /// The ordered columns of a performance report.
+    pub fn schema() -> Schema {
+        Self::iter()
+            .map(|c| Field::new(&c.to_string(), c.dtype()))
+            .collect()
+    }
+}
+
+impl TradeBreakDownReportColumnKind {
+    pub fn dtype(&self) -> DataType {
+        match self {
+            Self::Id
+            | Self::TotalNumberWinnerTrades
+            | Self::TotalNumberLoserTrades
+            | Self::TotalNumberTrades
+            | Self::NumberWinnerTrades
+            | Self::NumberLoserTrades
+            | Self::NumberTimeoutWinnerTrades
+            | Self::NumberTimeoutLoserTrades
+            | Self::NumberTimeoutTrades
+            | Self::NumberNoEntry
+            | Self::NumberTakeProfitExits
+            | Self::NumberStopLossExits
+            | Self::NumberTimeoutExits
+            | Self::NumberSessionEndExits
+            | Self::NumberMaxHoldingPeriodExits => DataType::UInt32,
+            Self::Year | Self::Market | Self::Strategy => DataType::Utf8,
+            _ => DataType::Float64,
+        }
+    }
+
+    /// The ordered columns of a trade breakdown report, without custom `ReportColumn`s.
+    pub fn schema() -> Schema {
+        Self::iter()
+            .map(|c| Field::new(&c.to_string(), c.dtype()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column_names(schema: Schema) -> Vec<String> {
+        schema.iter_names().map(|name| name.to_string()).collect()
+    }
+
+    /// Renaming, removing or reordering a column breaks downstream pipelines. New columns must be
+    /// appended at the end.
+    #[test]
+    fn test_report_schemas_are_stable() {
+        assert_eq!(
+            vec![
+                "Uid", "Id", "CalendarWeek", "Date", "Strategy", "Market", "TradeDirection",
+                "Entry", "TakeProfit", "StopLoss", "ExpectedWinTick", "ExpectedLossTick",
+                "ExpectedWinDollar", "ExpectedLossDollar", "Crv", "EntryTimestamp",
+                "TakeProfitTimestamp", "StopLossTimestamp", "ExitPrice", "Status", "PlTick",
+                "PlDollar", "ExitReason", "RMultiple",
+            ],
+            column_names(PnLReportColumnKind::schema())
+        );
+        assert_eq!(
+            vec![
+                "Id", "Year", "Market", "Strategy", "NetProfit", "AvgWinnByTrade",
+                "MaxDrawDownAbs", "MaxDrawDownRel", "PercentageProfitability",
+                "RatioAvgWinByAvgLoss", "AvgWin", "AvgLoss", "ProfitFactor", "Expectancy",
+                "ExpectancyStdError", "KellyFraction",
+            ],
+            column_names(PerformanceReportColumnKind::schema())
+        );
+        assert_eq!(
+            vec![
+                "Id", "Year", "Market", "Strategy", "TotalWin", "TotalLoss", "CleanWin",
+                "TimeoutWin", "CleanLoss", "TimeoutLoss", "TotalNumberWinnerTrades",
+                "TotalNumberLoserTrades", "TotalNumberTrades", "NumberWinnerTrades",
+                "NumberLoserTrades", "NumberTimeoutWinnerTrades", "NumberTimeoutLoserTrades",
+                "NumberTimeoutTrades", "NumberNoEntry", "NumberTakeProfitExits",
+                "TakeProfitExitPnL", "NumberStopLossExits", "StopLossExitPnL",
+                "NumberTimeoutExits", "TimeoutExitPnL", "NumberSessionEndExits",
+                "SessionEndExitPnL", "NumberMaxHoldingPeriodExits", "MaxHoldingPeriodExitPnL",
+                "AvgRMultiple", "RExpectancy", "PercentageTradesAbove2R",
+            ],
+            column_names(TradeBreakDownReportColumnKind::schema())
+        );
+    }
 }
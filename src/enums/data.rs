@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
 use super::{
@@ -5,7 +6,7 @@ use super::{
     indicator::{PriceHistogramKind, TradingIndicatorKind},
 };
 
-#[derive(Copy, Clone, Debug, EnumString, PartialEq, Display)]
+#[derive(Copy, Clone, Debug, EnumString, PartialEq, Eq, Hash, Display)]
 pub enum MarketSimulationDataKind {
     #[strum(serialize = "ohlc-1m")]
     Ohlc1m,
@@ -26,6 +27,19 @@ pub enum MarketSimulationDataKind {
     Ohlcv1h,
 }
 
+/// Compression of cached computations, e.g. `{ "Zstd": { "level": 3 } }` in a `BotConfig`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CompressionKind {
+    #[default]
+    None,
+    Zstd {
+        level: i32,
+    },
+    Lz4 {
+        level: u32,
+    },
+}
+
 #[derive(Copy, Clone, Debug, EnumString, PartialEq)]
 
 pub enum TickDataKind {
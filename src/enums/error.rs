@@ -3,12 +3,26 @@ use tokio::task::JoinError;
 #[derive(Debug, Clone)]
 pub enum ChapatyErrorKind {
     ParseBotError(String),
+    ParseBotConfigError(String),
     ParseDataProducerError(String),
     BuildBotError(String),
     FailedToFetchDataFrameFromMap(String),
     FailedToJoinFuturesInProfitAndLossComputation(String),
     FileNotFound(String),
     UnknownGoogleCloudStorageError(String),
+    FailedToSendNotification(String),
+    ParseNotificationTemplateError(String),
+    FailedToTrackExperiment(String),
+    FailedToExportDataset(String),
+    IncompatibleSerializationVersion(String),
+    CorruptCachedData(String),
+    FailedToSerializeCachedData(String),
+    FailedToWriteStreamingReport(String),
+    FailedToComputeRollCalendar(String),
+    FailedToRecordGoldenFile(String),
+    GoldenFileMismatch(String),
+    InvalidReportColumn(String),
+    MissingReportColumn(String),
 }
 
 impl From<JoinError> for ChapatyErrorKind {
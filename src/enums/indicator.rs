@@ -1,14 +1,124 @@
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString, FromRepr};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TradingIndicatorKind {
     Poc(PriceHistogramKind),
     ValueAreaLow(PriceHistogramKind),
     ValueAreaHigh(PriceHistogramKind),
 }
 
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Determines how an aggregated trade is weighted in the order flow imbalance.
+/// * `Quantity` - by its quantity
+/// * `Fills` - by the number of resting orders it was filled against, i.e. `ltid - ftid + 1`, such
+///   that orders sweeping deep into the order book count more than fills at the top of the book
+#[derive(
+    Copy, Clone, Debug, Default, Display, EnumString, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum ExecutionDepthKind {
+    #[default]
+    #[strum(serialize = "quantity")]
+    Quantity,
+    #[strum(serialize = "fills")]
+    Fills,
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PriceHistogramKind {
     Tpo1m,
     Tpo1h,
     VolTick,
     VolAggTrades,
 }
+
+/// Determines which price is the POC if several prices of a price histogram share the maximum
+/// volume.
+/// * `Lowest` - the lowest price
+/// * `Highest` - the highest price
+/// * `ClosestToClose` - the price closest to the last close of the market simulation data
+/// * `MostRecentlyTraded` - the price that was traded last according to the market simulation data
+/// * `MidpointOfPocCluster` - the price closest to the midpoint of the lowest and highest price
+#[derive(Copy, Clone, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+pub enum PocSelectionRule {
+    #[strum(serialize = "Lowest")]
+    Lowest,
+    #[strum(serialize = "Highest")]
+    Highest,
+    #[strum(serialize = "ClosestToClose")]
+    ClosestToClose,
+    #[strum(serialize = "MostRecentlyTraded")]
+    MostRecentlyTraded,
+    #[strum(serialize = "MidpointOfPocCluster")]
+    MidpointOfPocCluster,
+}
+
+/// Determines how the value area is expanded around the POC until it contains the requested share
+/// of the volume.
+/// * `ClassicTwoRow` - compares the volume of the next two rows above and below the value area and
+///   adds the two rows with the larger volume
+/// * `GreedySingleBin` - compares the volume of the next row above and below the value area and
+///   adds the row with the larger volume
+/// * `PercentOfPriceRange` - the value area spans the requested share of the price range of the
+///   price histogram, centered around the POC
+#[derive(Copy, Clone, Debug, Default, Display, EnumString, PartialEq, Eq, Hash)]
+pub enum ValueAreaRule {
+    #[default]
+    #[strum(serialize = "ClassicTwoRow")]
+    ClassicTwoRow,
+    #[strum(serialize = "GreedySingleBin")]
+    GreedySingleBin,
+    #[strum(serialize = "PercentOfPriceRange")]
+    PercentOfPriceRange,
+}
+
+/// Classification of a completed session by the shape of its market profile.
+/// * `Trend` - the session opened near one extreme of its range and closed near the other one
+/// * `DoubleDistribution` - the profile has two areas of high activity, separated by a thin area
+/// * `Balance` - any other session, e.g. a session rotating around a single area of value
+#[derive(Copy, Clone, Debug, Display, EnumString, FromRepr, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum DayTypeKind {
+    Trend = 0,
+    DoubleDistribution = 1,
+    Balance = 2,
+}
+
+/// Determines how the pivot point levels are computed from the high, low and close of the prior
+/// period.
+/// * `Classic` - floor trader pivots, where `R1 = 2 * PP - L` and `S1 = 2 * PP - H`
+/// * `Camarilla` - levels at `C +/- (H - L) * 1.1 / 12`, `/ 6` and `/ 4`
+#[derive(Copy, Clone, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+pub enum PivotPointKind {
+    Classic,
+    Camarilla,
+}
+
+/// A single pivot point level, where `Pp = (H + L + C) / 3` for both `PivotPointKind`s.
+#[derive(Copy, Clone, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+pub enum PivotLevelKind {
+    Pp,
+    R1,
+    R2,
+    R3,
+    S1,
+    S2,
+    S3,
+}
+
+/// A price action pattern of the last candle of the pre-trade market data. Two-candle patterns
+/// compare it with the candle before.
+/// * `BullishEngulfing` - a bearish candle followed by a bullish candle whose body covers the body
+///   of the bearish candle
+/// * `BearishEngulfing` - a bullish candle followed by a bearish candle whose body covers the body
+///   of the bullish candle
+/// * `InsideBar` - the high is below and the low is above the ones of the candle before
+/// * `Doji` - the body is at most 10% of the range
+/// * `PinBar` - the upper or lower wick is at least two thirds of the range
+#[derive(Copy, Clone, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+pub enum CandlePatternKind {
+    BullishEngulfing,
+    BearishEngulfing,
+    InsideBar,
+    Doji,
+    PinBar,
+}
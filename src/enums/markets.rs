@@ -1,3 +1,4 @@
+use crate::converter::fixed_point::ticks_to_price;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
@@ -69,15 +70,16 @@ impl MarketKind {
     }
 
     pub fn try_offset_in_tick(&self, offset: f64) -> f64 {
+        let tick_size = self.tick_step_size();
         match self {
             MarketKind::BtcUsdt => offset,
-            MarketKind::EurUsdFuture => offset / 6.25 * self.tick_step_size().unwrap(),
-            MarketKind::AudUsdFuture => offset / 5.0 * self.tick_step_size().unwrap(),
-            MarketKind::GbpUsdFuture => offset / 6.25 * self.tick_step_size().unwrap(),
-            MarketKind::CadUsdFuture => offset / 5.0 * self.tick_step_size().unwrap(),
-            MarketKind::YenUsdFuture => offset / 6.25 * self.tick_step_size().unwrap(),
-            MarketKind::NzdUsdFuture => offset / 5.0 * self.tick_step_size().unwrap(),
-            MarketKind::BtcUsdFuture => offset / 25.0 * self.tick_step_size().unwrap(),
+            MarketKind::EurUsdFuture => ticks_to_price(offset / 6.25, tick_size.unwrap()),
+            MarketKind::AudUsdFuture => ticks_to_price(offset / 5.0, tick_size.unwrap()),
+            MarketKind::GbpUsdFuture => ticks_to_price(offset / 6.25, tick_size.unwrap()),
+            MarketKind::CadUsdFuture => ticks_to_price(offset / 5.0, tick_size.unwrap()),
+            MarketKind::YenUsdFuture => ticks_to_price(offset / 6.25, tick_size.unwrap()),
+            MarketKind::NzdUsdFuture => ticks_to_price(offset / 5.0, tick_size.unwrap()),
+            MarketKind::BtcUsdFuture => ticks_to_price(offset / 25.0, tick_size.unwrap()),
         }
     }
 }
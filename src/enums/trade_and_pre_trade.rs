@@ -1,3 +1,7 @@
+use super::{
+    bot::TimeFrameKind,
+    indicator::{CandlePatternKind, ExecutionDepthKind, PivotLevelKind, PivotPointKind},
+};
 use strum_macros::Display;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -11,11 +15,81 @@ pub enum TradeDataKind {
     HighestTradePriceSinceEntryTimestamp,
     InitialBalance,
 }
+/// * `Gap` - the first open of the session minus the last close of the prior session. The first
+///   session of the year has no prior session, hence it is not backtested
+/// * `GapFillProbability` - the share of gaps of the prior sessions in the same year that were
+///   filled, i.e. the price traded back to the close of the prior session
+/// * `DayType` - the `DayTypeKind` of the prior session, encoded as its discriminant
+/// * `PreviousWeekHigh`, `PreviousWeekLow`, `PreviousWeekClose` - the levels of the calendar week
+///   before the session, independent of the time frame. Sessions in the first week of the data
+///   have no previous week, hence they are not backtested
+/// * `PreviousMonthHigh`, `PreviousMonthLow`, `PreviousMonthClose` - the levels of the calendar
+///   month before the session, independent of the time frame. The data of a backtest covers a
+///   single year, hence sessions in January are not backtested
+/// * `PivotPoint` - a pivot point level computed from the prior session for `Daily`, or from the
+///   previous calendar week for `Weekly`, which is not available in the first week
+/// * `CandlePattern` - `1.0` if the candle of the prior session forms the pattern, `0.0`
+///   otherwise. Each session is aggregated into one candle, and two-candle patterns compare the
+///   prior session with the session before
+/// * `OrderFlowImbalance` - `(buy - sell) / (buy + sell)` of the aggressor volume of the prior
+///   session's aggregated trades within the last `window_minutes` before the session end, or of
+///   the whole session if `None`. The value is `0.0` if nothing was traded in the window
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum PreTradeDataKind {
     LastTradePrice,
     LowestTradePrice,
     HighestTradePrice,
+    Gap,
+    GapFillProbability,
+    DayType,
+    PreviousWeekHigh,
+    PreviousWeekLow,
+    PreviousWeekClose,
+    PreviousMonthHigh,
+    PreviousMonthLow,
+    PreviousMonthClose,
+    PivotPoint {
+        time_frame: TimeFrameKind,
+        kind: PivotPointKind,
+        level: PivotLevelKind,
+    },
+    CandlePattern(CandlePatternKind),
+    OrderFlowImbalance {
+        window_minutes: Option<u32>,
+        execution_depth: ExecutionDepthKind,
+    },
+}
+
+/// Determines why a trade was closed.
+/// * `TakeProfit` - the take profit was reached
+/// * `StopLoss` - the stop loss was reached, or it is not clear if the stop loss or take profit
+///   was reached first
+/// * `Timeout` - neither stop loss nor take profit were reached until the end of the available
+///   market data
+/// * `MaxHoldingPeriod` - the trade was closed after the maximum holding period of the strategy
+/// * `SessionEnd` - the trade was closed at the end of the trading session, which requires
+///   `BotBuilder::with_flat_at_session_end`
+#[derive(Debug, Copy, Clone, Display, PartialEq, Eq, Hash)]
+pub enum TerminationReason {
+    TakeProfit,
+    StopLoss,
+    Timeout,
+    MaxHoldingPeriod,
+    SessionEnd,
+}
+
+/// Suspicious trades of a backtest that hint at a bug in the strategy logic or at unrealistic
+/// execution assumptions.
+/// * `SameBarExit` - the trade was entered and exited within the same candle
+/// * `ImmediateStopLoss` - the stop loss was hit within the entry candle and was at most one tick
+///   away from the entry
+/// * `AmbiguousExit` - stop loss and take profit were reached within the same candle, hence the
+///   trade was conservatively treated as loser
+#[derive(Debug, Copy, Clone, Display, PartialEq, Eq, Hash)]
+pub enum SimulationArtifactKind {
+    SameBarExit,
+    ImmediateStopLoss,
+    AmbiguousExit,
 }
 
 #[derive(Debug, Copy, Clone, Display, PartialEq)]
@@ -1,9 +1,137 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, hash::Hash};
 
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::MarketKind;
 
+/// The capital the equity curves start from.
+/// * `initial` - the initial capital in dollar
+/// * `is_compounding` - if `true`, the position size scales with the current equity, i.e. the PnL
+///   of a trade is scaled by `equity / initial`. Otherwise, every trade has the fixed position size
+///   of the backtest.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct Capital {
+    pub initial: f64,
+    pub is_compounding: bool,
+}
+
+impl Capital {
+    pub fn new(initial: f64, is_compounding: bool) -> Self {
+        Self {
+            initial,
+            is_compounding,
+        }
+    }
+
+    /// Compounding requires a positive initial capital.
+    pub fn is_valid(&self) -> bool {
+        self.initial >= 0.0 && (!self.is_compounding || self.initial > 0.0)
+    }
+}
+
+/// Simulates alternative equity curves by resampling the order of the trades of an equity curve.
+/// * `number_of_simulations` - the number of resampled equity curves
+/// * `seed` - the seed of the random number generator, such that the bands are reproducible
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct MonteCarloSimulation {
+    pub number_of_simulations: usize,
+    pub seed: u64,
+}
+
+impl Default for MonteCarloSimulation {
+    fn default() -> Self {
+        Self {
+            number_of_simulations: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// The 5%, 50% and 95% percentiles of the simulated equity after every trade.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EquityCurveBands {
+    pub lower: Vec<f64>,
+    pub median: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+impl MonteCarloSimulation {
+    pub fn new(number_of_simulations: usize, seed: u64) -> Self {
+        Self {
+            number_of_simulations,
+            seed,
+        }
+    }
+
+    /// Computes the percentile bands of `equity_curve`. With compounding, the returns of the trades
+    /// are resampled instead of their PnL. Trades after the ruin of the account, i.e. at an equity
+    /// that is not positive, have no return, and a simulated account stops trading at its ruin.
+    /// Returns empty bands if `equity_curve` is empty.
+    pub fn compute_bands(&self, equity_curve: &[f64], capital: &Capital) -> EquityCurveBands {
+        let Some(&initial_equity) = equity_curve.first() else {
+            return EquityCurveBands {
+                lower: Vec::new(),
+                median: Vec::new(),
+                upper: Vec::new(),
+            };
+        };
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut steps: Vec<f64> = equity_curve
+            .windows(2)
+            .map(|w| match capital.is_compounding {
+                true if w[0] <= 0.0 => 1.0,
+                true => w[1] / w[0],
+                false => w[1] - w[0],
+            })
+            .collect();
+
+        let simulations: Vec<Vec<f64>> = (0..self.number_of_simulations)
+            .map(|_| {
+                steps.shuffle(&mut rng);
+                simulate_equity_curve(initial_equity, &steps, capital.is_compounding)
+            })
+            .collect();
+
+        let mut bands = EquityCurveBands {
+            lower: Vec::with_capacity(equity_curve.len()),
+            median: Vec::with_capacity(equity_curve.len()),
+            upper: Vec::with_capacity(equity_curve.len()),
+        };
+        for i in 0..equity_curve.len() {
+            let mut equities: Vec<f64> = simulations.iter().map(|curve| curve[i]).collect();
+            if equities.is_empty() {
+                equities.push(equity_curve[i]);
+            }
+            equities.sort_by(f64::total_cmp);
+            bands.lower.push(percentile(&equities, 0.05));
+            bands.median.push(percentile(&equities, 0.5));
+            bands.upper.push(percentile(&equities, 0.95));
+        }
+        bands
+    }
+}
+
+fn simulate_equity_curve(initial: f64, steps: &[f64], is_compounding: bool) -> Vec<f64> {
+    steps.iter().fold(vec![initial], |mut acc, step| {
+        let equity = *acc.last().unwrap();
+        if is_compounding && equity <= 0.0 {
+            acc.push(equity);
+        } else if is_compounding {
+            acc.push(equity * step);
+        } else {
+            acc.push(equity + step);
+        }
+        acc
+    })
+}
+
+/// Nearest-rank percentile of the ascending sorted `values`.
+fn percentile(values: &[f64], p: f64) -> f64 {
+    let idx = ((values.len() - 1) as f64 * p).round() as usize;
+    values[idx]
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EquityCurvesAggMarkets {
     pub markets: Vec<MarketKind>,
@@ -29,4 +157,116 @@ pub struct EquityCurves {
     pub market: MarketKind,
     pub years: Vec<u32>,
     pub curves: HashMap<u32, Vec<f64>>,
-}
\ No newline at end of file
+}
+
+impl EquityCurvesAggMarkets {
+    pub fn compute_bands(
+        &self,
+        simulation: &MonteCarloSimulation,
+        capital: &Capital,
+    ) -> HashMap<u32, EquityCurveBands> {
+        compute_bands_by_key(&self.curves, simulation, capital)
+    }
+}
+
+impl EquityCurvesAggYears {
+    pub fn compute_bands(
+        &self,
+        simulation: &MonteCarloSimulation,
+        capital: &Capital,
+    ) -> HashMap<MarketKind, EquityCurveBands> {
+        compute_bands_by_key(&self.curves, simulation, capital)
+    }
+}
+
+impl EquityCurvesReport {
+    pub fn compute_bands(
+        &self,
+        simulation: &MonteCarloSimulation,
+        capital: &Capital,
+    ) -> HashMap<MarketKind, HashMap<u32, EquityCurveBands>> {
+        self.curves
+            .iter()
+            .map(|(market, curves)| (*market, curves.compute_bands(simulation, capital)))
+            .collect()
+    }
+}
+
+impl EquityCurves {
+    pub fn compute_bands(
+        &self,
+        simulation: &MonteCarloSimulation,
+        capital: &Capital,
+    ) -> HashMap<u32, EquityCurveBands> {
+        compute_bands_by_key(&self.curves, simulation, capital)
+    }
+}
+
+fn compute_bands_by_key<K: Copy + Eq + Hash>(
+    curves: &HashMap<K, Vec<f64>>,
+    simulation: &MonteCarloSimulation,
+    capital: &Capital,
+) -> HashMap<K, EquityCurveBands> {
+    curves
+        .iter()
+        .map(|(key, curve)| (*key, simulation.compute_bands(curve, capital)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_bands() {
+        let curve = vec![0.0, 10.0, 5.0, 25.0, 15.0];
+        let simulation = MonteCarloSimulation::new(200, 42);
+        let bands = simulation.compute_bands(&curve, &Capital::default());
+
+        assert_eq!(bands, simulation.compute_bands(&curve, &Capital::default()));
+        assert_eq!(curve.len(), bands.median.len());
+        assert_eq!(0.0, bands.lower[0]);
+        assert_eq!(15.0, bands.lower[4]);
+        assert_eq!(15.0, bands.upper[4]);
+        assert!(bands
+            .lower
+            .iter()
+            .zip(bands.median.iter().zip(bands.upper.iter()))
+            .all(|(lower, (median, upper))| lower <= median && median <= upper));
+    }
+
+    #[test]
+    fn test_compute_bands_of_empty_equity_curve() {
+        let bands = MonteCarloSimulation::new(10, 42).compute_bands(&[], &Capital::default());
+
+        assert!(bands.lower.is_empty());
+        assert!(bands.median.is_empty());
+        assert!(bands.upper.is_empty());
+    }
+
+    #[test]
+    fn test_compute_bands_with_compounding() {
+        let curve = vec![100.0, 110.0, 121.0, 133.1];
+        let capital = Capital::new(100.0, true);
+        let bands = MonteCarloSimulation::new(50, 7).compute_bands(&curve, &capital);
+
+        for (target, band) in curve.iter().zip(bands.median) {
+            assert!((target - band).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_compute_bands_with_compounding_after_ruin() {
+        let curve = vec![100.0, 50.0, -10.0, -10.0, -10.0];
+        let capital = Capital::new(100.0, true);
+        let bands = MonteCarloSimulation::new(50, 7).compute_bands(&curve, &capital);
+
+        assert!(bands
+            .lower
+            .iter()
+            .chain(bands.median.iter())
+            .chain(bands.upper.iter())
+            .all(|equity| equity.is_finite()));
+        assert!(bands.median[4] <= 0.0);
+    }
+}
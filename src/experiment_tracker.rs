@@ -0,0 +1,117 @@
+pub mod file;
+pub mod http;
+use crate::{
+    backtest_result::BacktestResult, cloud_api::path_finder::fingerprint, config::BotConfig,
+    enums::error::ChapatyErrorKind,
+};
+use async_trait::async_trait;
+use polars::prelude::DataFrame;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf, process::Command};
+
+/// A single tracked backtest run. Runs with the same `config_hash` were computed with the same
+/// `BotConfig` and are therefore comparable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentRun {
+    pub run_id: String,
+    pub config_hash: String,
+    pub git_revision: Option<String>,
+    pub config: serde_json::Value,
+    pub metrics: BTreeMap<String, f64>,
+    pub artifacts: Vec<PathBuf>,
+}
+
+/// An `ExperimentTracker` persists `ExperimentRun`s, e.g. to the local file system or to a remote
+/// tracking server.
+#[async_trait]
+pub trait ExperimentTracker {
+    async fn log_run(&self, run: &ExperimentRun) -> Result<(), ChapatyErrorKind>;
+}
+
+impl ExperimentRun {
+    /// Creates a run for a backtest of the bot described by `config`. The metrics are taken from
+    /// the performance report aggregated over all markets and years.
+    pub fn new(config: &BotConfig, backtest_result: &BacktestResult) -> Self {
+        let config = serde_json::to_value(config).unwrap();
+        let config_hash = config_hash(&config);
+        let run_id = format!("{config_hash}-{}", chrono::Utc::now().timestamp_millis());
+        Self {
+            run_id,
+            config_hash,
+            git_revision: git_revision(),
+            config,
+            metrics: metrics_from_performance_report(
+                &backtest_result.agg_market_and_agg_year.performance_report,
+            ),
+            artifacts: Vec::new(),
+        }
+    }
+
+    pub fn with_artifacts(self, artifacts: Vec<PathBuf>) -> Self {
+        Self { artifacts, ..self }
+    }
+}
+
+/// Computes a stable hash of the serialized config.
+fn config_hash(config: &serde_json::Value) -> String {
+    format!("{:016x}", fingerprint(config.to_string().bytes()))
+}
+
+fn git_revision() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn metrics_from_performance_report(performance_report: &DataFrame) -> BTreeMap<String, f64> {
+    performance_report
+        .get_columns()
+        .iter()
+        .filter(|series| series.dtype().is_float())
+        .filter_map(|series| {
+            let value = series.get(0).ok()?.extract::<f64>()?;
+            Some((series.name().to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::column_names::PerformanceReportColumnKind;
+    use polars::{df, prelude::NamedFrom};
+
+    #[test]
+    fn test_config_hash_is_stable() {
+        let config = serde_json::json!({ "name": "chapaty", "years": [2022] });
+
+        assert_eq!(config_hash(&config), config_hash(&config.clone()));
+        assert_ne!(
+            config_hash(&config),
+            config_hash(&serde_json::json!({ "name": "chapaty", "years": [2023] }))
+        );
+    }
+
+    #[test]
+    fn test_metrics_from_performance_report() {
+        let performance_report = df!(
+            &PerformanceReportColumnKind::Year.to_string() => &[2022_u32],
+            &PerformanceReportColumnKind::Strategy.to_string() => &["ppp"],
+            &PerformanceReportColumnKind::NetProfit.to_string() => &[13475.0],
+            &PerformanceReportColumnKind::ProfitFactor.to_string() => &[1.54],
+        )
+        .unwrap();
+
+        let target = BTreeMap::from([
+            (PerformanceReportColumnKind::NetProfit.to_string(), 13475.0),
+            (PerformanceReportColumnKind::ProfitFactor.to_string(), 1.54),
+        ]);
+
+        assert_eq!(target, metrics_from_performance_report(&performance_report));
+    }
+}
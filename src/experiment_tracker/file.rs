@@ -0,0 +1,81 @@
+use super::{ExperimentRun, ExperimentTracker};
+use crate::enums::error::ChapatyErrorKind;
+use async_trait::async_trait;
+use std::{fs, path::PathBuf};
+
+/// This struct stores every `ExperimentRun` in its own directory `{root_dir}/{run_id}`. The run is
+/// written to `run.json` and all artifacts are copied into the `artifacts` subdirectory.
+#[derive(Debug, Clone)]
+pub struct FileExperimentTracker {
+    root_dir: PathBuf,
+}
+
+impl FileExperimentTracker {
+    pub fn new(root_dir: PathBuf) -> Self {
+        Self { root_dir }
+    }
+}
+
+#[async_trait]
+impl ExperimentTracker for FileExperimentTracker {
+    async fn log_run(&self, run: &ExperimentRun) -> Result<(), ChapatyErrorKind> {
+        let run_dir = self.root_dir.join(&run.run_id);
+        let artifacts_dir = run_dir.join("artifacts");
+        fs::create_dir_all(&artifacts_dir).map_err(to_tracking_error)?;
+
+        let run_as_json = serde_json::to_string_pretty(run).map_err(to_tracking_error)?;
+        fs::write(run_dir.join("run.json"), run_as_json).map_err(to_tracking_error)?;
+
+        for artifact in &run.artifacts {
+            let file_name = artifact.file_name().ok_or_else(|| {
+                ChapatyErrorKind::FailedToTrackExperiment(format!(
+                    "Artifact <{}> is not a file",
+                    artifact.display()
+                ))
+            })?;
+            fs::copy(artifact, artifacts_dir.join(file_name)).map_err(to_tracking_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn to_tracking_error(e: impl ToString) -> ChapatyErrorKind {
+    ChapatyErrorKind::FailedToTrackExperiment(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_log_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let root_dir = dir.path().join("experiments");
+        let artifact = dir.path().join("chapaty_test_artifact.csv");
+        fs::write(&artifact, "a,b\n1,2\n").unwrap();
+
+        let run = ExperimentRun {
+            run_id: "run-1".to_string(),
+            config_hash: "0123456789abcdef".to_string(),
+            git_revision: None,
+            config: serde_json::json!({ "name": "chapaty" }),
+            metrics: BTreeMap::from([("NetProfit".to_string(), 100.0)]),
+            artifacts: vec![artifact],
+        };
+
+        FileExperimentTracker::new(root_dir.clone())
+            .log_run(&run)
+            .await
+            .unwrap();
+
+        let logged: ExperimentRun =
+            serde_json::from_str(&fs::read_to_string(root_dir.join("run-1/run.json")).unwrap())
+                .unwrap();
+        assert_eq!(run, logged);
+        assert!(root_dir
+            .join("run-1/artifacts/chapaty_test_artifact.csv")
+            .exists());
+    }
+}
@@ -0,0 +1,37 @@
+use super::{ExperimentRun, ExperimentTracker};
+use crate::enums::error::ChapatyErrorKind;
+use async_trait::async_trait;
+
+/// This struct posts every `ExperimentRun` as JSON to the endpoint of a tracking server.
+/// Artifacts are only referenced by their path and are not uploaded.
+#[derive(Clone)]
+pub struct HttpExperimentTracker {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpExperimentTracker {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait]
+impl ExperimentTracker for HttpExperimentTracker {
+    async fn log_run(&self, run: &ExperimentRun) -> Result<(), ChapatyErrorKind> {
+        let body = serde_json::to_string(run)
+            .map_err(|e| ChapatyErrorKind::FailedToTrackExperiment(e.to_string()))?;
+        self.client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| ChapatyErrorKind::FailedToTrackExperiment(e.to_string()))
+    }
+}
@@ -0,0 +1,101 @@
+//! C ABI to embed the backtester into non-Rust hosts. A backtest is described by a JSON encoded
+//! [`BotConfig`] and the result is returned as a JSON report.
+//!
+//! # Example config
+//! ```json
+//! {
+//!     "name": "chapaty",
+//!     "historical_market_data_bucket_name": "chapaty-ai-hdb",
+//!     "cached_bot_data_bucket_name": "chapaty-ai-cache",
+//!     "data_provider": "cme",
+//!     "strategy": {
+//!         "name": "ppp",
+//!         "entry": { "Poc": "Tpo1m" },
+//!         "stop_loss": { "kind": "PrevHighOrLow", "offset": 0.0 },
+//!         "take_profit": { "kind": "PrevClose", "offset": 0.0 }
+//!     },
+//!     "markets": ["6e"],
+//!     "years": [2022],
+//!     "market_simulation_data": "ohlc-1m",
+//!     "time_frame": "1d"
+//! }
+//! ```
+use crate::{backtest_result::BacktestResult, config::BotConfig, enums::error::ChapatyErrorKind};
+use std::ffi::{c_char, CStr, CString};
+
+async fn run_backtest_from_config(config_json: &str) -> Result<BacktestResult, ChapatyErrorKind> {
+    let bot = BotConfig::from_json(config_json)?.build().await?;
+
+    bot.backtest().await
+}
+
+/// Runs the backtest described by `config_json` and returns the report as JSON. If the backtest
+/// fails, a JSON object of the form `{"error": "..."}` is returned instead.
+pub fn run_backtest(config_json: &str) -> String {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => return error_json(e.to_string()),
+    };
+
+    match runtime.block_on(run_backtest_from_config(config_json)) {
+        Ok(result) => serde_json::to_string(&result).unwrap_or_else(|e| error_json(e.to_string())),
+        Err(e) => error_json(format!("{e:?}")),
+    }
+}
+
+fn error_json(error: String) -> String {
+    serde_json::json!({ "error": error }).to_string()
+}
+
+/// C entry point of [`run_backtest`]. The returned string is owned by chapaty and must be released
+/// with [`chapaty_free_string`].
+///
+/// # Safety
+/// `config_json` must be a valid, nul-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn chapaty_run_backtest(config_json: *const c_char) -> *mut c_char {
+    let report = if config_json.is_null() {
+        error_json("Config is a null pointer".to_string())
+    } else {
+        match CStr::from_ptr(config_json).to_str() {
+            Ok(config_json) => std::panic::catch_unwind(|| run_backtest(config_json))
+                .unwrap_or_else(|_| error_json("Backtest panicked".to_string())),
+            Err(e) => error_json(e.to_string()),
+        }
+    };
+
+    CString::new(report).unwrap_or_default().into_raw()
+}
+
+/// Releases a string returned by [`chapaty_run_backtest`].
+///
+/// # Safety
+/// `s` must be a pointer returned by [`chapaty_run_backtest`] that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn chapaty_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_backtest_with_invalid_config() {
+        let report: serde_json::Value = serde_json::from_str(&run_backtest("{}")).unwrap();
+        assert!(report.get("error").is_some());
+    }
+
+    #[test]
+    fn test_ffi_round_trip() {
+        let config = CString::new("not json").unwrap();
+        unsafe {
+            let report = chapaty_run_backtest(config.as_ptr());
+            let report_str = CStr::from_ptr(report).to_str().unwrap().to_string();
+            chapaty_free_string(report);
+            assert!(report_str.starts_with("{\"error\""));
+        }
+    }
+}
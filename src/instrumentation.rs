@@ -0,0 +1,143 @@
+//! Process wide metrics of the backtest pipeline in the Prometheus text exposition format. The
+//! counters are only recorded with the `metrics` feature. They are rendered with
+//! [`render_prometheus`], such that the caller can serve them with the HTTP server of its choice.
+//!
+//! Rates like backtest units per second or the cache hit rate are derived by Prometheus, e.g.
+//! `rate(chapaty_backtest_units_total[5m])`.
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+static METRICS: PipelineMetrics = PipelineMetrics::new();
+
+struct PipelineMetrics {
+    backtest_units: AtomicU64,
+    backtest_unit_micros: AtomicU64,
+    time_frame_snapshots: AtomicU64,
+    fetches: AtomicU64,
+    fetch_micros: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+impl PipelineMetrics {
+    const fn new() -> Self {
+        Self {
+            backtest_units: AtomicU64::new(0),
+            backtest_unit_micros: AtomicU64::new(0),
+            time_frame_snapshots: AtomicU64::new(0),
+            fetches: AtomicU64::new(0),
+            fetch_micros: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Adds `value` to the `counter`, if the metrics are recorded at all.
+fn increment(counter: &AtomicU64, value: u64) {
+    if cfg!(feature = "metrics") {
+        counter.fetch_add(value, Ordering::Relaxed);
+    }
+}
+
+/// Records one computed market-year profit and loss report.
+pub(crate) fn record_backtest_unit(duration: Duration) {
+    increment(&METRICS.backtest_units, 1);
+    increment(&METRICS.backtest_unit_micros, duration.as_micros() as u64);
+}
+
+/// Records one backtested time frame snapshot, e.g. one trading day.
+pub(crate) fn record_time_frame_snapshot() {
+    increment(&METRICS.time_frame_snapshots, 1);
+}
+
+/// Records one download from Google Cloud Storage.
+pub(crate) fn record_fetch(duration: Duration) {
+    increment(&METRICS.fetches, 1);
+    increment(&METRICS.fetch_micros, duration.as_micros() as u64);
+}
+
+pub(crate) fn record_cache_hit() {
+    increment(&METRICS.cache_hits, 1);
+}
+
+pub(crate) fn record_cache_miss() {
+    increment(&METRICS.cache_misses, 1);
+}
+
+/// Renders all metrics in the Prometheus text exposition format.
+#[cfg(feature = "metrics")]
+pub fn render_prometheus() -> String {
+    let counter = |name: &str, help: &str, value: String| {
+        format!("# HELP {name} {help}\n# TYPE {name} counter\n{name} {value}\n")
+    };
+    let seconds = |micros: &AtomicU64| (micros.load(Ordering::Relaxed) as f64 / 1e6).to_string();
+    let count = |value: &AtomicU64| value.load(Ordering::Relaxed).to_string();
+
+    [
+        counter(
+            "chapaty_backtest_units_total",
+            "Number of computed market-year profit and loss reports.",
+            count(&METRICS.backtest_units),
+        ),
+        counter(
+            "chapaty_backtest_unit_duration_seconds_total",
+            "Total time spent computing market-year profit and loss reports.",
+            seconds(&METRICS.backtest_unit_micros),
+        ),
+        counter(
+            "chapaty_time_frame_snapshots_total",
+            "Number of backtested time frame snapshots.",
+            count(&METRICS.time_frame_snapshots),
+        ),
+        counter(
+            "chapaty_fetches_total",
+            "Number of downloads from Google Cloud Storage.",
+            count(&METRICS.fetches),
+        ),
+        counter(
+            "chapaty_fetch_duration_seconds_total",
+            "Total time spent downloading from Google Cloud Storage.",
+            seconds(&METRICS.fetch_micros),
+        ),
+        counter(
+            "chapaty_cache_hits_total",
+            "Number of data frame maps served from the cache bucket.",
+            count(&METRICS.cache_hits),
+        ),
+        counter(
+            "chapaty_cache_misses_total",
+            "Number of data frame maps computed from the historical market data.",
+            count(&METRICS.cache_misses),
+        ),
+    ]
+    .concat()
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        record_cache_hit();
+        record_fetch(Duration::from_millis(1500));
+
+        let metrics = render_prometheus();
+        let value = |name: &str| -> f64 {
+            metrics
+                .lines()
+                .find_map(|line| line.strip_prefix(&format!("{name} ")))
+                .unwrap()
+                .parse()
+                .unwrap()
+        };
+
+        assert!(metrics.contains("# TYPE chapaty_cache_hits_total counter"));
+        assert!(value("chapaty_cache_hits_total") >= 1.0);
+        assert!(value("chapaty_fetches_total") >= 1.0);
+        assert!(value("chapaty_fetch_duration_seconds_total") >= 1.5);
+    }
+}
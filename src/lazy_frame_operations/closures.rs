@@ -1,4 +1,4 @@
-use chrono::{Datelike, Duration, NaiveDateTime};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Weekday};
 use polars::{
     prelude::{Float64Chunked, Int64Chunked},
     series::{IntoSeries, Series},
@@ -70,6 +70,52 @@ pub fn get_weekday_from_ts(val: &Series) -> Series {
         .into_series()
 }
 
+/// Returns the business day of a **UTC** timestamp in **milliseconds**. Timestamps at or after
+/// 22:00 UTC belong to the next day, since the CME session opens at 17:00 CT. Weekends and
+/// `holidays` roll forward to the next business day.
+pub fn business_day_from_ts(utc_ts_in_milliseconds: i64, holidays: &[NaiveDate]) -> NaiveDate {
+    let mut day = DateTime::from_timestamp(utc_ts_in_milliseconds / 1000, 0)
+        .unwrap()
+        .naive_utc()
+        .checked_add_signed(Duration::hours(2))
+        .unwrap()
+        .date();
+    while matches!(day.weekday(), Weekday::Sat | Weekday::Sun) || holidays.contains(&day) {
+        day = day.succ_opt().unwrap();
+    }
+    day
+}
+
+pub fn get_business_day_cw_from_ts(val: &Series, holidays: &[NaiveDate]) -> Series {
+    val.i64()
+        .unwrap()
+        .into_iter()
+        .map(|o: Option<i64>| {
+            o.map(|ts: i64| {
+                i64::from(business_day_from_ts(ts, holidays).iso_week().week())
+            })
+        })
+        .collect::<Int64Chunked>()
+        .into_series()
+}
+
+pub fn get_business_day_weekday_from_ts(val: &Series, holidays: &[NaiveDate]) -> Series {
+    val.i64()
+        .unwrap()
+        .into_iter()
+        .map(|o: Option<i64>| {
+            o.map(|ts: i64| {
+                i64::from(
+                    business_day_from_ts(ts, holidays)
+                        .weekday()
+                        .number_from_monday(),
+                )
+            })
+        })
+        .collect::<Int64Chunked>()
+        .into_series()
+}
+
 pub fn comma_separated_string_to_f64(val: Series) -> Series {
     val.utf8()
         .unwrap()
@@ -196,4 +242,25 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn test_business_day_from_ts() {
+        let monday = NaiveDate::from_ymd_opt(2022, 2, 21).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2022, 2, 22).unwrap();
+
+        // Saturday 19:56:40 UTC and Sunday 23:43:20 UTC belong to Monday
+        assert_eq!(monday, business_day_from_ts(1645300600000, &[]));
+        assert_eq!(monday, business_day_from_ts(1645400600000, &[]));
+        // Monday 21:59:59 UTC still belongs to Monday, 22:00:00 UTC to Tuesday
+        assert_eq!(monday, business_day_from_ts(1645480799000, &[]));
+        assert_eq!(tuesday, business_day_from_ts(1645480800000, &[]));
+        // Presidents' Day rolls forward to Tuesday
+        assert_eq!(tuesday, business_day_from_ts(1645400600000, &[monday]));
+
+        let ts = Series::new("ts", &[1645300600000_i64, 1645400600000, 1645480800000]);
+        let cw = Series::new("", &[8_i64, 8, 8]);
+        let weekday = Series::new("", &[1_i64, 1, 2]);
+        assert_eq!(cw, get_business_day_cw_from_ts(&ts, &[]));
+        assert_eq!(weekday, get_business_day_weekday_from_ts(&ts, &[]));
+    }
 }
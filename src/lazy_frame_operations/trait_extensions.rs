@@ -1,6 +1,12 @@
-use super::closures::{get_cw_from_ts, get_weekday_from_ts};
+use super::closures::{
+    get_business_day_cw_from_ts, get_business_day_weekday_from_ts, get_cw_from_ts,
+    get_weekday_from_ts,
+};
 use crate::{
-    bot::time_interval::{InInterval, TimeInterval},
+    bot::{
+        excluded_time_range::ExcludedTimeRange,
+        time_interval::{InInterval, TimeInterval},
+    },
     converter::any_value::AnyValueConverter,
     data_frame_operations::trait_extensions::MyDataFrameOperations,
     enums::{
@@ -12,21 +18,34 @@ use polars::{
     lazy::dsl::GetOutput,
     prelude::{col, lit, DataFrame, LazyCsvReader, LazyFileListReader, LazyFrame},
 };
+use chrono::NaiveDate;
 use std::path::PathBuf;
 
 pub trait MyLazyFrameOperations {
     fn add_cw_col(self, ts_col: &str) -> Self;
     fn add_weekday_col(self, ts_col: &str) -> Self;
+    fn add_business_day_cw_and_weekday_cols(self, ts_col: &str, holidays: &[NaiveDate]) -> Self;
     fn filter_ts_col_by_time_interval(
         self,
         ts_col: &str,
         time_interval: TimeInterval,
         time_frame: TimeFrameKind,
     ) -> Self;
+    fn drop_rows_in_excluded_time_ranges(
+        self,
+        open_ts_col: &str,
+        close_ts_col: &str,
+        excluded_time_ranges: &[ExcludedTimeRange],
+    ) -> Self;
     fn filter_ts_col_by_price(self, px: f64) -> Self;
     fn drop_rows_before_entry_ts(self, entry_ts: i64) -> Self;
+    fn drop_trades_before_ts(self, ts: i64) -> Self;
     fn filter_trade_data_kind_values(self) -> Self;
     fn find_timestamp_when_price_reached(self, px: f64) -> Option<i64>;
+    fn find_open_time_of_nth_candle(self, n: usize) -> Option<i64>;
+    fn find_last_candle_before_ts(self, ts: i64) -> Option<(i64, f64)>;
+    fn find_last_trade_before_ts(self, ts: i64) -> Option<(i64, f64)>;
+    fn find_trade_timestamp_when_price_reached(self, px: f64, from_above: bool) -> Option<i64>;
     fn get_row_of_poc_as_df(self, poc: f64) -> DataFrame;
     fn sort_by_date(self) -> Self;
     fn sort_by_date_and_market(self) -> Self;
@@ -47,6 +66,24 @@ impl MyLazyFrameOperations for LazyFrame {
                 .alias("weekday"),
         )
     }
+    fn add_business_day_cw_and_weekday_cols(self, ts_col: &str, holidays: &[NaiveDate]) -> Self {
+        let cw_holidays = holidays.to_vec();
+        let weekday_holidays = holidays.to_vec();
+        self.with_columns([
+            col(ts_col)
+                .apply(
+                    move |x| Ok(Some(get_business_day_cw_from_ts(&x, &cw_holidays))),
+                    GetOutput::default(),
+                )
+                .alias("cw"),
+            col(ts_col)
+                .apply(
+                    move |x| Ok(Some(get_business_day_weekday_from_ts(&x, &weekday_holidays))),
+                    GetOutput::default(),
+                )
+                .alias("weekday"),
+        ])
+    }
 
     fn filter_ts_col_by_time_interval(
         self,
@@ -66,6 +103,25 @@ impl MyLazyFrameOperations for LazyFrame {
         .select([col("*")])
     }
 
+    /// Drops every row whose time span `[open_ts_col, close_ts_col]` overlaps an excluded time
+    /// range, e.g. a candle that opens before and closes within a maintenance window. For trades,
+    /// both columns are the timestamp of the trade.
+    fn drop_rows_in_excluded_time_ranges(
+        self,
+        open_ts_col: &str,
+        close_ts_col: &str,
+        excluded_time_ranges: &[ExcludedTimeRange],
+    ) -> Self {
+        let outside_of_ranges = excluded_time_ranges.iter().fold(lit(true), |predicate, range| {
+            predicate.and(
+                col(close_ts_col)
+                    .lt(lit(range.start))
+                    .or(col(open_ts_col).gt_eq(lit(range.end))),
+            )
+        });
+        self.filter(outside_of_ranges)
+    }
+
     /// # Returns
     /// This function returns a `DaraFrame` with a single column, the `timestamp` column
     fn filter_ts_col_by_price(self, px: f64) -> Self {
@@ -80,6 +136,11 @@ impl MyLazyFrameOperations for LazyFrame {
         self.filter(col(&col_name).gt_eq(lit(entry_ts)))
     }
 
+    fn drop_trades_before_ts(self, ts: i64) -> Self {
+        let col_name = DataProviderColumnKind::Timestamp.to_string();
+        self.filter(col(&col_name).gt_eq(lit(ts)))
+    }
+
     /// # Returns
     /// This function returns a `DataFrame` with a single row, containing the following column values at index
     /// * Index 0: last trade price
@@ -116,6 +177,80 @@ impl MyLazyFrameOperations for LazyFrame {
         }
     }
 
+    /// Returns the open time of the candle at index `n`.
+    fn find_open_time_of_nth_candle(self, n: usize) -> Option<i64> {
+        let ots = DataProviderColumnKind::OpenTime.to_string();
+        let df = self
+            .slice(i64::try_from(n).unwrap(), 1)
+            .select([col(&ots)])
+            .collect()
+            .unwrap();
+        if df.is_not_an_empty_frame() {
+            Some(df.get(0).unwrap()[0].unwrap_int64())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the open time and close price of the last candle that opened before `ts`.
+    fn find_last_candle_before_ts(self, ts: i64) -> Option<(i64, f64)> {
+        let ots = DataProviderColumnKind::OpenTime.to_string();
+        let close = DataProviderColumnKind::Close.to_string();
+        let df = self
+            .filter(col(&ots).lt(lit(ts)))
+            .select([col(&ots), col(&close)])
+            .last()
+            .collect()
+            .unwrap();
+        if df.is_not_an_empty_frame() {
+            let row = df.get(0).unwrap();
+            Some((row[0].unwrap_int64(), row[1].unwrap_float64()))
+        } else {
+            None
+        }
+    }
+
+    /// This function returns the timestamp and price of the last raw trade before `ts`.
+    fn find_last_trade_before_ts(self, ts: i64) -> Option<(i64, f64)> {
+        let timestamp = DataProviderColumnKind::Timestamp.to_string();
+        let price = DataProviderColumnKind::Price.to_string();
+        let df = self
+            .filter(col(&timestamp).lt(lit(ts)))
+            .select([col(&timestamp), col(&price)])
+            .last()
+            .collect()
+            .unwrap();
+        if df.is_not_an_empty_frame() {
+            let row = df.get(0).unwrap();
+            Some((row[0].unwrap_int64(), row[1].unwrap_float64()))
+        } else {
+            None
+        }
+    }
+
+    /// This function replays raw trades and returns the timestamp of the first trade that reached
+    /// `px`. If `from_above` is set, the price is reached by a trade at or below `px`, otherwise by a
+    /// trade at or above `px`.
+    fn find_trade_timestamp_when_price_reached(self, px: f64, from_above: bool) -> Option<i64> {
+        let ts = DataProviderColumnKind::Timestamp.to_string();
+        let price = DataProviderColumnKind::Price.to_string();
+        let is_reached = if from_above {
+            col(&price).lt_eq(lit(px))
+        } else {
+            col(&price).gt_eq(lit(px))
+        };
+        let df = self
+            .select([col(&ts).filter(is_reached)])
+            .first()
+            .collect()
+            .unwrap();
+        if df.is_not_an_empty_frame() {
+            Some(df.get(0).unwrap()[0].unwrap_int64())
+        } else {
+            None
+        }
+    }
+
     fn get_row_of_poc_as_df(self, poc: f64) -> DataFrame {
         self.filter(col("px").eq(lit(poc)))
             .select(&[col("*")])
@@ -164,7 +299,45 @@ impl MyLazyFrameVecOperations for Vec<LazyFrame> {
 mod tests {
     use super::*;
     use crate::cloud_api::api_for_unit_tests::download_df;
-    use polars::prelude::IntoLazy;
+    use polars::{
+        df,
+        prelude::{IntoLazy, NamedFrom},
+    };
+
+    #[test]
+    fn test_drop_rows_in_excluded_time_ranges() {
+        let ldf = df!(
+            "ots" => &[0_i64, 60_000, 120_000, 180_000, 240_000],
+            "close" => &[1.0, 2.0, 3.0, 4.0, 5.0],
+            "cts" => &[59_999_i64, 119_999, 179_999, 239_999, 299_999],
+        )
+        .unwrap()
+        .lazy();
+        let excluded_time_ranges = [
+            ExcludedTimeRange::new(60_000, 120_000),
+            // Starts within the candle that opens at 180_000
+            ExcludedTimeRange::new(200_000, 210_000),
+        ];
+
+        let result = ldf
+            .clone()
+            .drop_rows_in_excluded_time_ranges("ots", "cts", &excluded_time_ranges)
+            .collect()
+            .unwrap();
+        let target = df!(
+            "ots" => &[0_i64, 120_000, 240_000],
+            "close" => &[1.0, 3.0, 5.0],
+            "cts" => &[59_999_i64, 179_999, 299_999],
+        )
+        .unwrap();
+        assert_eq!(target, result);
+
+        let result = ldf
+            .drop_rows_in_excluded_time_ranges("ots", "cts", &[])
+            .collect()
+            .unwrap();
+        assert_eq!(5, result.height());
+    }
 
     #[tokio::test]
     async fn test_compute_cw_and_weekday_col() {
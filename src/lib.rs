@@ -8,23 +8,42 @@ pub mod converter;
 mod data_frame_operations;
 pub mod data_provider;
 mod enums;
+pub mod experiment_tracker;
+pub mod ffi;
+pub mod instrumentation;
 mod lazy_frame_operations;
+pub mod notification;
 pub mod performance_report;
 pub mod pnl;
+pub mod report_column;
+pub mod roll_calendar;
 mod price_histogram;
 mod serde;
 pub mod equity_curve;
 pub mod strategy;
+pub mod streaming_report;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod trade_breakdown_report;
 mod trading_indicator;
 
+pub use bot::dataset_export::{DatasetManifest, DatasetStream};
+pub use bot::excluded_time_range::ExcludedTimeRange;
+pub use bot::instrument_override::InstrumentOverride;
 pub use bot::time_interval::TimeInterval;
 pub use bot::{BotBuilder, Bot};
+pub use calculator::pre_trade_values_cache::PreTradeValuesCache;
+pub use equity_curve::{Capital, EquityCurveBands, MonteCarloSimulation};
 pub use enums::{
-    bot::{StopLossKind, TakeProfitKind, TimeFrameKind},
+    bot::{ExecutionModeKind, PeriodKind, StopLossKind, TakeProfitKind, TimeFrameKind},
     column_names::{DataProviderColumnKind, PnLReportColumnKind, PerformanceReportColumnKind, TradeBreakDownReportColumnKind},
-    data::MarketSimulationDataKind,
-    indicator::{PriceHistogramKind, TradingIndicatorKind},
+    data::{CompressionKind, MarketSimulationDataKind},
+    error::ChapatyErrorKind,
+    indicator::{
+        CandlePatternKind, DayTypeKind, ExecutionDepthKind, PivotLevelKind, PivotPointKind,
+        PocSelectionRule, PriceHistogramKind, TradingIndicatorKind, ValueAreaRule,
+    },
     markets::MarketKind,
+    trade_and_pre_trade::{SimulationArtifactKind, TerminationReason},
 };
 pub use polars::prelude::DataFrame;
\ No newline at end of file
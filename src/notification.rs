@@ -0,0 +1,217 @@
+pub mod webhook;
+use crate::{
+    converter::any_value::AnyValueConverter,
+    enums::{
+        column_names::PnLReportColumnKind, error::ChapatyErrorKind,
+        trade_and_pre_trade::TradeDirectionKind,
+    },
+};
+use async_trait::async_trait;
+use polars::prelude::DataFrame;
+use std::sync::Arc;
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+};
+
+/// A trade signal generated by a strategy, i.e. the entry together with its stop loss and take
+/// profit levels for a single time frame snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeSignal {
+    pub strategy: String,
+    pub market: String,
+    pub date: String,
+    pub direction: String,
+    pub entry: f64,
+    pub stop_loss: f64,
+    pub take_profit: f64,
+}
+
+/// A `NotificationSink` receives every `TradeSignal` generated while backtesting a `Bot`, e.g. to
+/// forward the signal to a webhook.
+#[async_trait]
+pub trait NotificationSink {
+    async fn notify(&self, signal: &TradeSignal) -> Result<(), ChapatyErrorKind>;
+}
+
+/// Extracts all trade signals from a profit and loss report. Rows without a clear trade direction
+/// do not generate a signal.
+pub fn trade_signals_from_pnl_report(pnl: &DataFrame) -> Vec<TradeSignal> {
+    let no_trade = TradeDirectionKind::None.to_string();
+    (0..pnl.height())
+        .map(|idx| trade_signal_from_row(pnl, idx))
+        .filter(|signal| signal.direction != no_trade)
+        .collect()
+}
+
+fn trade_signal_from_row(pnl: &DataFrame, idx: usize) -> TradeSignal {
+    TradeSignal {
+        strategy: get_utf8(pnl, PnLReportColumnKind::Strategy, idx),
+        market: get_utf8(pnl, PnLReportColumnKind::Market, idx),
+        date: get_utf8(pnl, PnLReportColumnKind::Date, idx),
+        direction: get_utf8(pnl, PnLReportColumnKind::TradeDirection, idx),
+        entry: get_float64(pnl, PnLReportColumnKind::Entry, idx),
+        stop_loss: get_float64(pnl, PnLReportColumnKind::StopLoss, idx),
+        take_profit: get_float64(pnl, PnLReportColumnKind::TakeProfit, idx),
+    }
+}
+
+fn get_utf8(pnl: &DataFrame, column: PnLReportColumnKind, idx: usize) -> String {
+    pnl.column(&column.to_string())
+        .unwrap()
+        .get(idx)
+        .unwrap()
+        .unwrap_utf8()
+}
+
+fn get_float64(pnl: &DataFrame, column: PnLReportColumnKind, idx: usize) -> f64 {
+    pnl.column(&column.to_string())
+        .unwrap()
+        .get(idx)
+        .unwrap()
+        .unwrap_float64()
+}
+
+/// Sends every trade signal of the profit and loss report to all registered sinks. Returns the
+/// error of the first sink that fails to send a signal.
+pub async fn notify_sinks(
+    sinks: &[Arc<dyn NotificationSink + Send + Sync>],
+    pnl: &DataFrame,
+) -> Result<(), ChapatyErrorKind> {
+    for signal in trade_signals_from_pnl_report(pnl) {
+        notify_all(sinks, &signal).await?;
+    }
+    Ok(())
+}
+
+/// Spawns a task that forwards every trade signal sent to the returned sender to all sinks, as
+/// soon as the signal is generated. The task finishes once all senders are dropped and returns
+/// the error of the first sink that fails to send a signal. Signals sent after a failure are
+/// discarded.
+pub fn spawn_signal_dispatcher(
+    sinks: Vec<Arc<dyn NotificationSink + Send + Sync>>,
+) -> (
+    UnboundedSender<TradeSignal>,
+    JoinHandle<Result<(), ChapatyErrorKind>>,
+) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<TradeSignal>();
+    let dispatcher = tokio::spawn(async move {
+        while let Some(signal) = rx.recv().await {
+            notify_all(&sinks, &signal).await?;
+        }
+        Ok(())
+    });
+    (tx, dispatcher)
+}
+
+async fn notify_all(
+    sinks: &[Arc<dyn NotificationSink + Send + Sync>],
+    signal: &TradeSignal,
+) -> Result<(), ChapatyErrorKind> {
+    for sink in sinks {
+        sink.notify(signal).await.map_err(|e| {
+            ChapatyErrorKind::FailedToSendNotification(format!(
+                "Failed to send notification for {signal:?}, caused by: {e:?}"
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{df, prelude::NamedFrom};
+    use std::sync::Mutex;
+
+    struct RecordingSink {
+        signals: Mutex<Vec<TradeSignal>>,
+    }
+
+    #[async_trait]
+    impl NotificationSink for RecordingSink {
+        async fn notify(&self, signal: &TradeSignal) -> Result<(), ChapatyErrorKind> {
+            self.signals.lock().unwrap().push(signal.clone());
+            Ok(())
+        }
+    }
+
+    struct FailingSink;
+
+    #[async_trait]
+    impl NotificationSink for FailingSink {
+        async fn notify(&self, _signal: &TradeSignal) -> Result<(), ChapatyErrorKind> {
+            Err(ChapatyErrorKind::FailedToSendNotification(
+                "Webhook is not reachable".to_string(),
+            ))
+        }
+    }
+
+    fn pnl_report() -> DataFrame {
+        df!(
+            &PnLReportColumnKind::Date.to_string() => &["2022-01-10", "2022-01-11"],
+            &PnLReportColumnKind::Strategy.to_string() => &["PPP", "PPP"],
+            &PnLReportColumnKind::Market.to_string() => &["6e", "6e"],
+            &PnLReportColumnKind::TradeDirection.to_string() => &["Long", "Not Clear"],
+            &PnLReportColumnKind::Entry.to_string() => &[1.1549, 1.15735],
+            &PnLReportColumnKind::TakeProfit.to_string() => &[1.162, 1.1585],
+            &PnLReportColumnKind::StopLoss.to_string() => &[1.1537, 1.15315],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trade_signals_from_pnl_report() {
+        let target = vec![TradeSignal {
+            strategy: "PPP".to_string(),
+            market: "6e".to_string(),
+            date: "2022-01-10".to_string(),
+            direction: "Long".to_string(),
+            entry: 1.1549,
+            stop_loss: 1.1537,
+            take_profit: 1.162,
+        }];
+
+        assert_eq!(target, trade_signals_from_pnl_report(&pnl_report()));
+    }
+
+    #[tokio::test]
+    async fn test_notify_sinks() {
+        let sink = Arc::new(RecordingSink {
+            signals: Mutex::new(Vec::new()),
+        });
+        let sinks: Vec<Arc<dyn NotificationSink + Send + Sync>> = vec![sink.clone()];
+
+        notify_sinks(&sinks, &pnl_report()).await.unwrap();
+
+        let signals = sink.signals.lock().unwrap();
+        assert_eq!(1, signals.len());
+        assert_eq!("Long", signals[0].direction);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_signal_dispatcher() {
+        let sink = Arc::new(RecordingSink {
+            signals: Mutex::new(Vec::new()),
+        });
+        let (tx, dispatcher) = spawn_signal_dispatcher(vec![sink.clone()]);
+        for signal in trade_signals_from_pnl_report(&pnl_report()) {
+            tx.send(signal).unwrap();
+        }
+        drop(tx);
+
+        dispatcher.await.unwrap().unwrap();
+        assert_eq!(1, sink.signals.lock().unwrap().len());
+
+        let (tx, dispatcher) = spawn_signal_dispatcher(vec![Arc::new(FailingSink)]);
+        for signal in trade_signals_from_pnl_report(&pnl_report()) {
+            tx.send(signal).unwrap();
+        }
+        drop(tx);
+
+        assert!(matches!(
+            dispatcher.await.unwrap(),
+            Err(ChapatyErrorKind::FailedToSendNotification(_))
+        ));
+    }
+}
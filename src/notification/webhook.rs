@@ -0,0 +1,197 @@
+use super::{NotificationSink, TradeSignal};
+use crate::enums::error::ChapatyErrorKind;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Slack-compatible default payload.
+const DEFAULT_TEMPLATE: &str = r#"{"text": "{strategy} {direction} {market} on {date} | Entry: {entry} | SL: {stop_loss} | TP: {take_profit}"}"#;
+
+/// This struct posts every `TradeSignal` as JSON to a webhook URL. The payload is rendered from a
+/// JSON template, whose string values may contain the placeholders `{strategy}`, `{market}`,
+/// `{date}`, `{direction}`, `{entry}`, `{stop_loss}` and `{take_profit}`. A string that consists of
+/// a single placeholder is replaced by the value of the `TradeSignal`, i.e. prices become JSON
+/// numbers. Otherwise, the placeholders are interpolated into the string. All values are escaped.
+///
+/// # Example
+/// ```
+/// use chapaty::notification::webhook::WebhookSink;
+///
+/// let sink = WebhookSink::new("https://hooks.slack.com/services/...".to_string())
+///     .with_template(r#"{"symbol": "{market}", "side": "{direction}", "sl": "{stop_loss}", "tp": "{take_profit}"}"#.to_string())
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    template: Value,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            template: serde_json::from_str(DEFAULT_TEMPLATE).unwrap(),
+        }
+    }
+
+    /// # Errors
+    /// Returns a `ParseNotificationTemplateError` if the template is not valid JSON.
+    pub fn with_template(self, template: String) -> Result<Self, ChapatyErrorKind> {
+        let template = serde_json::from_str(&template)
+            .map_err(|e| ChapatyErrorKind::ParseNotificationTemplateError(e.to_string()))?;
+        Ok(Self { template, ..self })
+    }
+
+    fn render(&self, signal: &TradeSignal) -> String {
+        let mut payload = self.template.clone();
+        fill_placeholders(&mut payload, signal);
+        payload.to_string()
+    }
+}
+
+fn fill_placeholders(value: &mut Value, signal: &TradeSignal) {
+    match value {
+        Value::String(s) => {
+            *value = match placeholder_value(s, signal) {
+                Some(placeholder_value) => placeholder_value,
+                None => Value::String(interpolate(s, signal)),
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| fill_placeholders(value, signal)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|value| fill_placeholders(value, signal)),
+        Value::Null | Value::Bool(_) | Value::Number(_) => (),
+    }
+}
+
+fn placeholder_value(s: &str, signal: &TradeSignal) -> Option<Value> {
+    let name = s.strip_prefix('{')?.strip_suffix('}')?;
+    let value = match name {
+        "strategy" => Value::from(signal.strategy.clone()),
+        "market" => Value::from(signal.market.clone()),
+        "date" => Value::from(signal.date.clone()),
+        "direction" => Value::from(signal.direction.clone()),
+        "entry" => Value::from(signal.entry),
+        "stop_loss" => Value::from(signal.stop_loss),
+        "take_profit" => Value::from(signal.take_profit),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Replaces the placeholders in a single pass, such that placeholders within the values of the
+/// `TradeSignal` are not replaced.
+fn interpolate(s: &str, signal: &TradeSignal) -> String {
+    let mut rendered = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let placeholder = candidate.find('}').and_then(|end| {
+            placeholder_value(&candidate[..=end], signal).map(|value| (end, value))
+        });
+        match placeholder {
+            Some((end, Value::String(value))) => {
+                rendered.push_str(&value);
+                rest = &candidate[end + 1..];
+            }
+            Some((end, value)) => {
+                rendered.push_str(&value.to_string());
+                rest = &candidate[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &candidate[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[async_trait]
+impl NotificationSink for WebhookSink {
+    async fn notify(&self, signal: &TradeSignal) -> Result<(), ChapatyErrorKind> {
+        self.client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(self.render(signal))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| ChapatyErrorKind::FailedToSendNotification(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn signal() -> TradeSignal {
+        TradeSignal {
+            strategy: "PPP".to_string(),
+            market: "6e".to_string(),
+            date: "2022-01-10".to_string(),
+            direction: "Long".to_string(),
+            entry: 1.1549,
+            stop_loss: 1.1537,
+            take_profit: 1.162,
+        }
+    }
+
+    fn render(sink: &WebhookSink, signal: &TradeSignal) -> Value {
+        serde_json::from_str(&sink.render(signal)).unwrap()
+    }
+
+    #[test]
+    fn test_render_default_template() {
+        let sink = WebhookSink::new("http://localhost".to_string());
+        let target =
+            json!({"text": "PPP Long 6e on 2022-01-10 | Entry: 1.1549 | SL: 1.1537 | TP: 1.162"});
+        assert_eq!(target, render(&sink, &signal()));
+    }
+
+    #[test]
+    fn test_render_custom_template() {
+        let sink = WebhookSink::new("http://localhost".to_string())
+            .with_template(
+                r#"{"symbol": "{market}", "side": "{direction}", "levels": {"sl": "{stop_loss}", "tp": "{take_profit}"}, "tags": ["{strategy}", 1]}"#
+                    .to_string(),
+            )
+            .unwrap();
+        let target = json!({
+            "symbol": "6e",
+            "side": "Long",
+            "levels": {"sl": 1.1537, "tp": 1.162},
+            "tags": ["PPP", 1],
+        });
+        assert_eq!(target, render(&sink, &signal()));
+    }
+
+    #[test]
+    fn test_render_escapes_values() {
+        let sink = WebhookSink::new("http://localhost".to_string());
+        let signal = TradeSignal {
+            strategy: r#"My "PPP" \ {market} strategy"#.to_string(),
+            ..signal()
+        };
+        let target = json!({"text": r#"My "PPP" \ {market} strategy Long 6e on 2022-01-10 | Entry: 1.1549 | SL: 1.1537 | TP: 1.162"#});
+        assert_eq!(target, render(&sink, &signal));
+    }
+
+    #[test]
+    fn test_with_invalid_template() {
+        let sink = WebhookSink::new("http://localhost".to_string());
+        assert!(matches!(
+            sink.with_template(r#"{"sl": {stop_loss}}"#.to_string()),
+            Err(ChapatyErrorKind::ParseNotificationTemplateError(_))
+        ));
+    }
+}
@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use polars::prelude::DataFrame;
 use serde::{Deserialize, Serialize};
 
-use crate::{data_frame_operations::io_operations::save_df_as_csv, MarketKind};
+use crate::{
+    converter::report_format::ReportFormat, data_frame_operations::io_operations::save_df_as_csv,
+    enums::column_names::PerformanceReportColumnKind, MarketKind,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PerformanceReportAggMarkets {
@@ -13,8 +16,12 @@ pub struct PerformanceReportAggMarkets {
 
 impl PerformanceReportAggMarkets {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         save_df_as_csv(
-            &mut self.report.clone(),
+            &mut format.export::<PerformanceReportColumnKind>(&self.report),
             &format!("{file_name}_all_markets_performance_report"),
         )
     }
@@ -29,11 +36,15 @@ pub struct PerformanceReportsAggYears {
 
 impl PerformanceReportsAggYears {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.reports
             .iter()
             .for_each(|(market, performance_report)| {
                 save_df_as_csv(
-                    &mut performance_report.clone(),
+                    &mut format.export::<PerformanceReportColumnKind>(performance_report),
                     &format!("{file_name}_{market}_all_years_performance_report"),
                 )
             })
@@ -48,11 +59,15 @@ pub struct PerformanceReports {
 
 impl PerformanceReports {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.reports
             .iter()
             .for_each(|(market, performance_report)| {
                 save_df_as_csv(
-                    &mut performance_report.clone(),
+                    &mut format.export::<PerformanceReportColumnKind>(performance_report),
                     &format!("{file_name}_{market}_performance_report"),
                 )
             })
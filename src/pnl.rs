@@ -4,3 +4,4 @@ pub mod pnl_statement;
 pub mod pnl_statement_agg_markets;
 pub mod pnl_statement_agg_markets_and_agg_years;
 pub mod pnl_statement_agg_years;
+pub mod simulation_artifacts;
@@ -1,4 +1,7 @@
-use crate::{converter::any_value::AnyValueConverter, enums::column_names::PnLReportColumnKind};
+use crate::{
+    converter::any_value::AnyValueConverter,
+    enums::{column_names::PnLReportColumnKind, trade_and_pre_trade::TerminationReason},
+};
 use polars::{
     datatypes::AnyValue,
     lazy::dsl::when,
@@ -41,6 +44,35 @@ pub fn number_timeout_trades(df: DataFrame) -> u32 {
     number_timeout_winner_trades(df.clone()) + number_timeout_loser_trades(df)
 }
 
+pub fn number_exits_by_reason(df: DataFrame, reason: TerminationReason) -> u32 {
+    let exit_reason_col = PnLReportColumnKind::ExitReason.to_string();
+    let exits = df
+        .lazy()
+        .filter(col(&exit_reason_col).eq(lit(reason.to_string())))
+        .collect()
+        .unwrap();
+    u32::try_from(exits.height()).unwrap()
+}
+
+pub fn pnl_by_exit_reason(df: DataFrame, reason: TerminationReason) -> f64 {
+    let exit_reason_col = PnLReportColumnKind::ExitReason.to_string();
+    let pl_dollar_col = PnLReportColumnKind::PlDollar.to_string();
+
+    let res = df
+        .lazy()
+        .filter(col(&exit_reason_col).eq(lit(reason.to_string())))
+        .select([col(&pl_dollar_col)])
+        .sum()
+        .collect()
+        .unwrap();
+
+    if let AnyValue::Null = res[pl_dollar_col.as_str()].get(0).unwrap() {
+        return 0.0;
+    }
+
+    res[pl_dollar_col.as_str()].get(0).unwrap().unwrap_float64()
+}
+
 pub fn number_no_entry(df: DataFrame) -> u32 {
     let summary = status_summary(df);
     get_number_of_trades_from_summary(summary, "NoEntry")
@@ -263,6 +295,86 @@ pub fn avg_trade(net_profit: f64, number_of_trades: u32) -> f64 {
     net_profit / f64::try_from(number_of_trades).unwrap()
 }
 
+/// Expected PnL per trade = Trefferquote * Avg Win + (1 - Trefferquote) * Avg Loss
+pub fn expectancy(percent_profitability: f64, avg_win: f64, avg_loss: f64) -> f64 {
+    percent_profitability * avg_win + (1.0 - percent_profitability) * avg_loss
+}
+
+/// Standard error of the expectancy, i.e. the sample standard deviation of the trade PnL divided
+/// by the square root of the number of trades. It is undefined, i.e. `NaN`, for less than two
+/// trades.
+pub fn expectancy_std_error(df: DataFrame) -> f64 {
+    let pnl = trade_values(df, PnLReportColumnKind::PlDollar);
+
+    let n = pnl.len() as f64;
+    if n < 2.0 {
+        return f64::NAN;
+    }
+    let mean = pnl.iter().sum::<f64>() / n;
+    let variance = pnl.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (variance / n).sqrt()
+}
+
+/// Average R-multiple of all trades.
+pub fn avg_r_multiple(df: DataFrame) -> f64 {
+    let r_multiples = trade_values(df, PnLReportColumnKind::RMultiple);
+    if r_multiples.is_empty() {
+        return 0.0;
+    }
+    r_multiples.iter().sum::<f64>() / r_multiples.len() as f64
+}
+
+/// R expectancy = Net Profit / Total Initial Risk, i.e. the average R-multiple where every trade is
+/// weighted by its initial risk.
+pub fn r_expectancy(df: DataFrame) -> f64 {
+    let pnl = trade_values(df.clone(), PnLReportColumnKind::PlDollar);
+    let risk = trade_values(df, PnLReportColumnKind::ExpectedLossDollar);
+    let total_risk = risk.iter().map(|x| x.abs()).sum::<f64>();
+    if total_risk == 0.0 {
+        return 0.0;
+    }
+    pnl.iter().sum::<f64>() / total_risk
+}
+
+/// Share of all trades with an R-multiple greater than `r`.
+pub fn percent_trades_above_r_multiple(df: DataFrame, r: f64) -> f64 {
+    let r_multiples = trade_values(df, PnLReportColumnKind::RMultiple);
+    if r_multiples.is_empty() {
+        return 0.0;
+    }
+    let above = r_multiples.iter().filter(|x| **x > r).count();
+    above as f64 / r_multiples.len() as f64
+}
+
+/// Returns the values of `column` for every row with an actual trade.
+pub(crate) fn trade_values(df: DataFrame, column: PnLReportColumnKind) -> Vec<f64> {
+    let status_col = PnLReportColumnKind::Status.to_string();
+    let value_col = column.to_string();
+
+    let trades = df
+        .lazy()
+        .filter(
+            col(&status_col)
+                .neq(lit("NoEntry"))
+                .and(col(&status_col).neq(lit("Not Clear")))
+                .and(col(&status_col).neq(lit("No Trade"))),
+        )
+        .select([col(&value_col)])
+        .collect()
+        .unwrap();
+    trades[value_col.as_str()]
+        .rechunk()
+        .iter()
+        .map(|val| val.unwrap_float64())
+        .collect()
+}
+
+/// Kelly fraction = Trefferquote - (1 - Trefferquote) / CRV, i.e. the fraction of the capital to
+/// risk per trade that maximizes the expected logarithmic growth of the equity.
+pub fn kelly_fraction(percent_profitability: f64, avg_win_by_avg_loss: f64) -> f64 {
+    percent_profitability - (1.0 - percent_profitability) / avg_win_by_avg_loss
+}
+
 pub fn accumulated_profit(df: DataFrame, initial: f64) -> Vec<f64> {
     let pl_dollar_col = PnLReportColumnKind::PlDollar.to_string();
     let series = &df[pl_dollar_col.as_str()];
@@ -273,6 +385,24 @@ pub fn accumulated_profit(df: DataFrame, initial: f64) -> Vec<f64> {
     })
 }
 
+/// Computes the equity after every trade if the position size scales with the current equity,
+/// i.e. the PnL of a trade is scaled by `equity / initial`. Once the equity is not positive, the
+/// account is ruined and the equity does not change anymore.
+pub fn compounded_equity(df: DataFrame, initial: f64) -> Vec<f64> {
+    let pl_dollar_col = PnLReportColumnKind::PlDollar.to_string();
+    let series = &df[pl_dollar_col.as_str()];
+
+    series.rechunk().iter().fold(vec![initial], |mut acc, val| {
+        let equity = *acc.last().unwrap();
+        if equity <= 0.0 {
+            acc.push(equity);
+        } else {
+            acc.push(equity + val.unwrap_float64() * equity / initial);
+        }
+        acc
+    })
+}
+
 pub fn max_draw_down_abs(accumulated_profit: &Vec<f64>) -> f64 {
     max_draw_down(accumulated_profit).1
 }
@@ -299,6 +429,22 @@ mod tests {
     use super::*;
     use polars::{df, prelude::NamedFrom};
 
+    #[test]
+    fn test_breakdown_by_exit_reason() {
+        let df = df!(
+            "PlDollar" => &[10_f64, -5.0, 20.0, 0.0],
+            "ExitReason" => &["TakeProfit", "StopLoss", "TakeProfit", "NoEntry"],
+        )
+        .unwrap();
+
+        assert_eq!(2, number_exits_by_reason(df.clone(), TerminationReason::TakeProfit));
+        assert_eq!(30.0, pnl_by_exit_reason(df.clone(), TerminationReason::TakeProfit));
+        assert_eq!(1, number_exits_by_reason(df.clone(), TerminationReason::StopLoss));
+        assert_eq!(-5.0, pnl_by_exit_reason(df.clone(), TerminationReason::StopLoss));
+        assert_eq!(0, number_exits_by_reason(df.clone(), TerminationReason::SessionEnd));
+        assert_eq!(0.0, pnl_by_exit_reason(df, TerminationReason::SessionEnd));
+    }
+
     #[test]
     fn test_accumulated_profit() {
         let initial = 10_f64;
@@ -327,6 +473,61 @@ mod tests {
         assert_eq!(res[1..], target);
     }
 
+    #[test]
+    fn test_compounded_equity() {
+        let df = df!("PlDollar" => &[50_f64, -50.0, 20.0]).unwrap();
+        assert_eq!(vec![100.0, 150.0, 75.0, 90.0], compounded_equity(df, 100.0));
+    }
+
+    #[test]
+    fn test_compounded_equity_stops_at_ruin() {
+        let df = df!("PlDollar" => &[-50_f64, -120.0, 80.0, -40.0]).unwrap();
+        assert_eq!(
+            vec![100.0, 50.0, -10.0, -10.0, -10.0],
+            compounded_equity(df, 100.0)
+        );
+    }
+
+    #[test]
+    fn test_expectancy_and_kelly_fraction() {
+        let p = percent_profitability(3, 4);
+        assert_eq!(5.0, expectancy(p, 10.0, -10.0));
+        assert_eq!(0.5, kelly_fraction(p, avg_win_by_avg_loose(10.0, -10.0)));
+        assert_eq!(p, kelly_fraction(p, avg_win_by_avg_loose(10.0, 0.0)));
+    }
+
+    #[test]
+    fn test_expectancy_std_error() {
+        let df = df!(
+            "Status" => &["Winner", "Loser", "Winner", "Winner", "NoEntry"],
+            "PlDollar" => &[10_f64, -10.0, 10.0, 10.0, 0.0],
+        )
+        .unwrap();
+        assert_eq!(5.0, expectancy_std_error(df));
+
+        let df = df!(
+            "Status" => &["Winner", "NoEntry"],
+            "PlDollar" => &[10_f64, 0.0],
+        )
+        .unwrap();
+        assert!(expectancy_std_error(df).is_nan());
+    }
+
+    #[test]
+    fn test_r_multiple_statistics() {
+        let df = df!(
+            "Status" => &["Winner", "Loser", "Winner", "NoEntry"],
+            "PlDollar" => &[30.0, -10.0, 20.0, 0.0],
+            "ExpectedLossDollar" => &[-10.0, -10.0, -5.0, -10.0],
+            "RMultiple" => &[3.0, -1.0, 4.0, 0.0],
+        )
+        .unwrap();
+
+        assert_eq!(2.0, avg_r_multiple(df.clone()));
+        assert_eq!(40.0 / 25.0, r_expectancy(df.clone()));
+        assert_eq!(2.0 / 3.0, percent_trades_above_r_multiple(df, 2.0));
+    }
+
     #[test]
     fn test_max_draw_down_abs() {
         let prices = [7.0, 1.0, 5.0, 3.0, 6.0, 4.0];
@@ -1,6 +1,9 @@
 use crate::{
-    bot::time_interval::timestamp_in_milli_to_string,
+    bot::{
+        time_frame_snapshot::TimeFrameSnapshotBuilder, time_interval::timestamp_in_milli_to_string,
+    },
     calculator::pnl_report_data_row_calculator::PnLReportDataRow,
+    chapaty,
     converter::market_decimal_places::MyDecimalPlaces,
     data_frame_operations::io_operations::save_df_as_csv,
     enums::markets::MarketKind,
@@ -10,7 +13,7 @@ use crate::{
 use chrono::NaiveDate;
 use polars::df;
 use polars::prelude::NamedFrom;
-use polars::prelude::{DataFrame, IntoLazy};
+use polars::prelude::{DataFrame, IntoLazy, LazyFrame};
 use std::{collections::HashMap, convert::identity};
 
 use serde::{Deserialize, Serialize};
@@ -74,13 +77,21 @@ impl PnLReportDataRow {
         };
         let pl_tick = match self.trade.trade_kind {
             TradeDirectionKind::None => 0.0,
-            _ => trade_pnl.profit() / tick_factor,
+            _ => self.trade.profit_in_ticks(exit_price, tick_factor),
+        };
+        let pl_dollar = match self.trade.trade_kind {
+            TradeDirectionKind::None => 0.0,
+            _ => pl_tick * tick_to_dollar - self.get_fee(),
         };
-        let pl_dollar = pl_tick * tick_to_dollar;
         let status = match self.trade.trade_kind {
             TradeDirectionKind::None => "No Trade".to_string(),
             _ => determine_status(pl_dollar),
         };
+        let exit_reason = match self.trade.trade_kind {
+            TradeDirectionKind::None => "No Trade".to_string(),
+            _ => trade_pnl.termination_reason().to_string(),
+        };
+        let r_multiple = compute_r_multiple(pl_dollar, expected_loss_dollar);
 
         let n = self.get_decimal_places();
 
@@ -105,6 +116,8 @@ impl PnLReportDataRow {
             &column_names::PnLReportColumnKind::Status.to_string() =>vec![status],
             &column_names::PnLReportColumnKind::PlTick.to_string() =>vec![pl_tick.round_to_n_decimal_places(n)],
             &column_names::PnLReportColumnKind::PlDollar.to_string() =>vec![pl_dollar.round_to_dollar_cents()],
+            &column_names::PnLReportColumnKind::ExitReason.to_string() =>vec![exit_reason],
+            &column_names::PnLReportColumnKind::RMultiple.to_string() =>vec![r_multiple.round_to_n_decimal_places(3)],
         ).unwrap()
     }
 
@@ -149,6 +162,8 @@ impl PnLReportDataRow {
             &column_names::PnLReportColumnKind::Status.to_string() => &["NoEntry".to_string()],
             &column_names::PnLReportColumnKind::PlTick.to_string() => &[0.0],
             &column_names::PnLReportColumnKind::PlDollar.to_string() => &[0.0],
+            &column_names::PnLReportColumnKind::ExitReason.to_string() => &["NoEntry".to_string()],
+            &column_names::PnLReportColumnKind::RMultiple.to_string() => &[0.0],
         )
         .unwrap()
     }
@@ -171,13 +186,24 @@ impl PnLReportDataRow {
     }
 
     fn get_tick_factor(&self) -> f64 {
-        self.market.tick_step_size().map_or_else(|| 1.0, identity)
+        match self.instrument_override {
+            Some(instrument) => instrument.tick_size,
+            None => self.market.tick_step_size().map_or_else(|| 1.0, identity),
+        }
     }
 
     fn get_tick_to_dollar_conversion_factor(&self) -> f64 {
-        self.market
-            .tik_to_dollar_conversion_factor()
-            .map_or_else(|| 1.0, identity)
+        match self.instrument_override {
+            Some(instrument) => instrument.tick_value,
+            None => self
+                .market
+                .tik_to_dollar_conversion_factor()
+                .map_or_else(|| 1.0, identity),
+        }
+    }
+
+    fn get_fee(&self) -> f64 {
+        self.instrument_override.map_or(0.0, |instrument| instrument.fee)
     }
 
     fn get_entry_ts(&self) -> String {
@@ -202,17 +228,13 @@ impl PnLReportDataRow {
     }
 
     fn expected_win_in_tick(&self, tick_factor: f64) -> f64 {
-        let profit = self
-            .trade
-            .profit(self.trade.take_profit.map_or_else(|| 0.0, identity));
-        (profit / tick_factor).round()
+        let take_profit = self.trade.take_profit.map_or_else(|| 0.0, identity);
+        self.trade.profit_in_ticks(take_profit, tick_factor).round()
     }
 
     fn expected_loss_in_tick(&self, tick_factor: f64) -> f64 {
-        let loss = self
-            .trade
-            .profit(self.trade.stop_loss.map_or_else(|| 0.0, identity));
-        (loss / tick_factor).round()
+        let stop_loss = self.trade.stop_loss.map_or_else(|| 0.0, identity);
+        self.trade.profit_in_ticks(stop_loss, tick_factor).round()
     }
 }
 
@@ -232,6 +254,16 @@ fn compute_crv(win: f64, loss: f64) -> f64 {
     }
 }
 
+/// The R-multiple is the profit of a trade in units of its initial risk, i.e. the distance between
+/// the entry and the stop loss.
+fn compute_r_multiple(profit: f64, loss: f64) -> f64 {
+    if loss == 0.0 {
+        0.0
+    } else {
+        profit / loss.abs()
+    }
+}
+
 impl From<PnLReportDataRow> for DataFrame {
     fn from(value: PnLReportDataRow) -> Self {
         match value.trade_pnl {
@@ -243,23 +275,52 @@ impl From<PnLReportDataRow> for DataFrame {
 
 impl FromIterator<PnLReportDataRow> for DataFrame {
     fn from_iter<T: IntoIterator<Item = PnLReportDataRow>>(iter: T) -> Self {
-        iter.into_iter()
+        let ldfs = iter
+            .into_iter()
             .fold(Vec::new(), |mut ldfs, pnl_report_data_row| {
                 let df: DataFrame = pnl_report_data_row.into();
                 ldfs.push(df.lazy());
                 ldfs
-            })
-            .concatenate_to_lazy_frame()
-            .sort_by_date()
-            .collect()
-            .unwrap()
-            .with_row_count(&PnLReportColumnKind::Id.to_string(), Some(1))
-            .unwrap()
-            .with_row_count(&PnLReportColumnKind::Uid.to_string(), Some(1))
-            .unwrap()
+            });
+        into_pnl_report(ldfs)
     }
 }
 
+/// Groups the rows of a pnl report by calendar week. Each segment is stored without the `Id` and
+/// `Uid` columns, such that segments of different runs can be merged with
+/// `pnl_report_from_segments`.
+pub fn pnl_report_segments(rows: Vec<PnLReportDataRow>) -> chapaty::types::DataFrameMap {
+    rows.into_iter()
+        .fold(HashMap::new(), |mut segments: HashMap<_, Vec<LazyFrame>>, row| {
+            let cw = row.time_frame_snapshot.get_calendar_week_as_int();
+            let df: DataFrame = row.into();
+            segments
+                .entry(TimeFrameSnapshotBuilder::new(cw).build())
+                .or_default()
+                .push(df.lazy());
+            segments
+        })
+        .into_iter()
+        .map(|(snapshot, ldfs)| (snapshot, ldfs.concatenate_to_data_frame()))
+        .collect()
+}
+
+/// Merges the weekly segments of a pnl report into a pnl report.
+pub fn pnl_report_from_segments(segments: &chapaty::types::DataFrameMap) -> DataFrame {
+    into_pnl_report(segments.values().map(|df| df.clone().lazy()).collect())
+}
+
+fn into_pnl_report(ldfs: Vec<LazyFrame>) -> DataFrame {
+    ldfs.concatenate_to_lazy_frame()
+        .sort_by_date()
+        .collect()
+        .unwrap()
+        .with_row_count(&PnLReportColumnKind::Id.to_string(), Some(1))
+        .unwrap()
+        .with_row_count(&PnLReportColumnKind::Uid.to_string(), Some(1))
+        .unwrap()
+}
+
 pub struct PnLReport {
     pub market: MarketKind,
     pub year: u32,
@@ -319,3 +380,90 @@ impl PnLReportsBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{instrument_override::InstrumentOverride, trade::Trade},
+        calculator::trade_pnl_calculator::{PnL, TradePnL},
+        enums::trade_and_pre_trade::TerminationReason,
+    };
+
+    #[test]
+    fn test_pnl_report_from_segments() {
+        let date = PnLReportColumnKind::Date.to_string();
+        let pl = PnLReportColumnKind::PlDollar.to_string();
+        let segments = HashMap::from([
+            (
+                TimeFrameSnapshotBuilder::new(2).build(),
+                df!(&date => &["2022-01-10", "2022-01-11"], &pl => &[3.0, -1.0]).unwrap(),
+            ),
+            (
+                TimeFrameSnapshotBuilder::new(1).build(),
+                df!(&date => &["2022-01-03"], &pl => &[2.0]).unwrap(),
+            ),
+        ]);
+
+        let target = df!(
+            &PnLReportColumnKind::Uid.to_string() => &[1_u32, 2, 3],
+            &PnLReportColumnKind::Id.to_string() => &[1_u32, 2, 3],
+            &date => &["2022-01-03", "2022-01-10", "2022-01-11"],
+            &pl => &[2.0, 3.0, -1.0],
+        )
+        .unwrap();
+        assert_eq!(target, pnl_report_from_segments(&segments));
+    }
+
+    #[test]
+    fn test_compute_r_multiple() {
+        assert_eq!(2.5, compute_r_multiple(50.0, -20.0));
+        assert_eq!(-1.0, compute_r_multiple(-20.0, -20.0));
+        assert_eq!(0.0, compute_r_multiple(10.0, 0.0));
+    }
+
+    #[test]
+    fn test_report_with_instrument_override() {
+        let row = PnLReportDataRow {
+            market: MarketKind::EurUsdFuture,
+            year: 2022,
+            strategy_name: "ppp".to_string(),
+            time_frame_snapshot: TimeFrameSnapshotBuilder::new(2).with_weekday(1).build(),
+            trade: Trade {
+                entry_price: 1.1,
+                stop_loss: Some(1.099),
+                take_profit: Some(1.102),
+                trade_kind: TradeDirectionKind::Long,
+                max_holding_period: None,
+            },
+            trade_pnl: Some(TradePnL {
+                trade_entry_ts: 0,
+                stop_loss: None,
+                take_profit: Some(PnL {
+                    price: 1.102,
+                    ts: Some(60_000),
+                    profit: Some(0.002),
+                }),
+                timeout: None,
+                timeout_reason: TerminationReason::Timeout,
+            }),
+            instrument_override: None,
+        };
+        let column = |report: &DataFrame, column: PnLReportColumnKind| {
+            report.column(&column.to_string()).unwrap().f64().unwrap().into_iter().next().unwrap().unwrap()
+        };
+
+        let full_size = DataFrame::from(row.clone());
+        assert_eq!(250.0, column(&full_size, PnLReportColumnKind::PlDollar));
+        assert_eq!(2.0, column(&full_size, PnLReportColumnKind::RMultiple));
+
+        let micro = DataFrame::from(PnLReportDataRow {
+            instrument_override: Some(InstrumentOverride::new(0.0001, 1.25, 0.5)),
+            ..row
+        });
+        assert_eq!(20.0, column(&micro, PnLReportColumnKind::PlTick));
+        assert_eq!(24.5, column(&micro, PnLReportColumnKind::PlDollar));
+        assert_eq!(-12.5, column(&micro, PnLReportColumnKind::ExpectedLossDollar));
+        assert_eq!(1.96, column(&micro, PnLReportColumnKind::RMultiple));
+    }
+}
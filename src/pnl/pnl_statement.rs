@@ -1,14 +1,15 @@
 use crate::{
     converter::pnl_to_report::{as_equity_curve, PnLToReportRequestBuilder},
     enums::markets::MarketKind,
-    equity_curve::{EquityCurves, EquityCurvesReport},
+    equity_curve::{Capital, EquityCurves, EquityCurvesReport},
     lazy_frame_operations::trait_extensions::{MyLazyFrameOperations, MyLazyFrameVecOperations},
     performance_report::PerformanceReports,
+    report_column::ReportColumn,
     trade_breakdown_report::TradeBreakdownReports, PnLReportColumnKind,
 };
 use polars::prelude::{DataFrame, IntoLazy, LazyFrame};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use super::pnl_report::PnLReports;
 
@@ -17,6 +18,9 @@ pub struct PnLStatement {
     pub strategy_name: String,
     pub markets: Vec<MarketKind>,
     pub pnl_data: HashMap<MarketKind, PnLReports>,
+    pub capital: Capital,
+    #[serde(skip)]
+    pub report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
 }
 
 impl PnLStatement {
@@ -55,7 +59,8 @@ impl PnLStatement {
     pub fn compute_trade_breakdown_report(&self) -> TradeBreakdownReports {
         let request_builder = PnLToReportRequestBuilder::new()
             .is_agg_markets(false)
-            .is_agg_years(false);
+            .is_agg_years(false)
+            .with_report_columns(self.report_columns.clone());
         let trade_breakdown_reports: HashMap<MarketKind, DataFrame> = self
             .pnl_data
             .iter()
@@ -92,7 +97,8 @@ impl PnLStatement {
     pub fn compute_performance_report(&self) -> PerformanceReports {
         let request_builder = PnLToReportRequestBuilder::new()
             .is_agg_markets(false)
-            .is_agg_years(false);
+            .is_agg_years(false)
+            .with_capital(self.capital);
         let trade_breakdown_reports: HashMap<MarketKind, DataFrame> = self
             .pnl_data
             .iter()
@@ -135,7 +141,9 @@ impl PnLStatement {
                 let curves = pnl_reports
                     .reports
                     .iter()
-                    .map(|(year, pnl_report)| (*year, as_equity_curve(&pnl_report, false)))
+                    .map(|(year, pnl_report)| {
+                        (*year, as_equity_curve(pnl_report, false, &self.capital))
+                    })
                     .collect();
                 (
                     *market,
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use polars::prelude::{DataFrame, IntoLazy, LazyFrame};
 use serde::{Deserialize, Serialize};
@@ -6,9 +6,10 @@ use serde::{Deserialize, Serialize};
 use crate::{
     converter::pnl_to_report::{as_equity_curve, PnLToReportRequestBuilder},
     data_frame_operations::io_operations::save_df_as_csv,
-    equity_curve::EquityCurvesAggMarkets,
+    equity_curve::{Capital, EquityCurvesAggMarkets},
     lazy_frame_operations::trait_extensions::{MyLazyFrameVecOperations, MyLazyFrameOperations},
     performance_report::PerformanceReportAggMarkets,
+    report_column::ReportColumn,
     trade_breakdown_report::TradeBreakDownReportAggMarkets,
     MarketKind, PnLReportColumnKind,
 };
@@ -21,6 +22,9 @@ pub struct PnLStatementAggMarkets {
     pub markets: Vec<MarketKind>,
     pub years: Vec<u32>,
     pub pnl_data: HashMap<u32, DataFrame>,
+    pub capital: Capital,
+    #[serde(skip)]
+    pub report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
 }
 
 impl PnLStatementAggMarkets {
@@ -48,7 +52,8 @@ impl PnLStatementAggMarkets {
     pub fn compute_trade_breakdown_report(&self) -> TradeBreakDownReportAggMarkets {
         let request_builder = PnLToReportRequestBuilder::new()
             .is_agg_markets(true)
-            .is_agg_years(false);
+            .is_agg_years(false)
+            .with_report_columns(self.report_columns.clone());
         let trade_breakdown_reports = self
             .pnl_data
             .iter()
@@ -75,7 +80,8 @@ impl PnLStatementAggMarkets {
     pub fn compute_performance_report(&self) -> PerformanceReportAggMarkets {
         let request_builder = PnLToReportRequestBuilder::new()
             .is_agg_markets(true)
-            .is_agg_years(false);
+            .is_agg_years(false)
+            .with_capital(self.capital);
         let trade_breakdown_reports = self
             .pnl_data
             .iter()
@@ -104,7 +110,7 @@ impl PnLStatementAggMarkets {
         let equity_curves = self
             .pnl_data
             .iter()
-            .map(|(year, pnl_report)| (*year, as_equity_curve(&pnl_report, true)))
+            .map(|(year, pnl_report)| (*year, as_equity_curve(pnl_report, true, &self.capital)))
             .collect();
 
         EquityCurvesAggMarkets {
@@ -122,6 +128,8 @@ impl From<PnLStatement> for PnLStatementAggMarkets {
             markets: value.markets.clone(),
             years: value.get_years(),
             pnl_data: value.agg_markets(),
+            capital: value.capital,
+            report_columns: value.report_columns.clone(),
         }
     }
 }
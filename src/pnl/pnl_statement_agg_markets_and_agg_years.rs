@@ -1,9 +1,12 @@
 use polars::prelude::DataFrame;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use crate::{
     converter::pnl_to_report::{as_equity_curve, PnLToReportRequestBuilder},
     data_frame_operations::io_operations::save_df_as_csv,
+    equity_curve::Capital,
+    report_column::ReportColumn,
     MarketKind, PnLReportColumnKind,
 };
 
@@ -15,6 +18,9 @@ pub struct PnLStatementAggMarketsAggYears {
     pub markets: Vec<MarketKind>,
     pub years: Vec<u32>,
     pub pnl: DataFrame,
+    pub capital: Capital,
+    #[serde(skip)]
+    pub report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
 }
 
 impl PnLStatementAggMarketsAggYears {
@@ -36,6 +42,8 @@ impl From<PnLStatementAggMarkets> for PnLStatementAggMarketsAggYears {
                 .agg_year()
                 .with_row_count(&PnLReportColumnKind::Uid.to_string(), Some(1))
                 .unwrap(),
+            capital: value.capital,
+            report_columns: value.report_columns.clone(),
         }
     }
 }
@@ -45,6 +53,7 @@ impl PnLStatementAggMarketsAggYears {
         PnLToReportRequestBuilder::new()
             .is_agg_markets(true)
             .is_agg_years(true)
+            .with_report_columns(self.report_columns.clone())
             .with_pnl(self.pnl.clone())
             .with_strategy(self.strategy_name.clone())
             .build()
@@ -57,6 +66,7 @@ impl PnLStatementAggMarketsAggYears {
         PnLToReportRequestBuilder::new()
             .is_agg_markets(true)
             .is_agg_years(true)
+            .with_capital(self.capital)
             .with_pnl(self.pnl.clone())
             .with_strategy(self.strategy_name.clone())
             .build()
@@ -66,6 +76,6 @@ impl PnLStatementAggMarketsAggYears {
     }
 
     pub fn compute_equity_curve(&self) -> Vec<f64> {
-        as_equity_curve(&self.pnl, true)
+        as_equity_curve(&self.pnl, true, &self.capital)
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use polars::prelude::DataFrame;
 use serde::{Deserialize, Serialize};
@@ -6,8 +6,9 @@ use serde::{Deserialize, Serialize};
 use crate::{
     converter::pnl_to_report::{as_equity_curve, PnLToReportRequestBuilder},
     data_frame_operations::io_operations::save_df_as_csv,
-    equity_curve::EquityCurvesAggYears,
+    equity_curve::{Capital, EquityCurvesAggYears},
     performance_report::PerformanceReportsAggYears,
+    report_column::ReportColumn,
     trade_breakdown_report::TradeBreakDownReportsAggYears,
     MarketKind, PnLReportColumnKind,
 };
@@ -20,6 +21,9 @@ pub struct PnLStatementAggYears {
     pub markets: Vec<MarketKind>,
     pub years: Vec<u32>,
     pub pnl_data: HashMap<MarketKind, DataFrame>,
+    pub capital: Capital,
+    #[serde(skip)]
+    pub report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>>,
 }
 
 impl PnLStatementAggYears {
@@ -35,7 +39,8 @@ impl PnLStatementAggYears {
     pub fn compute_trade_breakdown_reports(&self) -> TradeBreakDownReportsAggYears {
         let request_builder = PnLToReportRequestBuilder::new()
             .is_agg_markets(false)
-            .is_agg_years(true);
+            .is_agg_years(true)
+            .with_report_columns(self.report_columns.clone());
         let trade_breakdown_reports = self
             .pnl_data
             .iter()
@@ -64,7 +69,8 @@ impl PnLStatementAggYears {
     pub fn compute_performance_reports(&self) -> PerformanceReportsAggYears {
         let request_builder = PnLToReportRequestBuilder::new()
             .is_agg_markets(false)
-            .is_agg_years(true);
+            .is_agg_years(true)
+            .with_capital(self.capital);
         let performance_reports = self
             .pnl_data
             .iter()
@@ -94,7 +100,7 @@ impl PnLStatementAggYears {
         let equity_curves = self
             .pnl_data
             .iter()
-            .map(|(market, pnl)| (*market, as_equity_curve(pnl, false)))
+            .map(|(market, pnl)| (*market, as_equity_curve(pnl, false, &self.capital)))
             .collect();
 
         EquityCurvesAggYears {
@@ -128,6 +134,8 @@ impl From<PnLStatement> for PnLStatementAggYears {
             markets: value.markets,
             years,
             pnl_data,
+            capital: value.capital,
+            report_columns: value.report_columns,
         }
     }
 }
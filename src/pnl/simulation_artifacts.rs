@@ -0,0 +1,121 @@
+use crate::enums::{
+    column_names::{PnLReportColumnKind, SimulationArtifactsReportColumnKind},
+    trade_and_pre_trade::{SimulationArtifactKind, TerminationReason},
+};
+use polars::prelude::{BooleanChunked, DataFrame, NamedFrom, Series};
+
+/// Returns the trades of the profit and loss report `pnl` that show a `SimulationArtifactKind`,
+/// with an additional column listing the detected artifacts, e.g. `"SameBarExit,AmbiguousExit"`.
+pub fn detect_simulation_artifacts(pnl: &DataFrame) -> DataFrame {
+    let column = |kind: PnLReportColumnKind| pnl.column(&kind.to_string()).unwrap();
+    let entry_ts = column(PnLReportColumnKind::EntryTimestamp).utf8().unwrap();
+    let take_profit_ts = column(PnLReportColumnKind::TakeProfitTimestamp)
+        .utf8()
+        .unwrap();
+    let stop_loss_ts = column(PnLReportColumnKind::StopLossTimestamp)
+        .utf8()
+        .unwrap();
+    let exit_reason = column(PnLReportColumnKind::ExitReason).utf8().unwrap();
+    let expected_loss_tick = column(PnLReportColumnKind::ExpectedLossTick).f64().unwrap();
+
+    let artifacts: Vec<Vec<SimulationArtifactKind>> = entry_ts
+        .into_iter()
+        .zip(take_profit_ts)
+        .zip(stop_loss_ts)
+        .zip(exit_reason)
+        .zip(expected_loss_tick)
+        .map(|((((entry, take_profit), stop_loss), exit_reason), loss)| {
+            let trade = TradeTimestamps {
+                entry: entry.unwrap_or_default(),
+                take_profit: take_profit.unwrap_or_default(),
+                stop_loss: stop_loss.unwrap_or_default(),
+            };
+            trade.artifacts(exit_reason.unwrap_or_default(), loss.unwrap_or_default())
+        })
+        .collect();
+
+    let mask: BooleanChunked = artifacts.iter().map(|a| !a.is_empty()).collect();
+    let names: Vec<String> = artifacts
+        .iter()
+        .filter(|a| !a.is_empty())
+        .map(|a| {
+            a.iter()
+                .map(|kind| kind.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect();
+
+    let mut report = pnl.filter(&mask).unwrap();
+    report
+        .with_column(Series::new(
+            &SimulationArtifactsReportColumnKind::Artifacts.to_string(),
+            names,
+        ))
+        .unwrap();
+    report
+}
+
+struct TradeTimestamps<'a> {
+    entry: &'a str,
+    take_profit: &'a str,
+    stop_loss: &'a str,
+}
+
+impl TradeTimestamps<'_> {
+    fn artifacts(&self, exit_reason: &str, expected_loss_tick: f64) -> Vec<SimulationArtifactKind> {
+        let is_take_profit = exit_reason == TerminationReason::TakeProfit.to_string();
+        let is_stop_loss = exit_reason == TerminationReason::StopLoss.to_string();
+        let exit = if is_take_profit {
+            Some(self.take_profit)
+        } else if is_stop_loss {
+            Some(self.stop_loss)
+        } else {
+            None
+        };
+
+        let is_same_bar_exit = exit == Some(self.entry);
+        let mut artifacts = Vec::new();
+        if is_same_bar_exit {
+            artifacts.push(SimulationArtifactKind::SameBarExit);
+        }
+        if is_stop_loss && is_same_bar_exit && expected_loss_tick.abs() <= 1.0 {
+            artifacts.push(SimulationArtifactKind::ImmediateStopLoss);
+        }
+        if is_stop_loss && self.take_profit == self.stop_loss {
+            artifacts.push(SimulationArtifactKind::AmbiguousExit);
+        }
+        artifacts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::df;
+
+    #[test]
+    fn test_detect_simulation_artifacts() {
+        let pnl = df!(
+            "Id" => &[1_u32, 2, 3, 4, 5, 6],
+            "ExpectedLossTick" => &[-20.0, -20.0, -1.0, -20.0, 0.0, -1.0],
+            "EntryTimestamp" => &["2022-01-03 10:00:00", "2022-01-04 10:00:00", "2022-01-05 10:00:00", "2022-01-06 10:00:00", "NoEntry", "2022-01-07 10:00:00"],
+            "TakeProfitTimestamp" => &["2022-01-03 10:00:00", "2022-01-04 11:00:00", "Timeout", "2022-01-06 12:00:00", "NoEntry", "Timeout"],
+            "StopLossTimestamp" => &["Timeout", "2022-01-04 12:00:00", "2022-01-05 10:00:00", "2022-01-06 12:00:00", "NoEntry", "2022-01-07 15:00:00"],
+            "ExitReason" => &["TakeProfit", "TakeProfit", "StopLoss", "StopLoss", "NoEntry", "StopLoss"],
+        )
+        .unwrap();
+
+        let target = df!(
+            "Id" => &[1_u32, 3, 4],
+            "ExpectedLossTick" => &[-20.0, -1.0, -20.0],
+            "EntryTimestamp" => &["2022-01-03 10:00:00", "2022-01-05 10:00:00", "2022-01-06 10:00:00"],
+            "TakeProfitTimestamp" => &["2022-01-03 10:00:00", "Timeout", "2022-01-06 12:00:00"],
+            "StopLossTimestamp" => &["Timeout", "2022-01-05 10:00:00", "2022-01-06 12:00:00"],
+            "ExitReason" => &["TakeProfit", "StopLoss", "StopLoss"],
+            "Artifacts" => &["SameBarExit", "SameBarExit,ImmediateStopLoss", "AmbiguousExit"],
+        )
+        .unwrap();
+        assert_eq!(target, detect_simulation_artifacts(&pnl));
+    }
+}
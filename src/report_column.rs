@@ -0,0 +1,153 @@
+use crate::enums::error::ChapatyErrorKind;
+use polars::prelude::{DataFrame, NamedFrom, Series};
+use std::{fmt::Debug, sync::Arc};
+
+/// A `ReportColumn` appends a custom computed column to the profit and loss reports and the trade
+/// breakdown reports of a `Bot`, e.g. the time in trade or the maximum adverse excursion of every
+/// trade.
+pub trait ReportColumn: Debug {
+    /// The name of the appended column.
+    fn name(&self) -> String;
+
+    /// Computes one value for every row of a profit and loss report.
+    fn compute_trade_values(&self, pnl: &DataFrame) -> Series;
+
+    /// Computes the value of the trade breakdown report from the profit and loss report of a
+    /// period, e.g. a year or all years. By default, the trade values are summed up.
+    fn compute_period_value(&self, pnl: &DataFrame) -> Option<f64> {
+        pnl.column(&self.name())
+            .ok()
+            .and_then(|values| values.sum())
+    }
+}
+
+/// Appends the trade values of every `ReportColumn` to the profit and loss report.
+///
+/// # Errors
+/// Returns an `InvalidReportColumn` error if the name of a `ReportColumn` is already a column of
+/// the report, or if it does not compute one value for every row of the report.
+pub fn append_trade_columns(
+    mut pnl: DataFrame,
+    report_columns: &[Arc<dyn ReportColumn + Send + Sync>],
+) -> Result<DataFrame, ChapatyErrorKind> {
+    for report_column in report_columns {
+        let name = report_column.name();
+        if pnl.get_column_names().contains(&name.as_str()) {
+            return Err(ChapatyErrorKind::InvalidReportColumn(format!(
+                "Report column <{name}> clashes with an existing column of the report"
+            )));
+        }
+        let mut values = report_column.compute_trade_values(&pnl);
+        if values.len() != pnl.height() {
+            return Err(ChapatyErrorKind::InvalidReportColumn(format!(
+                "Report column <{name}> has <{}> values, but the report has <{}> rows",
+                values.len(),
+                pnl.height()
+            )));
+        }
+        values.rename(&name);
+        pnl.with_column(values).unwrap();
+    }
+    Ok(pnl)
+}
+
+/// Appends the period value of every `ReportColumn` to the trade breakdown report of `pnl`.
+pub fn append_period_columns(
+    mut trade_breakdown: DataFrame,
+    pnl: &DataFrame,
+    report_columns: &[Arc<dyn ReportColumn + Send + Sync>],
+) -> DataFrame {
+    for report_column in report_columns {
+        let value = report_column.compute_period_value(pnl);
+        trade_breakdown
+            .with_column(Series::new(&report_column.name(), &[value]))
+            .unwrap();
+    }
+    trade_breakdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::column_names::PnLReportColumnKind;
+    use polars::df;
+
+    #[derive(Debug)]
+    struct PlDollarSquared;
+
+    impl ReportColumn for PlDollarSquared {
+        fn name(&self) -> String {
+            "PlDollarSquared".to_string()
+        }
+
+        fn compute_trade_values(&self, pnl: &DataFrame) -> Series {
+            let pl_dollar = &pnl[PnLReportColumnKind::PlDollar.to_string().as_str()];
+            pl_dollar * pl_dollar
+        }
+    }
+
+    #[test]
+    fn test_append_report_columns() {
+        let report_columns: Vec<Arc<dyn ReportColumn + Send + Sync>> =
+            vec![Arc::new(PlDollarSquared)];
+        let pnl = df!("PlDollar" => &[2.0, -3.0]).unwrap();
+
+        let pnl = append_trade_columns(pnl, &report_columns).unwrap();
+        let target = df!(
+            "PlDollar" => &[2.0, -3.0],
+            "PlDollarSquared" => &[4.0, 9.0],
+        )
+        .unwrap();
+        assert_eq!(target, pnl);
+
+        let trade_breakdown = df!("Year" => &["2022"]).unwrap();
+        let target = df!(
+            "Year" => &["2022"],
+            "PlDollarSquared" => &[13.0],
+        )
+        .unwrap();
+        assert_eq!(
+            target,
+            append_period_columns(trade_breakdown, &pnl, &report_columns)
+        );
+    }
+
+    #[derive(Debug)]
+    struct ConstantColumn {
+        name: &'static str,
+        len: usize,
+    }
+
+    impl ReportColumn for ConstantColumn {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn compute_trade_values(&self, _pnl: &DataFrame) -> Series {
+            Series::new(self.name, vec![1.0; self.len])
+        }
+    }
+
+    #[test]
+    fn test_append_invalid_report_columns() {
+        let pnl = df!("PlDollar" => &[2.0, -3.0]).unwrap();
+        let name_clash: Vec<Arc<dyn ReportColumn + Send + Sync>> = vec![Arc::new(ConstantColumn {
+            name: "PlDollar",
+            len: 2,
+        })];
+        assert!(matches!(
+            append_trade_columns(pnl.clone(), &name_clash),
+            Err(ChapatyErrorKind::InvalidReportColumn(_))
+        ));
+
+        let length_mismatch: Vec<Arc<dyn ReportColumn + Send + Sync>> =
+            vec![Arc::new(ConstantColumn {
+                name: "Constant",
+                len: 3,
+            })];
+        assert!(matches!(
+            append_trade_columns(pnl, &length_mismatch),
+            Err(ChapatyErrorKind::InvalidReportColumn(_))
+        ));
+    }
+}
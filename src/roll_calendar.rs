@@ -0,0 +1,147 @@
+use crate::{
+    data_frame_operations::io_operations::save_df_as_csv,
+    enums::{
+        column_names::{DataProviderColumnKind, RollCalendarColumnKind},
+        error::ChapatyErrorKind,
+    },
+};
+use chrono::{DateTime, NaiveDate};
+use polars::{
+    df,
+    prelude::{DataFrame, DataType, NamedFrom},
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// The roll calendar of a futures market, i.e. the date from which on each contract month was the
+/// front month by volume. Compare it with the roll dates of a continuous contract to validate the
+/// stitching.
+///
+/// ```
+/// // Date       ,Contract ,Volume
+/// // 2022-01-03 ,6EH2     ,154321.0
+/// // 2022-03-10 ,6EM2     ,201234.0
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollCalendar {
+    pub calendar: DataFrame,
+}
+
+impl RollCalendar {
+    /// Computes the roll calendar from the OHLCV data of every contract month, e.g. `"6EH2"`. The
+    /// front month of a UTC calendar day is the contract with the highest volume on that day.
+    pub fn from_contracts(
+        contracts: &HashMap<String, DataFrame>,
+    ) -> Result<Self, ChapatyErrorKind> {
+        let mut front_months: BTreeMap<NaiveDate, (String, f64)> = BTreeMap::new();
+        for (contract, data) in contracts {
+            for (day, volume) in daily_volume(data)? {
+                let front_month = front_months
+                    .entry(day)
+                    .or_insert_with(|| (contract.clone(), volume));
+                if volume > front_month.1 || (volume == front_month.1 && *contract < front_month.0)
+                {
+                    *front_month = (contract.clone(), volume);
+                }
+            }
+        }
+
+        let mut dates = Vec::new();
+        let mut contract_months = Vec::new();
+        let mut volumes = Vec::new();
+        for (day, (contract, volume)) in front_months {
+            if contract_months.last() != Some(&contract) {
+                dates.push(day.format("%Y-%m-%d").to_string());
+                contract_months.push(contract);
+                volumes.push(volume);
+            }
+        }
+
+        let calendar = df!(
+            &RollCalendarColumnKind::Date.to_string() => dates,
+            &RollCalendarColumnKind::Contract.to_string() => contract_months,
+            &RollCalendarColumnKind::Volume.to_string() => volumes,
+        )
+        .unwrap();
+        Ok(Self { calendar })
+    }
+
+    pub fn save_as_csv(&self, file_name: &str) {
+        save_df_as_csv(
+            &mut self.calendar.clone(),
+            &format!("{file_name}_roll_calendar"),
+        )
+    }
+}
+
+fn daily_volume(data: &DataFrame) -> Result<BTreeMap<NaiveDate, f64>, ChapatyErrorKind> {
+    let to_error = |e: polars::prelude::PolarsError| {
+        ChapatyErrorKind::FailedToComputeRollCalendar(e.to_string())
+    };
+    let open_time = data
+        .column(&DataProviderColumnKind::OpenTime.to_string())
+        .and_then(|ots| ots.cast(&DataType::Int64))
+        .map_err(to_error)?;
+    let volume = data
+        .column(&DataProviderColumnKind::Volume.to_string())
+        .and_then(|vol| vol.cast(&DataType::Float64))
+        .map_err(to_error)?;
+
+    let mut daily_volume = BTreeMap::new();
+    let rows = open_time
+        .i64()
+        .unwrap()
+        .into_iter()
+        .zip(volume.f64().unwrap());
+    for (ots, vol) in rows {
+        if let (Some(ots), Some(vol)) = (ots, vol) {
+            let day = DateTime::from_timestamp(ots / 1000, 0)
+                .ok_or_else(|| {
+                    ChapatyErrorKind::FailedToComputeRollCalendar(format!(
+                        "Invalid open time <{ots}>"
+                    ))
+                })?
+                .date_naive();
+            *daily_volume.entry(day).or_insert(0.0) += vol;
+        }
+    }
+    Ok(daily_volume)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roll_calendar_from_contracts() {
+        // 2022-03-09 00:00:00 UTC, 2022-03-10 00:00:00 UTC, 2022-03-11 00:00:00 UTC
+        let ots = [1646784000000_i64, 1646870400000, 1646956800000];
+        let contracts = HashMap::from([
+            (
+                "6EH2".to_string(),
+                df!("ots" => &ots, "vol" => &[300.0, 150.0, 50.0]).unwrap(),
+            ),
+            (
+                "6EM2".to_string(),
+                df!("ots" => &ots[1..], "vol" => &[100.0, 250.0]).unwrap(),
+            ),
+            (
+                "6EU2".to_string(),
+                df!("ots" => &ots[2..], "vol" => &[250.0]).unwrap(),
+            ),
+        ]);
+
+        let target = df!(
+            "Date" => &["2022-03-09", "2022-03-11"],
+            "Contract" => &["6EH2", "6EM2"],
+            "Volume" => &[300.0, 250.0],
+        )
+        .unwrap();
+        assert_eq!(
+            target,
+            RollCalendar::from_contracts(&contracts).unwrap().calendar
+        );
+
+        let missing_volume = HashMap::from([("6EH2".to_string(), df!("ots" => &ots).unwrap())]);
+        assert!(RollCalendar::from_contracts(&missing_volume).is_err());
+    }
+}
@@ -1,10 +1,21 @@
+pub mod compression;
 pub mod deserialize;
 pub mod serialize;
 
+/// Version of the serialized data frame map layout. Increment it whenever the layout changes and
+/// migrate older versions in `deserialize_data_frame_map`.
+pub const DATA_FRAME_MAP_VERSION: u32 = 1;
+
 #[cfg(test)]
 mod tests {
-    use super::{deserialize::deserialize_data_frame_map, serialize::serialize_data_frame_map};
-    use crate::bot::time_frame_snapshot::TimeFrameSnapshotBuilder;
+    use super::{
+        deserialize::deserialize_data_frame_map, serialize::serialize_data_frame_map,
+        DATA_FRAME_MAP_VERSION,
+    };
+    use crate::{
+        bot::time_frame_snapshot::TimeFrameSnapshotBuilder, enums::data::CompressionKind,
+        ChapatyErrorKind,
+    };
     use polars::{df, prelude::NamedFrom};
     use std::collections::HashMap;
 
@@ -18,10 +29,44 @@ mod tests {
 
         let df_map = HashMap::from([(time_frame_snapshot, df.unwrap())]);
 
-        let ser = serialize_data_frame_map(&df_map);
-        let bytes: Vec<u8> = ser.into_bytes();
-        let des = deserialize_data_frame_map(bytes);
+        for compression in [CompressionKind::None, CompressionKind::Zstd { level: 3 }] {
+            let bytes = serialize_data_frame_map(&df_map, compression).unwrap();
+            let des = deserialize_data_frame_map(bytes).unwrap();
+            assert_eq!(df_map, des);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_versions() {
+        let time_frame_snapshot = TimeFrameSnapshotBuilder::new(1).with_weekday(1).build();
+        let df_map = HashMap::from([(time_frame_snapshot, df!("a" => [1, 2]).unwrap())]);
+        let versioned: serde_json::Value = serde_json::from_slice(
+            &serialize_data_frame_map(&df_map, CompressionKind::None).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(DATA_FRAME_MAP_VERSION as u64, versioned["version"]);
 
-        assert_eq!(df_map, des);
+        let unversioned = versioned["df_map"].to_string().into_bytes();
+        assert_eq!(df_map, deserialize_data_frame_map(unversioned).unwrap());
+
+        let mut unknown_version = versioned;
+        unknown_version["version"] = serde_json::json!(DATA_FRAME_MAP_VERSION + 1);
+        let res = deserialize_data_frame_map(unknown_version.to_string().into_bytes());
+        assert!(matches!(
+            res,
+            Err(ChapatyErrorKind::IncompatibleSerializationVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_malformed_data() {
+        for malformed in [
+            r#"{"version":1,"df_map":[[{"cw":1}]"#,
+            r#"{"version":1}"#,
+            "not json",
+        ] {
+            let res = deserialize_data_frame_map(malformed.to_string().into_bytes());
+            assert!(matches!(res, Err(ChapatyErrorKind::CorruptCachedData(_))));
+        }
     }
 }
@@ -0,0 +1,91 @@
+use crate::enums::{data::CompressionKind, error::ChapatyErrorKind};
+use std::io::{self, Read, Write};
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+const LZ4_MAGIC: [u8; 4] = [0x04, 0x22, 0x4D, 0x18];
+
+pub fn compress(bytes: Vec<u8>, compression: CompressionKind) -> Result<Vec<u8>, ChapatyErrorKind> {
+    match compression {
+        CompressionKind::None => Ok(bytes),
+        CompressionKind::Zstd { level } => {
+            zstd::encode_all(bytes.as_slice(), level).map_err(to_serialization_error)
+        }
+        CompressionKind::Lz4 { level } => {
+            let mut encoder = lz4::EncoderBuilder::new()
+                .level(level)
+                .build(Vec::new())
+                .map_err(to_serialization_error)?;
+            encoder.write_all(&bytes).map_err(to_serialization_error)?;
+            let (compressed, result) = encoder.finish();
+            result.map_err(to_serialization_error)?;
+            Ok(compressed)
+        }
+    }
+}
+
+/// Detects the compression by the magic number of the frame, such that data that was cached with
+/// a different `CompressionKind` can still be read. Returns `ChapatyErrorKind::CorruptCachedData`
+/// if the frame cannot be decoded, e.g. because the cached object is truncated.
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, ChapatyErrorKind> {
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        zstd::decode_all(bytes.as_slice()).map_err(to_corrupt_cached_data_error)
+    } else if bytes.starts_with(&LZ4_MAGIC) {
+        let mut decompressed = Vec::new();
+        let mut decoder =
+            lz4::Decoder::new(bytes.as_slice()).map_err(to_corrupt_cached_data_error)?;
+        decoder
+            .read_to_end(&mut decompressed)
+            .map_err(to_corrupt_cached_data_error)?;
+        // The decoder stops at the end of a truncated frame without an error
+        let (_, result) = decoder.finish();
+        result.map_err(to_corrupt_cached_data_error)?;
+        Ok(decompressed)
+    } else {
+        Ok(bytes)
+    }
+}
+
+fn to_serialization_error(error: io::Error) -> ChapatyErrorKind {
+    ChapatyErrorKind::FailedToSerializeCachedData(error.to_string())
+}
+
+fn to_corrupt_cached_data_error(error: io::Error) -> ChapatyErrorKind {
+    ChapatyErrorKind::CorruptCachedData(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_roundtrip() {
+        let bytes = br#"{"version":1,"df_map":[]}"#.repeat(100);
+        for compression in [
+            CompressionKind::None,
+            CompressionKind::Zstd { level: 3 },
+            CompressionKind::Lz4 { level: 4 },
+        ] {
+            let compressed = compress(bytes.clone(), compression).unwrap();
+            if compression != CompressionKind::None {
+                assert!(compressed.len() < bytes.len());
+            }
+            assert_eq!(bytes, decompress(compressed).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_decompress_truncated_data() {
+        let bytes = br#"{"version":1,"df_map":[]}"#.repeat(100);
+        for compression in [
+            CompressionKind::Zstd { level: 3 },
+            CompressionKind::Lz4 { level: 4 },
+        ] {
+            let compressed = compress(bytes.clone(), compression).unwrap();
+            let truncated = compressed[..compressed.len() / 2].to_vec();
+            assert!(matches!(
+                decompress(truncated),
+                Err(ChapatyErrorKind::CorruptCachedData(_))
+            ));
+        }
+    }
+}
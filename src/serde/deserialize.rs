@@ -1,9 +1,93 @@
-use crate::{chapaty, bot::time_frame_snapshot::TimeFrameSnapshot};
+use super::{compression::decompress, DATA_FRAME_MAP_VERSION};
+use crate::{bot::time_frame_snapshot::TimeFrameSnapshot, chapaty, enums::error::ChapatyErrorKind};
 use polars::prelude::DataFrame;
-use std::str::from_utf8;
+use serde::{
+    de::{value::SeqAccessDeserializer, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::fmt;
 
-pub fn deserialize_data_frame_map(bytes: Vec<u8>) -> chapaty::types::DataFrameMap {
-    let df_map_as_str = from_utf8(&bytes).expect("DataFrameMapVec is not valid UTF-8");
-    let df_map_as_vec: Vec<(TimeFrameSnapshot, DataFrame)> = serde_json::from_str(&df_map_as_str).unwrap();
-    df_map_as_vec.into_iter().collect()
+type DataFrameMapVec = Vec<(TimeFrameSnapshot, DataFrame)>;
+
+/// A serialized data frame map is either `{ "version": <u32>, "df_map": [...] }` or, if it was
+/// serialized before the version header was introduced, a plain list of
+/// `(TimeFrameSnapshot, DataFrame)` pairs, i.e. version `0`. The pairs are deserialized in a
+/// single pass, without an intermediate representation of the whole map.
+enum SerializedDataFrameMap {
+    Supported(DataFrameMapVec),
+    UnknownVersion(u32),
+}
+
+impl<'de> Deserialize<'de> for SerializedDataFrameMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SerializedDataFrameMapVisitor)
+    }
+}
+
+struct SerializedDataFrameMapVisitor;
+
+impl<'de> Visitor<'de> for SerializedDataFrameMapVisitor {
+    type Value = SerializedDataFrameMap;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a versioned data frame map or a list of data frames")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        let df_map = DataFrameMapVec::deserialize(SeqAccessDeserializer::new(seq))?;
+        Ok(SerializedDataFrameMap::Supported(df_map))
+    }
+
+    /// The `version` is serialized before the `df_map`, such that the data frames of an unknown
+    /// version are skipped instead of being parsed with the wrong layout. If the keys were
+    /// reordered, the data frames are parsed with the current layout.
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut version = None;
+        let mut df_map = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match (key.as_str(), version) {
+                ("version", _) => version = Some(map.next_value::<u32>()?),
+                // Version 0 has the same layout as version 1, only the header is missing
+                ("df_map", None | Some(0 | DATA_FRAME_MAP_VERSION)) => {
+                    df_map = Some(map.next_value::<DataFrameMapVec>()?)
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        match (version, df_map) {
+            (None, _) => Err(serde::de::Error::missing_field("version")),
+            (Some(0 | DATA_FRAME_MAP_VERSION), None) => {
+                Err(serde::de::Error::missing_field("df_map"))
+            }
+            (Some(0 | DATA_FRAME_MAP_VERSION), Some(df_map)) => {
+                Ok(SerializedDataFrameMap::Supported(df_map))
+            }
+            (Some(version), _) => Ok(SerializedDataFrameMap::UnknownVersion(version)),
+        }
+    }
+}
+
+/// Deserializes a data frame map of any `CompressionKind` and migrates older versions to the
+/// current layout. Returns
+/// * `ChapatyErrorKind::IncompatibleSerializationVersion` if the version is unknown
+/// * `ChapatyErrorKind::CorruptCachedData` if the bytes are not a valid data frame map
+pub fn deserialize_data_frame_map(
+    bytes: Vec<u8>,
+) -> Result<chapaty::types::DataFrameMap, ChapatyErrorKind> {
+    let bytes = decompress(bytes)?;
+    let serialized: SerializedDataFrameMap = serde_json::from_slice(&bytes)
+        .map_err(|e| ChapatyErrorKind::CorruptCachedData(e.to_string()))?;
+
+    match serialized {
+        SerializedDataFrameMap::Supported(df_map) => Ok(df_map.into_iter().collect()),
+        SerializedDataFrameMap::UnknownVersion(version) => {
+            Err(ChapatyErrorKind::IncompatibleSerializationVersion(format!(
+                "Data frame map has version <{version}>, but only versions <0> to \
+                 <{DATA_FRAME_MAP_VERSION}> are supported"
+            )))
+        }
+    }
 }
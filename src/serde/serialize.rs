@@ -1,7 +1,27 @@
-use crate::{bot::time_frame_snapshot::TimeFrameSnapshot, chapaty};
+use super::{compression::compress, DATA_FRAME_MAP_VERSION};
+use crate::{
+    bot::time_frame_snapshot::TimeFrameSnapshot,
+    chapaty,
+    enums::{data::CompressionKind, error::ChapatyErrorKind},
+};
 use polars::prelude::DataFrame;
+use serde::Serialize;
 
-pub fn serialize_data_frame_map(df_map: &chapaty::types::DataFrameMap) -> String {
-    let df_map_as_vec: Vec<(&TimeFrameSnapshot, &DataFrame)> = df_map.iter().collect();
-    serde_json::to_string(&df_map_as_vec).unwrap()
+#[derive(Serialize)]
+struct VersionedDataFrameMap<'a> {
+    version: u32,
+    df_map: Vec<(&'a TimeFrameSnapshot, &'a DataFrame)>,
+}
+
+pub fn serialize_data_frame_map(
+    df_map: &chapaty::types::DataFrameMap,
+    compression: CompressionKind,
+) -> Result<Vec<u8>, ChapatyErrorKind> {
+    let versioned_df_map = VersionedDataFrameMap {
+        version: DATA_FRAME_MAP_VERSION,
+        df_map: df_map.iter().collect(),
+    };
+    let bytes = serde_json::to_vec(&versioned_df_map)
+        .map_err(|e| ChapatyErrorKind::FailedToSerializeCachedData(e.to_string()))?;
+    compress(bytes, compression)
 }
@@ -1,6 +1,7 @@
 pub mod ppp;
 use crate::{
-    bot::trade::Trade,
+    bot::{instrument_override::InstrumentOverride, trade::Trade},
+    converter::fixed_point::ticks_to_price,
     calculator::pre_trade_values_calculator::RequiredPreTradeValuesWithData,
     enums::{
         bot::{StopLossKind, TakeProfitKind},
@@ -10,24 +11,60 @@ use crate::{
     }, MarketKind, trading_indicator::initial_balance::InitialBalance,
 };
 use mockall::automock;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct StopLoss {
     pub kind: StopLossKind,
     pub offset: f64,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct TakeProfit {
     pub kind: TakeProfitKind,
     pub offset: f64,
 }
 
+/// Force-closes a trade at the close of the last candle within the holding period, if neither stop
+/// loss nor take profit were reached before. If the trades are replayed, the trade is closed at the
+/// last trade within the holding period instead.
+/// * `Candles` - number of candles the trade is held, including the entry candle
+/// * `Minutes` - number of minutes the trade is held, starting at the entry
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MaxHoldingPeriod {
+    Candles(usize),
+    Minutes(i64),
+}
+
+impl MaxHoldingPeriod {
+    /// A trade is held for at least one candle or minute.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Candles(n) => *n > 0,
+            Self::Minutes(minutes) => *minutes > 0,
+        }
+    }
+}
+
 pub struct TradeRequestObject {
     pub pre_trade_values: RequiredPreTradeValuesWithData,
     pub initial_balance: Option<InitialBalance>,
     pub market: MarketKind,
+    pub instrument_override: Option<InstrumentOverride>,
+}
+
+impl TradeRequestObject {
+    /// Converts the dollar `offset` into a price offset of the market, using the tick size and
+    /// tick value of the `InstrumentOverride` if there is one.
+    pub fn try_offset_in_tick(&self, offset: f64) -> f64 {
+        match self.instrument_override {
+            Some(instrument) => {
+                ticks_to_price(offset / instrument.tick_value, instrument.tick_size)
+            }
+            None => self.market.try_offset_in_tick(offset),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -43,4 +80,11 @@ pub trait Strategy {
     fn get_trade_kind(&self, pre_trade_values: &RequiredPreTradeValuesWithData) -> TradeDirectionKind;
     fn get_entry_price(&self, pre_trade_values: &RequiredPreTradeValuesWithData) -> f64;
     fn get_name(&self) -> String;
+
+    /// Describes every parameter that changes the trades of the strategy, e.g. its stop loss and
+    /// take profit. Cached pnl report segments of an incremental backtest are only reused if the
+    /// parameters did not change.
+    fn get_parameters(&self) -> String {
+        String::new()
+    }
 }
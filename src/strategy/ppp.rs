@@ -5,12 +5,14 @@ pub struct Ppp {
     stop_loss: StopLoss,
     take_profit: TakeProfit,
     entry: TradingIndicatorKind,
+    max_holding_period: Option<MaxHoldingPeriod>,
 }
 
 pub struct PppBuilder {
     stop_loss: Option<StopLoss>,
     take_profit: Option<TakeProfit>,
     entry: Option<TradingIndicatorKind>,
+    max_holding_period: Option<MaxHoldingPeriod>,
 }
 
 impl PppBuilder {
@@ -19,6 +21,7 @@ impl PppBuilder {
             stop_loss: None,
             take_profit: None,
             entry: None,
+            max_holding_period: None,
         }
     }
 
@@ -43,12 +46,33 @@ impl PppBuilder {
         }
     }
 
+    pub fn with_max_holding_period(self, max_holding_period: MaxHoldingPeriod) -> Self {
+        Self {
+            max_holding_period: Some(max_holding_period),
+            ..self
+        }
+    }
+
+    /// # Panics
+    /// If the maximum holding period is not positive, see `try_build`.
     pub fn build(self) -> Ppp {
-        Ppp {
+        self.try_build().unwrap()
+    }
+
+    /// # Errors
+    /// Returns a `BuildBotError` if the maximum holding period is not positive.
+    pub fn try_build(self) -> Result<Ppp, ChapatyErrorKind> {
+        if let Some(max_holding_period) = self.max_holding_period.filter(|p| !p.is_valid()) {
+            return Err(ChapatyErrorKind::BuildBotError(format!(
+                "Maximum holding period <{max_holding_period:?}> must be positive"
+            )));
+        }
+        Ok(Ppp {
             stop_loss: self.stop_loss.unwrap(),
             take_profit: self.take_profit.unwrap(),
             entry: self.entry.unwrap(),
-        }
+            max_holding_period: self.max_holding_period,
+        })
     }
 }
 
@@ -75,7 +99,7 @@ impl Ppp {
         let value_area_low = pre_trade_values.value_area_low(ph);
         let lowest_trade_price = pre_trade_values.lowest_trade_price();
         let entry_price = self.get_entry_price(pre_trade_values);
-        let offset = request.market.try_offset_in_tick(self.stop_loss.offset);
+        let offset = request.try_offset_in_tick(self.stop_loss.offset);
         match self.stop_loss.kind {
             StopLossKind::PriceUponTradeEntry => entry_price - offset,
             StopLossKind::PrevHighOrLow => lowest_trade_price - offset,
@@ -89,7 +113,7 @@ impl Ppp {
         let value_area_high = pre_trade_values.value_area_high(ph);
         let highest_trade_price = pre_trade_values.highest_trade_price();
         let entry_price = self.get_entry_price(pre_trade_values);
-        let offset = request.market.try_offset_in_tick(self.stop_loss.offset);
+        let offset = request.try_offset_in_tick(self.stop_loss.offset);
         match self.stop_loss.kind {
             StopLossKind::PriceUponTradeEntry => entry_price + offset,
             StopLossKind::PrevHighOrLow => highest_trade_price + offset,
@@ -104,7 +128,7 @@ impl Ppp {
         let lst_trade_price = pre_trade_values.last_trade_price();
         let highest_trade_price = pre_trade_values.highest_trade_price();
         let entry_price = self.get_entry_price(pre_trade_values);
-        let offset = request.market.try_offset_in_tick(self.take_profit.offset);
+        let offset = request.try_offset_in_tick(self.take_profit.offset);
         match self.take_profit.kind {
             TakeProfitKind::PrevClose => lst_trade_price + offset,
             TakeProfitKind::PriceUponTradeEntry => entry_price + offset,
@@ -120,7 +144,7 @@ impl Ppp {
         let lst_trade_price = pre_trade_values.last_trade_price();
         let lowest_trade_price = pre_trade_values.lowest_trade_price();
         let entry_price = self.get_entry_price(pre_trade_values);
-        let offset = request.market.try_offset_in_tick(self.take_profit.offset);
+        let offset = request.try_offset_in_tick(self.take_profit.offset);
         match self.take_profit.kind {
             TakeProfitKind::PrevClose => lst_trade_price - offset,
             TakeProfitKind::PriceUponTradeEntry => entry_price - offset,
@@ -149,6 +173,7 @@ impl Strategy for Ppp {
             stop_loss: self.get_sl_price(request),
             take_profit: self.get_tp_price(request),
             trade_kind: self.get_trade_kind(&request.pre_trade_values),
+            max_holding_period: self.max_holding_period,
         }
     }
 
@@ -200,6 +225,13 @@ impl Strategy for Ppp {
     fn get_name(&self) -> String {
         "ppp".to_string()
     }
+
+    fn get_parameters(&self) -> String {
+        format!(
+            "{:?}-{:?}-{:?}-{:?}",
+            self.entry, self.stop_loss, self.take_profit, self.max_holding_period
+        )
+    }
 }
 
 #[cfg(test)]
@@ -267,4 +299,45 @@ mod tests {
             TradeDirectionKind::None
         );
     }
+
+    #[test]
+    fn test_build_rejects_empty_max_holding_period() {
+        let build = |max_holding_period| {
+            PppBuilder::new()
+                .with_stop_loss(StopLoss {
+                    kind: StopLossKind::PrevHighOrLow,
+                    offset: 0.0,
+                })
+                .with_take_profit(TakeProfit {
+                    kind: TakeProfitKind::PrevClose,
+                    offset: 0.0,
+                })
+                .with_entry(TradingIndicatorKind::Poc(PriceHistogramKind::Tpo1m))
+                .with_max_holding_period(max_holding_period)
+                .try_build()
+        };
+        assert!(build(MaxHoldingPeriod::Candles(0)).is_err());
+        assert!(build(MaxHoldingPeriod::Minutes(0)).is_err());
+        assert!(build(MaxHoldingPeriod::Candles(1)).is_ok());
+        assert!(build(MaxHoldingPeriod::Minutes(90)).is_ok());
+    }
+
+    #[test]
+    fn test_parameters_change_with_stop_loss() {
+        let build = |offset| {
+            PppBuilder::new()
+                .with_stop_loss(StopLoss {
+                    kind: StopLossKind::PrevHighOrLow,
+                    offset,
+                })
+                .with_take_profit(TakeProfit {
+                    kind: TakeProfitKind::PrevClose,
+                    offset: 0.0,
+                })
+                .with_entry(TradingIndicatorKind::Poc(PriceHistogramKind::Tpo1m))
+                .build()
+        };
+        assert_eq!(build(10.0).get_parameters(), build(10.0).get_parameters());
+        assert_ne!(build(10.0).get_parameters(), build(20.0).get_parameters());
+    }
 }
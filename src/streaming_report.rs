@@ -0,0 +1,228 @@
+use crate::{
+    enums::{column_names::PnLReportColumnKind, error::ChapatyErrorKind},
+    pnl::metrics::{percent_profitability, profit_factor, trade_values},
+};
+use polars::{
+    io::parquet::BatchedWriter,
+    prelude::{CsvWriter, DataFrame, ParquetWriter, SerWriter},
+};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, path::Path};
+
+/// Aggregate metrics of a stream of trades, computed with online algorithms such that no trade
+/// has to be kept in memory. The mean and the variance of the trade PnL follow Welford's
+/// algorithm.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OnlineTradeStatistics {
+    pub number_trades: u32,
+    pub number_winner_trades: u32,
+    pub net_profit: f64,
+    pub total_win: f64,
+    pub total_loss: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl OnlineTradeStatistics {
+    pub fn update(&mut self, pl_dollar: f64) {
+        self.number_trades += 1;
+        self.net_profit += pl_dollar;
+        if pl_dollar > 0.0 {
+            self.number_winner_trades += 1;
+            self.total_win += pl_dollar;
+        } else {
+            self.total_loss += pl_dollar;
+        }
+
+        let delta = pl_dollar - self.mean;
+        self.mean += delta / f64::from(self.number_trades);
+        self.m2 += delta * (pl_dollar - self.mean);
+    }
+
+    pub fn avg_trade(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample standard deviation of the trade PnL.
+    pub fn std_dev(&self) -> f64 {
+        if self.number_trades < 2 {
+            return 0.0;
+        }
+        (self.m2 / f64::from(self.number_trades - 1)).sqrt()
+    }
+
+    pub fn percent_profitability(&self) -> f64 {
+        percent_profitability(self.number_winner_trades, self.number_trades)
+    }
+
+    pub fn profit_factor(&self) -> f64 {
+        profit_factor(self.total_win, self.total_loss)
+    }
+}
+
+/// Appends profit and loss reports to a file as soon as they are computed, while keeping track
+/// of the `OnlineTradeStatistics` of all appended trades. Files with the extension `parquet` are
+/// written as Parquet, any other file as CSV. Call `finish` after the last report, which writes
+/// the footer of a Parquet file.
+pub struct StreamingReportWriter {
+    sink: ReportSink,
+    columns: Option<Vec<String>>,
+    statistics: OnlineTradeStatistics,
+}
+
+enum ReportSink {
+    Csv(File),
+    /// The Parquet writer requires the schema of the reports, hence it is created with the first
+    /// report. The writer gets a handle of the file, such that the file is kept if the writer
+    /// cannot be created.
+    Parquet {
+        file: File,
+        writer: Option<Box<BatchedWriter<File>>>,
+    },
+}
+
+impl StreamingReportWriter {
+    pub fn new(path: &Path) -> Result<Self, ChapatyErrorKind> {
+        let file = File::create(path).map_err(to_streaming_report_error)?;
+        let sink = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("parquet") => ReportSink::Parquet { file, writer: None },
+            _ => ReportSink::Csv(file),
+        };
+
+        Ok(Self {
+            sink,
+            columns: None,
+            statistics: OnlineTradeStatistics::default(),
+        })
+    }
+
+    /// Appends the rows of `pnl`. The header is written with the first report, hence every
+    /// following report must have the same columns.
+    pub fn append(&mut self, pnl: &DataFrame) -> Result<(), ChapatyErrorKind> {
+        let columns: Vec<String> = pnl
+            .get_column_names()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        if let Some(expected) = &self.columns {
+            if *expected != columns {
+                return Err(ChapatyErrorKind::FailedToWriteStreamingReport(format!(
+                    "Columns {columns:?} do not match the columns {expected:?} of the report"
+                )));
+            }
+        }
+
+        match &mut self.sink {
+            ReportSink::Csv(file) => CsvWriter::new(file)
+                .has_header(self.columns.is_none())
+                .finish(&mut pnl.clone())
+                .map_err(to_streaming_report_error)?,
+            ReportSink::Parquet { file, writer } => {
+                if writer.is_none() {
+                    let file = file.try_clone().map_err(to_streaming_report_error)?;
+                    let batched = ParquetWriter::new(file)
+                        .batched(&pnl.schema())
+                        .map_err(to_streaming_report_error)?;
+                    *writer = Some(Box::new(batched));
+                }
+                writer
+                    .as_mut()
+                    .unwrap()
+                    .write_batch(pnl)
+                    .map_err(to_streaming_report_error)?
+            }
+        };
+        self.columns = Some(columns);
+
+        trade_values(pnl.clone(), PnLReportColumnKind::PlDollar)
+            .into_iter()
+            .for_each(|pl_dollar| self.statistics.update(pl_dollar));
+        Ok(())
+    }
+
+    pub fn statistics(&self) -> &OnlineTradeStatistics {
+        &self.statistics
+    }
+
+    /// Completes the file and returns the statistics of all appended trades.
+    pub fn finish(self) -> Result<OnlineTradeStatistics, ChapatyErrorKind> {
+        if let ReportSink::Parquet {
+            writer: Some(mut writer),
+            ..
+        } = self.sink
+        {
+            writer.finish().map_err(to_streaming_report_error)?;
+        }
+        Ok(self.statistics)
+    }
+}
+
+fn to_streaming_report_error(e: impl std::fmt::Display) -> ChapatyErrorKind {
+    ChapatyErrorKind::FailedToWriteStreamingReport(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{
+        df,
+        prelude::{NamedFrom, ParquetReader, SerReader},
+    };
+    use std::fs;
+
+    #[test]
+    fn test_online_trade_statistics() {
+        let mut statistics = OnlineTradeStatistics::default();
+        [10.0, -10.0, 10.0, 10.0]
+            .into_iter()
+            .for_each(|pl_dollar| statistics.update(pl_dollar));
+
+        assert_eq!(4, statistics.number_trades);
+        assert_eq!(20.0, statistics.net_profit);
+        assert_eq!(5.0, statistics.avg_trade());
+        assert_eq!(10.0, statistics.std_dev());
+        assert_eq!(0.75, statistics.percent_profitability());
+        assert_eq!(3.0, statistics.profit_factor());
+    }
+
+    fn pnl() -> DataFrame {
+        df!(
+            "Status" => &["Winner", "NoEntry"],
+            "PlDollar" => &[10.0, 0.0],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_streaming_report_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pnl.csv");
+        let mut writer = StreamingReportWriter::new(&path).unwrap();
+        writer.append(&pnl()).unwrap();
+        writer.append(&pnl()).unwrap();
+
+        let other_columns = df!("PlDollar" => &[10.0]).unwrap();
+        assert!(writer.append(&other_columns).is_err());
+
+        assert_eq!(2, writer.finish().unwrap().number_trades);
+        assert_eq!(
+            "Status,PlDollar\nWinner,10.0\nNoEntry,0.0\nWinner,10.0\nNoEntry,0.0\n",
+            fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_streaming_report_writer_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pnl.parquet");
+        let mut writer = StreamingReportWriter::new(&path).unwrap();
+        writer.append(&pnl()).unwrap();
+        writer.append(&pnl()).unwrap();
+        assert_eq!(2, writer.finish().unwrap().number_trades);
+
+        let streamed = ParquetReader::new(File::open(&path).unwrap())
+            .finish()
+            .unwrap();
+        assert!(streamed.frame_equal(&pnl().vstack(&pnl()).unwrap()));
+    }
+}
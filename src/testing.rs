@@ -0,0 +1,397 @@
+//! Utilities to property-test strategies against the engine: seeded generators of random but
+//! valid market data, and assertions on the profit and loss reports of a backtest.
+pub mod golden_file;
+
+use crate::{
+    converter::{fixed_point::ticks_to_price, pnl_to_report::as_equity_curve},
+    enums::{
+        column_names::{DataProviderColumnKind, PnLReportColumnKind},
+        error::ChapatyErrorKind,
+        trade_and_pre_trade::TerminationReason,
+    },
+    equity_curve::Capital,
+};
+use polars::{
+    df,
+    prelude::{DataFrame, NamedFrom},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Generates OHLCV candles as a random walk on the tick grid. Every candle opens at the close of
+/// the previous candle, and `low <= min(open, close) <= max(open, close) <= high` holds.
+/// * `start_ts` - **UTC** open timestamp of the first candle in **milliseconds**
+/// * `candle_duration` - duration of a candle in **milliseconds**
+/// * `max_ticks_per_candle` - maximum number of ticks between open and close, and between the
+///   body and the wicks of a candle
+#[derive(Debug, Clone, PartialEq)]
+pub struct OhlcvGenerator {
+    pub seed: u64,
+    pub start_ts: i64,
+    pub candle_duration: i64,
+    pub start_price: f64,
+    pub tick_size: f64,
+    pub max_ticks_per_candle: i64,
+}
+
+impl Default for OhlcvGenerator {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            start_ts: 1_640_995_200_000,
+            candle_duration: 60_000,
+            start_price: 1.1,
+            tick_size: 0.00005,
+            max_ticks_per_candle: 10,
+        }
+    }
+}
+
+impl OhlcvGenerator {
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub fn with_start_ts(self, start_ts: i64) -> Self {
+        Self { start_ts, ..self }
+    }
+
+    pub fn with_candle_duration(self, candle_duration: i64) -> Self {
+        Self {
+            candle_duration,
+            ..self
+        }
+    }
+
+    pub fn with_start_price(self, start_price: f64) -> Self {
+        Self {
+            start_price,
+            ..self
+        }
+    }
+
+    pub fn with_tick_size(self, tick_size: f64) -> Self {
+        Self { tick_size, ..self }
+    }
+
+    pub fn with_max_ticks_per_candle(self, max_ticks_per_candle: i64) -> Self {
+        Self {
+            max_ticks_per_candle,
+            ..self
+        }
+    }
+
+    /// Returns `number_of_candles` candles with the columns of the OHLCV market data.
+    pub fn generate(&self, number_of_candles: usize) -> DataFrame {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let k = self.max_ticks_per_candle.max(1);
+        let mut close_in_ticks = (self.start_price / self.tick_size).round() as i64;
+        let to_price = |ticks: i64| ticks_to_price(ticks as f64, self.tick_size);
+
+        let (mut ots, mut cts) = (Vec::new(), Vec::new());
+        let (mut open, mut high, mut low, mut close) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut vol = Vec::new();
+        for idx in 0..number_of_candles {
+            let o = close_in_ticks;
+            let c = (o + rng.gen_range(-k..=k)).max(k + 1);
+            let h = o.max(c) + rng.gen_range(0..=k);
+            let l = (o.min(c) - rng.gen_range(0..=k)).max(1);
+            close_in_ticks = c;
+
+            let open_ts = self.start_ts + idx as i64 * self.candle_duration;
+            ots.push(open_ts);
+            cts.push(open_ts + self.candle_duration - 1);
+            open.push(to_price(o));
+            high.push(to_price(h));
+            low.push(to_price(l));
+            close.push(to_price(c));
+            vol.push(f64::from(rng.gen_range(1..=100_u32)));
+        }
+
+        df!(
+            &DataProviderColumnKind::OpenTime.to_string() => ots,
+            &DataProviderColumnKind::Open.to_string() => open,
+            &DataProviderColumnKind::High.to_string() => high,
+            &DataProviderColumnKind::Low.to_string() => low,
+            &DataProviderColumnKind::Close.to_string() => close,
+            &DataProviderColumnKind::Volume.to_string() => vol,
+            &DataProviderColumnKind::CloseTime.to_string() => cts,
+        )
+        .unwrap()
+    }
+}
+
+/// Generates aggregated trades as a random walk on the tick grid with strictly increasing
+/// timestamps and trade ids.
+/// * `start_ts` - **UTC** timestamp of the first trade in **milliseconds**
+/// * `max_gap` - maximum number of **milliseconds** between two trades
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggTradesGenerator {
+    pub seed: u64,
+    pub start_ts: i64,
+    pub max_gap: i64,
+    pub start_price: f64,
+    pub tick_size: f64,
+}
+
+impl Default for AggTradesGenerator {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            start_ts: 1_640_995_200_000,
+            max_gap: 1_000,
+            start_price: 40_000.0,
+            tick_size: 0.01,
+        }
+    }
+}
+
+impl AggTradesGenerator {
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub fn with_start_ts(self, start_ts: i64) -> Self {
+        Self { start_ts, ..self }
+    }
+
+    pub fn with_max_gap(self, max_gap: i64) -> Self {
+        Self { max_gap, ..self }
+    }
+
+    pub fn with_start_price(self, start_price: f64) -> Self {
+        Self {
+            start_price,
+            ..self
+        }
+    }
+
+    pub fn with_tick_size(self, tick_size: f64) -> Self {
+        Self { tick_size, ..self }
+    }
+
+    /// Returns `number_of_trades` trades with the columns of the aggregated trades market data.
+    pub fn generate(&self, number_of_trades: usize) -> DataFrame {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut px_in_ticks = (self.start_price / self.tick_size).round() as i64;
+        let mut ts = self.start_ts;
+
+        let (mut atid, mut px, mut qx, mut tss) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let (mut bm, mut btpm) = (Vec::new(), Vec::new());
+        for idx in 0..number_of_trades {
+            px_in_ticks = (px_in_ticks + rng.gen_range(-1..=1)).max(1);
+            ts += rng.gen_range(1..=self.max_gap.max(1));
+
+            atid.push(idx as i64);
+            px.push(ticks_to_price(px_in_ticks as f64, self.tick_size));
+            qx.push(f64::from(rng.gen_range(1..=10_u32)));
+            tss.push(ts);
+            bm.push(rng.gen_bool(0.5));
+            btpm.push(true);
+        }
+
+        df!(
+            &DataProviderColumnKind::AggTradeId.to_string() => atid.clone(),
+            &DataProviderColumnKind::Price.to_string() => px,
+            &DataProviderColumnKind::Quantity.to_string() => qx,
+            &DataProviderColumnKind::FirstTradeId.to_string() => atid.clone(),
+            &DataProviderColumnKind::LastTradeId.to_string() => atid,
+            &DataProviderColumnKind::Timestamp.to_string() => tss,
+            &DataProviderColumnKind::BuyerEqualsMaker.to_string() => bm,
+            &DataProviderColumnKind::BestTradePriceMatch.to_string() => btpm,
+        )
+        .unwrap()
+    }
+}
+
+/// Asserts that no trade of the profit and loss report `pnl` that was stopped out lost more than
+/// its stop loss, up to half a tick of rounding.
+///
+/// # Errors
+/// Returns a `MissingReportColumn` error if `pnl` lacks a column of a profit and loss report per
+/// market and year, e.g. the `Id` column, which the aggregated profit and loss statement drops.
+pub fn assert_stop_loss_respected(pnl: &DataFrame) -> Result<(), ChapatyErrorKind> {
+    let expected_loss = PnLReportColumnKind::ExpectedLossTick;
+    for (id, pl_tick, expected_loss_tick) in exits(pnl, TerminationReason::StopLoss, expected_loss)?
+    {
+        assert!(
+            pl_tick >= expected_loss_tick - 0.5,
+            "Trade <{id}> lost {pl_tick} ticks, but its stop loss is at {expected_loss_tick} ticks"
+        );
+    }
+    Ok(())
+}
+
+/// Asserts that no trade of the profit and loss report `pnl` that reached its take profit won
+/// more than its take profit, up to half a tick of rounding.
+///
+/// # Errors
+/// Returns a `MissingReportColumn` error under the same conditions as
+/// [`assert_stop_loss_respected`].
+pub fn assert_take_profit_respected(pnl: &DataFrame) -> Result<(), ChapatyErrorKind> {
+    let expected_win = PnLReportColumnKind::ExpectedWinTick;
+    for (id, pl_tick, expected_win_tick) in exits(pnl, TerminationReason::TakeProfit, expected_win)?
+    {
+        assert!(
+            pl_tick <= expected_win_tick + 0.5,
+            "Trade <{id}> won {pl_tick} ticks, but its take profit is at {expected_win_tick} ticks"
+        );
+    }
+    Ok(())
+}
+
+/// Asserts that the equity curve of the profit and loss report `pnl`, starting at
+/// `initial_capital`, never drops below zero.
+pub fn assert_equity_never_negative(pnl: &DataFrame, initial_capital: f64) {
+    let equity_curve = as_equity_curve(pnl, false, &Capital::new(initial_capital, false));
+    if let Some((idx, equity)) = equity_curve
+        .iter()
+        .enumerate()
+        .find(|(_, equity)| **equity < 0.0)
+    {
+        panic!("Equity drops to {equity} after trade <{idx}>");
+    }
+}
+
+/// Returns the id, the profit in ticks and the `expected` ticks of every trade exited by `reason`.
+fn exits(
+    pnl: &DataFrame,
+    reason: TerminationReason,
+    expected: PnLReportColumnKind,
+) -> Result<Vec<(f64, f64, f64)>, ChapatyErrorKind> {
+    let pl = column(pnl, PnLReportColumnKind::PlTick)?;
+    let expected = column(pnl, expected)?;
+    let ids = column(pnl, PnLReportColumnKind::Id)?;
+    let exits = pnl
+        .column(&PnLReportColumnKind::ExitReason.to_string())
+        .map_err(|_| missing_column(PnLReportColumnKind::ExitReason))?
+        .utf8()
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, exit_reason)| *exit_reason == Some(reason.to_string().as_str()))
+        .map(|(idx, _)| (ids[idx], pl[idx], expected[idx]))
+        .collect();
+    Ok(exits)
+}
+
+fn column(pnl: &DataFrame, kind: PnLReportColumnKind) -> Result<Vec<f64>, ChapatyErrorKind> {
+    let values = pnl
+        .column(&kind.to_string())
+        .map_err(|_| missing_column(kind))?
+        .cast(&polars::prelude::DataType::Float64)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .map(|value| value.unwrap_or_default())
+        .collect();
+    Ok(values)
+}
+
+fn missing_column(kind: PnLReportColumnKind) -> ChapatyErrorKind {
+    ChapatyErrorKind::MissingReportColumn(format!(
+        "Profit and loss report has no <{kind}> column, assert on a report per market and year"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::Series;
+
+    #[test]
+    fn test_ohlcv_generator() {
+        let generator = OhlcvGenerator::default().with_seed(42);
+        let candles = generator.generate(500);
+        assert_eq!(500, candles.height());
+        assert_eq!(candles, generator.generate(500));
+
+        let column = |kind: DataProviderColumnKind| -> Vec<f64> {
+            candles[kind.to_string().as_str()]
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect()
+        };
+        let (open, high) = (
+            column(DataProviderColumnKind::Open),
+            column(DataProviderColumnKind::High),
+        );
+        let (low, close) = (
+            column(DataProviderColumnKind::Low),
+            column(DataProviderColumnKind::Close),
+        );
+        for idx in 0..candles.height() {
+            assert!(low[idx] > 0.0);
+            assert!(low[idx] <= open[idx].min(close[idx]));
+            assert!(high[idx] >= open[idx].max(close[idx]));
+            if idx > 0 {
+                assert_eq!(close[idx - 1], open[idx]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_agg_trades_generator() {
+        let trades = AggTradesGenerator::default().with_seed(7).generate(100);
+        let ts: Vec<i64> = trades[DataProviderColumnKind::Timestamp.to_string().as_str()]
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(100, trades.height());
+        assert!(ts.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    fn pnl() -> DataFrame {
+        df!(
+            "Id" => &[1_u32, 2, 3],
+            "ExpectedWinTick" => &[20.0, 20.0, 20.0],
+            "ExpectedLossTick" => &[-10.0, -10.0, -10.0],
+            "PlTick" => &[20.0, -10.0, -3.0],
+            "PlDollar" => &[100.0, -50.0, -15.0],
+            "ExitReason" => &["TakeProfit", "StopLoss", "SessionEnd"],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_assertions_hold() {
+        let pnl = pnl();
+        assert_stop_loss_respected(&pnl).unwrap();
+        assert_take_profit_respected(&pnl).unwrap();
+        assert_equity_never_negative(&pnl, 0.0);
+    }
+
+    #[test]
+    fn test_assertions_without_id() {
+        let pnl = pnl().drop("Id").unwrap();
+        assert!(matches!(
+            assert_stop_loss_respected(&pnl),
+            Err(ChapatyErrorKind::MissingReportColumn(_))
+        ));
+        assert!(matches!(
+            assert_take_profit_respected(&pnl),
+            Err(ChapatyErrorKind::MissingReportColumn(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Trade <2> lost -12 ticks")]
+    fn test_stop_loss_not_respected() {
+        let mut pnl = pnl();
+        pnl.replace("PlTick", Series::new("PlTick", &[20.0, -12.0, -3.0]))
+            .unwrap();
+        assert_stop_loss_respected(&pnl).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Equity drops to -50")]
+    fn test_equity_negative() {
+        let mut pnl = pnl();
+        pnl.replace("PlDollar", Series::new("PlDollar", &[-50.0, -50.0, 0.0]))
+            .unwrap();
+        assert_equity_never_negative(&pnl, 50.0);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{backtest_result::BacktestResult, enums::error::ChapatyErrorKind};
+use polars::prelude::{CsvReader, CsvWriter, DataFrame, DataType, SerReader, SerWriter, Series};
+use std::{fs, io::Cursor, path::Path};
+
+/// Tolerance of floating point values in [`verify_against_golden`]. Two values `a` and `b` match
+/// if `|a - b| <= absolute + relative * |b|`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenTolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        Self {
+            absolute: 1e-9,
+            relative: 0.0,
+        }
+    }
+}
+
+impl GoldenTolerance {
+    pub fn new(absolute: f64, relative: f64) -> Self {
+        Self { absolute, relative }
+    }
+
+    fn is_close(&self, value: f64, golden: f64) -> bool {
+        value == golden || (value - golden).abs() <= self.absolute + self.relative * golden.abs()
+    }
+}
+
+/// Records the profit and loss statement of all markets and years, and the consolidated
+/// performance and trade breakdown reports of `result` as golden files in `dir`.
+pub fn record_golden(result: &BacktestResult, dir: &Path) -> Result<(), ChapatyErrorKind> {
+    fs::create_dir_all(dir)
+        .map_err(|e| ChapatyErrorKind::FailedToRecordGoldenFile(e.to_string()))?;
+    for (file_name, report) in golden_reports(result) {
+        let bytes = to_csv(&report)?;
+        fs::write(dir.join(file_name), bytes)
+            .map_err(|e| ChapatyErrorKind::FailedToRecordGoldenFile(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Compares the reports of `result` with the golden files recorded in `dir` by [`record_golden`].
+/// Floating point values match within `tolerance`, all other values must be equal.
+pub fn verify_against_golden(
+    result: &BacktestResult,
+    dir: &Path,
+    tolerance: &GoldenTolerance,
+) -> Result<(), ChapatyErrorKind> {
+    for (file_name, report) in golden_reports(result) {
+        let path = dir.join(file_name);
+        let golden = fs::read(&path)
+            .map_err(|_| ChapatyErrorKind::FileNotFound(path.display().to_string()))?;
+        let golden = from_csv(golden)?;
+        let report = from_csv(to_csv(&report)?)?;
+        compare_reports(&report, &golden, tolerance)
+            .map_err(|e| ChapatyErrorKind::GoldenFileMismatch(format!("{file_name}: {e}")))?;
+    }
+    Ok(())
+}
+
+fn golden_reports(result: &BacktestResult) -> [(&'static str, DataFrame); 3] {
+    [
+        (
+            "pnl.csv",
+            result.agg_market_and_agg_year.pnl_statement.pnl.clone(),
+        ),
+        (
+            "performance_report.csv",
+            result.consolidated_performance_report(),
+        ),
+        (
+            "trade_breakdown_report.csv",
+            result.consolidated_trade_breakdown_report(),
+        ),
+    ]
+}
+
+fn to_csv(report: &DataFrame) -> Result<Vec<u8>, ChapatyErrorKind> {
+    let mut bytes = Vec::new();
+    CsvWriter::new(&mut bytes)
+        .finish(&mut report.clone())
+        .map_err(|e| ChapatyErrorKind::FailedToRecordGoldenFile(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Reads the reports back from CSV, such that the data types of the report and of the golden
+/// file are inferred in the same way.
+fn from_csv(bytes: Vec<u8>) -> Result<DataFrame, ChapatyErrorKind> {
+    CsvReader::new(Cursor::new(bytes))
+        .finish()
+        .map_err(|e| ChapatyErrorKind::GoldenFileMismatch(e.to_string()))
+}
+
+fn compare_reports(
+    report: &DataFrame,
+    golden: &DataFrame,
+    tolerance: &GoldenTolerance,
+) -> Result<(), String> {
+    if report.get_column_names() != golden.get_column_names() {
+        return Err(format!(
+            "Columns {:?} do not match the golden columns {:?}",
+            report.get_column_names(),
+            golden.get_column_names()
+        ));
+    }
+    if report.height() != golden.height() {
+        return Err(format!(
+            "{} rows do not match the {} golden rows",
+            report.height(),
+            golden.height()
+        ));
+    }
+    report
+        .get_columns()
+        .iter()
+        .zip(golden.get_columns())
+        .try_for_each(|(values, golden_values)| compare_columns(values, golden_values, tolerance))
+}
+
+fn compare_columns(
+    values: &Series,
+    golden: &Series,
+    tolerance: &GoldenTolerance,
+) -> Result<(), String> {
+    let is_float = |series: &Series| series.dtype().is_float();
+    if is_float(values) || is_float(golden) {
+        let values = values.cast(&DataType::Float64).map_err(|e| e.to_string())?;
+        let golden = golden.cast(&DataType::Float64).map_err(|e| e.to_string())?;
+        let rows = values.f64().unwrap().into_iter().zip(golden.f64().unwrap());
+        for (row, (value, golden)) in rows.enumerate() {
+            let is_match = match (value, golden) {
+                (Some(value), Some(golden)) => tolerance.is_close(value, golden),
+                (value, golden) => value == golden,
+            };
+            if !is_match {
+                return Err(format!(
+                    "Column <{}> in row {row}: {value:?} does not match the golden {golden:?}",
+                    values.name()
+                ));
+            }
+        }
+        Ok(())
+    } else if values.series_equal_missing(golden) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Column <{}> does not match the golden column",
+            values.name()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{df, prelude::NamedFrom};
+
+    #[test]
+    fn test_compare_reports() {
+        let golden = df!(
+            "Year" => &["2022"],
+            "NetProfit" => &[100.0],
+        )
+        .unwrap();
+        let report = df!(
+            "Year" => &["2022"],
+            "NetProfit" => &[100.004],
+        )
+        .unwrap();
+
+        assert!(compare_reports(&report, &golden, &GoldenTolerance::default()).is_err());
+        assert!(compare_reports(&report, &golden, &GoldenTolerance::new(0.01, 0.0)).is_ok());
+        assert!(compare_reports(&report, &golden, &GoldenTolerance::new(0.0, 1e-4)).is_ok());
+
+        let other_year = df!(
+            "Year" => &["2023"],
+            "NetProfit" => &[100.0],
+        )
+        .unwrap();
+        assert!(compare_reports(&other_year, &golden, &GoldenTolerance::default()).is_err());
+    }
+
+    #[test]
+    fn test_csv_round_trip_keeps_report() {
+        let report = df!(
+            "Id" => &[1_u32, 2],
+            "NetProfit" => &[100.25, -3.5],
+            "Status" => &["Winner", "Loser"],
+        )
+        .unwrap();
+        let golden = from_csv(to_csv(&report).unwrap()).unwrap();
+        let report = from_csv(to_csv(&report).unwrap()).unwrap();
+        assert!(compare_reports(&report, &golden, &GoldenTolerance::default()).is_ok());
+    }
+}
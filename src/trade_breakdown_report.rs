@@ -1,4 +1,8 @@
-use crate::{data_frame_operations::io_operations::save_df_as_csv, enums::markets::MarketKind};
+use crate::{
+    converter::report_format::ReportFormat,
+    data_frame_operations::io_operations::save_df_as_csv,
+    enums::{column_names::TradeBreakDownReportColumnKind, markets::MarketKind},
+};
 use polars::prelude::DataFrame;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,11 +15,15 @@ pub struct TradeBreakdownReports {
 
 impl TradeBreakdownReports {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.reports
             .iter()
             .for_each(|(market, trade_break_down_report)| {
                 save_df_as_csv(
-                    &mut trade_break_down_report.clone(),
+                    &mut format.export::<TradeBreakDownReportColumnKind>(trade_break_down_report),
                     &format!("{file_name}_{market}_trade_breakdown_report"),
                 )
             })
@@ -30,11 +38,15 @@ pub struct TradeBreakDownReportsAggYears {
 
 impl TradeBreakDownReportsAggYears {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         self.reports
             .iter()
             .for_each(|(market, trade_break_down_report)| {
                 save_df_as_csv(
-                    &mut trade_break_down_report.clone(),
+                    &mut format.export::<TradeBreakDownReportColumnKind>(trade_break_down_report),
                     &format!("{file_name}_{market}_all_years_trade_breakdown_report"),
                 )
             })
@@ -49,8 +61,12 @@ pub struct TradeBreakDownReportAggMarkets {
 
 impl TradeBreakDownReportAggMarkets {
     pub fn save_as_csv(&self, file_name: &str) {
+        self.save_as_csv_with_format(file_name, &ReportFormat::default())
+    }
+
+    pub fn save_as_csv_with_format(&self, file_name: &str, format: &ReportFormat) {
         save_df_as_csv(
-            &mut self.report.clone(),
+            &mut format.export::<TradeBreakDownReportColumnKind>(&self.report),
             &format!("{file_name}_all_markets_trade_breakdown_report"),
         )
     }
@@ -1,2 +1,8 @@
+pub mod candle_pattern;
+pub mod day_type;
+pub mod gap;
+pub mod higher_time_frame_levels;
 pub mod initial_balance;
+pub mod order_flow_imbalance;
+pub mod pivot_points;
 pub mod price_histogram;
@@ -0,0 +1,195 @@
+use crate::{enums::indicator::CandlePatternKind, DataProviderColumnKind};
+use polars::prelude::{col, DataFrame, IntoLazy};
+
+const DOJI_MAX_BODY_SHARE_OF_RANGE: f64 = 0.1;
+const PIN_BAR_MIN_WICK_SHARE_OF_RANGE: f64 = 2.0 / 3.0;
+
+/// The candle of a whole session, see `Candle::from_session`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candle {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+impl Candle {
+    /// Aggregates the candles of a session into one candle, i.e. the first open, the highest high,
+    /// the lowest low and the last close. Returns `None` if the session has no candles.
+    pub fn from_session(df: &DataFrame) -> Option<Self> {
+        if df.height() == 0 {
+            return None;
+        }
+        let column = |kind: DataProviderColumnKind| col(&kind.to_string());
+        let candle = df
+            .clone()
+            .lazy()
+            .select([
+                column(DataProviderColumnKind::Open).first(),
+                column(DataProviderColumnKind::High).max(),
+                column(DataProviderColumnKind::Low).min(),
+                column(DataProviderColumnKind::Close).last(),
+            ])
+            .collect()
+            .unwrap();
+
+        Some(Self {
+            open: value(&candle, DataProviderColumnKind::Open),
+            high: value(&candle, DataProviderColumnKind::High),
+            low: value(&candle, DataProviderColumnKind::Low),
+            close: value(&candle, DataProviderColumnKind::Close),
+        })
+    }
+
+    fn range(&self) -> f64 {
+        self.high - self.low
+    }
+
+    fn body(&self) -> f64 {
+        (self.close - self.open).abs()
+    }
+
+    fn is_bullish(&self) -> bool {
+        self.close > self.open
+    }
+
+    fn is_bearish(&self) -> bool {
+        self.close < self.open
+    }
+
+    fn engulfs(&self, other: &Candle) -> bool {
+        self.open.min(self.close) <= other.open.min(other.close)
+            && self.open.max(self.close) >= other.open.max(other.close)
+    }
+}
+
+/// Returns `true` if the last of the chronologically ordered `candles` forms the `pattern`, where
+/// two-candle patterns also take the candle before into account. Hence, two-candle patterns are
+/// never formed by a single candle.
+pub fn is_candle_pattern(candles: &[Candle], pattern: CandlePatternKind) -> bool {
+    let Some((last, rest)) = candles.split_last() else {
+        return false;
+    };
+    let previous = rest.last().copied();
+
+    match pattern {
+        CandlePatternKind::BullishEngulfing => previous.is_some_and(|previous| {
+            previous.is_bearish() && last.is_bullish() && last.engulfs(&previous)
+        }),
+        CandlePatternKind::BearishEngulfing => previous.is_some_and(|previous| {
+            previous.is_bullish() && last.is_bearish() && last.engulfs(&previous)
+        }),
+        CandlePatternKind::InsideBar => {
+            previous.is_some_and(|previous| last.high < previous.high && last.low > previous.low)
+        }
+        CandlePatternKind::Doji => {
+            last.range() > 0.0 && last.body() <= DOJI_MAX_BODY_SHARE_OF_RANGE * last.range()
+        }
+        CandlePatternKind::PinBar => {
+            let upper_wick = last.high - last.open.max(last.close);
+            let lower_wick = last.open.min(last.close) - last.low;
+            last.range() > 0.0
+                && upper_wick.max(lower_wick) >= PIN_BAR_MIN_WICK_SHARE_OF_RANGE * last.range()
+        }
+    }
+}
+
+fn value(df: &DataFrame, column: DataProviderColumnKind) -> f64 {
+    df.column(&column.to_string())
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .next()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{df, prelude::NamedFrom};
+
+    fn candles(open: &[f64], high: &[f64], low: &[f64], close: &[f64]) -> Vec<Candle> {
+        (0..open.len())
+            .map(|idx| Candle {
+                open: open[idx],
+                high: high[idx],
+                low: low[idx],
+                close: close[idx],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_candle_from_session() {
+        let session = df!(
+            "open" => &[100.0, 103.0, 101.0],
+            "high" => &[104.0, 103.5, 106.0],
+            "low" => &[99.0, 101.5, 100.5],
+            "close" => &[103.0, 102.0, 105.0],
+        )
+        .unwrap();
+        let target = Candle {
+            open: 100.0,
+            high: 106.0,
+            low: 99.0,
+            close: 105.0,
+        };
+        assert_eq!(Some(target), Candle::from_session(&session));
+        assert_eq!(None, Candle::from_session(&session.head(Some(0))));
+    }
+
+    #[test]
+    fn test_is_candle_pattern() {
+        let bullish_engulfing = candles(
+            &[100.0, 103.0, 101.0],
+            &[104.0, 103.5, 106.0],
+            &[99.0, 101.5, 100.5],
+            &[103.0, 102.0, 105.0],
+        );
+        assert!(is_candle_pattern(
+            &bullish_engulfing,
+            CandlePatternKind::BullishEngulfing
+        ));
+        assert!(!is_candle_pattern(
+            &bullish_engulfing,
+            CandlePatternKind::BearishEngulfing
+        ));
+
+        let bearish_engulfing = candles(
+            &[100.0, 102.0],
+            &[101.5, 103.0],
+            &[99.5, 98.0],
+            &[101.0, 99.0],
+        );
+        assert!(is_candle_pattern(
+            &bearish_engulfing,
+            CandlePatternKind::BearishEngulfing
+        ));
+
+        let inside_bar = candles(
+            &[100.0, 102.0],
+            &[105.0, 104.0],
+            &[99.0, 100.0],
+            &[104.0, 101.0],
+        );
+        assert!(is_candle_pattern(&inside_bar, CandlePatternKind::InsideBar));
+        assert!(!is_candle_pattern(
+            &bearish_engulfing,
+            CandlePatternKind::InsideBar
+        ));
+
+        let doji = candles(&[100.0], &[102.0], &[98.0], &[100.2]);
+        assert!(is_candle_pattern(&doji, CandlePatternKind::Doji));
+        assert!(!is_candle_pattern(&doji, CandlePatternKind::PinBar));
+        assert!(!is_candle_pattern(&doji, CandlePatternKind::InsideBar));
+
+        let pin_bar = candles(&[100.0], &[100.5], &[94.0], &[100.2]);
+        assert!(is_candle_pattern(&pin_bar, CandlePatternKind::PinBar));
+
+        let flat = candles(&[100.0], &[100.0], &[100.0], &[100.0]);
+        assert!(!is_candle_pattern(&flat, CandlePatternKind::Doji));
+        assert!(!is_candle_pattern(&flat, CandlePatternKind::PinBar));
+        assert!(!is_candle_pattern(&[], CandlePatternKind::Doji));
+    }
+}
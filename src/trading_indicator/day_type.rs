@@ -0,0 +1,115 @@
+use crate::{enums::indicator::DayTypeKind, DataProviderColumnKind};
+use polars::prelude::DataFrame;
+
+const NUMBER_OF_BINS: usize = 20;
+const EXTREME_SHARE_OF_RANGE: f64 = 0.2;
+const PEAK_SHARE_OF_MAX: f64 = 0.5;
+const THIN_SHARE_OF_MAX: f64 = 0.25;
+
+/// Classifies a completed session given by its candles.
+/// * `Trend` - the open is in the lowest and the close in the highest 20% of the range, or vice
+///   versa
+/// * `DoubleDistribution` - the TPO profile with 20 price bins has two bins with at least 50% of
+///   the maximum TPO count, separated by a bin with at most 25% of the maximum TPO count
+/// * `Balance` - otherwise
+pub fn classify_day_type(df: &DataFrame) -> DayTypeKind {
+    let open = column_as_vec(df, DataProviderColumnKind::Open);
+    let high = column_as_vec(df, DataProviderColumnKind::High);
+    let low = column_as_vec(df, DataProviderColumnKind::Low);
+    let close = column_as_vec(df, DataProviderColumnKind::Close);
+
+    let max = high.iter().copied().fold(f64::MIN, f64::max);
+    let min = low.iter().copied().fold(f64::MAX, f64::min);
+    let range = max - min;
+    if range <= 0.0 {
+        return DayTypeKind::Balance;
+    }
+
+    let share_of_range = |px: f64| (px - min) / range;
+    let open = share_of_range(open[0]);
+    let close = share_of_range(*close.last().unwrap());
+    let is_at_low = |share: f64| share <= EXTREME_SHARE_OF_RANGE;
+    let is_at_high = |share: f64| share >= 1.0 - EXTREME_SHARE_OF_RANGE;
+    if (is_at_low(open) && is_at_high(close)) || (is_at_high(open) && is_at_low(close)) {
+        return DayTypeKind::Trend;
+    }
+
+    if is_double_distribution(&tpo_profile(&high, &low, min, range)) {
+        DayTypeKind::DoubleDistribution
+    } else {
+        DayTypeKind::Balance
+    }
+}
+
+fn column_as_vec(df: &DataFrame, column: DataProviderColumnKind) -> Vec<f64> {
+    df.column(&column.to_string())
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+/// Counts for every price bin the number of candles that traded in it.
+fn tpo_profile(high: &[f64], low: &[f64], min: f64, range: f64) -> Vec<u32> {
+    let bin_size = range / NUMBER_OF_BINS as f64;
+    let bin = |px: f64| (((px - min) / bin_size) as usize).min(NUMBER_OF_BINS - 1);
+
+    high.iter()
+        .zip(low)
+        .fold(vec![0; NUMBER_OF_BINS], |mut profile, (high, low)| {
+            (bin(*low)..=bin(*high)).for_each(|idx| profile[idx] += 1);
+            profile
+        })
+}
+
+fn is_double_distribution(profile: &[u32]) -> bool {
+    let max = *profile.iter().max().unwrap() as f64;
+    let is_peak = |count: &u32| *count as f64 >= PEAK_SHARE_OF_MAX * max;
+    let first_peak = profile.iter().position(is_peak).unwrap();
+    let last_peak = profile.iter().rposition(is_peak).unwrap();
+
+    profile[first_peak..=last_peak]
+        .iter()
+        .any(|count| *count as f64 <= THIN_SHARE_OF_MAX * max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{df, prelude::NamedFrom};
+
+    fn session(open: &[f64], high: &[f64], low: &[f64], close: &[f64]) -> DataFrame {
+        df!("open" => open, "high" => high, "low" => low, "close" => close).unwrap()
+    }
+
+    #[test]
+    fn test_classify_day_type() {
+        let trend = session(
+            &[100.0, 101.0, 103.0, 105.0],
+            &[101.0, 103.0, 105.0, 110.0],
+            &[100.0, 101.0, 103.0, 105.0],
+            &[101.0, 103.0, 105.0, 109.5],
+        );
+        assert_eq!(DayTypeKind::Trend, classify_day_type(&trend));
+
+        let double_distribution = session(
+            &[101.0, 101.0, 101.0, 109.0, 109.0, 109.0],
+            &[102.0, 102.0, 102.0, 110.0, 110.0, 110.0],
+            &[100.0, 100.0, 100.0, 108.0, 108.0, 108.0],
+            &[101.0, 101.0, 101.0, 109.0, 109.0, 105.0],
+        );
+        assert_eq!(
+            DayTypeKind::DoubleDistribution,
+            classify_day_type(&double_distribution)
+        );
+
+        let balance = session(
+            &[104.0, 105.0, 104.0, 105.0],
+            &[106.0, 110.0, 106.0, 106.0],
+            &[100.0, 104.0, 103.0, 104.0],
+            &[105.0, 104.0, 105.0, 105.0],
+        );
+        assert_eq!(DayTypeKind::Balance, classify_day_type(&balance));
+    }
+}
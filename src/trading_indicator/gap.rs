@@ -0,0 +1,189 @@
+use crate::{
+    bot::time_frame_snapshot::TimeFrameSnapshot, converter::any_value::AnyValueConverter,
+    DataProviderColumnKind,
+};
+use polars::prelude::{col, ChunkAgg, DataFrame, IntoLazy};
+use std::collections::HashMap;
+
+/// The gap between the last close of the prior session and the first open of a session, e.g. an
+/// overnight or weekend gap. The gap is filled if the session traded back to the prior close.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gap {
+    pub size: f64,
+    pub is_filled: bool,
+}
+
+/// The gap of a session together with the share of gaps that were filled in the sessions before.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GapStatistics {
+    pub gap: Gap,
+    pub gap_fill_probability: f64,
+}
+
+impl Gap {
+    pub fn new(prior_session: &DataFrame, session: &DataFrame) -> Self {
+        let open = DataProviderColumnKind::Open.to_string();
+        let high = DataProviderColumnKind::High.to_string();
+        let low = DataProviderColumnKind::Low.to_string();
+        let close = DataProviderColumnKind::Close.to_string();
+
+        let prior_close = prior_session
+            .clone()
+            .lazy()
+            .select([col(&close).last()])
+            .collect()
+            .unwrap()
+            .get(0)
+            .unwrap()[0]
+            .unwrap_float64();
+        let res = session
+            .clone()
+            .lazy()
+            .select([col(&open).first(), col(&high).max(), col(&low).min()])
+            .collect()
+            .unwrap();
+        let row = res.get(0).unwrap();
+        let (open, high, low) = (
+            row[0].unwrap_float64(),
+            row[1].unwrap_float64(),
+            row[2].unwrap_float64(),
+        );
+
+        let size = open - prior_close;
+        let is_filled = if size > 0.0 {
+            low <= prior_close
+        } else {
+            high >= prior_close
+        };
+
+        Self { size, is_filled }
+    }
+}
+
+/// Returns the share of filled gaps. Sessions without a gap are ignored. Returns `0.0` if there is
+/// no gap.
+pub fn gap_fill_probability(gaps: &[Gap]) -> f64 {
+    let gaps: Vec<_> = gaps.iter().filter(|gap| gap.size != 0.0).collect();
+    if gaps.is_empty() {
+        return 0.0;
+    }
+
+    let number_filled = gaps.iter().filter(|gap| gap.is_filled).count();
+    number_filled as f64 / gaps.len() as f64
+}
+
+/// Computes the gap of every session with respect to the session before in chronological order,
+/// i.e. ordered by the first `ots` of the sessions. Hence, the prior session of a session after a
+/// weekend or holiday is the last session that has data, and `PeriodKind::BusinessDay` sessions
+/// are ordered by their actual start. The gap fill probability of a session only takes the gaps of
+/// the sessions before into account. The first session has no prior session and thus no gap.
+pub fn gap_statistics(
+    sessions: &HashMap<TimeFrameSnapshot, DataFrame>,
+) -> HashMap<TimeFrameSnapshot, GapStatistics> {
+    let ots = DataProviderColumnKind::OpenTime.to_string();
+    let mut sessions: Vec<_> = sessions
+        .iter()
+        .filter_map(|(snapshot, df)| {
+            let session_start = df.column(&ots).ok()?.i64().ok()?.min()?;
+            Some((session_start, snapshot, df))
+        })
+        .collect();
+    sessions.sort_by_key(|(session_start, ..)| *session_start);
+
+    let mut gaps = Vec::new();
+    sessions
+        .windows(2)
+        .map(|pair| {
+            let (_, _, prior_session) = pair[0];
+            let (_, snapshot, session) = pair[1];
+            let gap = Gap::new(prior_session, session);
+            let statistics = GapStatistics {
+                gap,
+                gap_fill_probability: gap_fill_probability(&gaps),
+            };
+            gaps.push(gap);
+            (*snapshot, statistics)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::time_frame_snapshot::TimeFrameSnapshotBuilder;
+    use polars::{df, prelude::NamedFrom};
+
+    fn session(open: f64, high: f64, low: f64, close: f64) -> DataFrame {
+        df!(
+            "open" => &[open],
+            "high" => &[high],
+            "low" => &[low],
+            "close" => &[close],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_gap() {
+        let prior_session = session(99.0, 101.0, 98.0, 100.0);
+
+        let gap_up_filled = Gap::new(&prior_session, &session(102.0, 103.0, 99.5, 101.0));
+        assert_eq!(
+            Gap {
+                size: 2.0,
+                is_filled: true
+            },
+            gap_up_filled
+        );
+
+        let gap_down_not_filled = Gap::new(&prior_session, &session(97.0, 99.0, 96.0, 98.0));
+        assert_eq!(
+            Gap {
+                size: -3.0,
+                is_filled: false
+            },
+            gap_down_not_filled
+        );
+
+        let no_gap = Gap::new(&prior_session, &session(100.0, 101.0, 99.0, 100.5));
+        assert_eq!(0.0, no_gap.size);
+
+        assert_eq!(0.0, gap_fill_probability(&[]));
+        assert_eq!(
+            0.5,
+            gap_fill_probability(&[gap_up_filled, gap_down_not_filled, no_gap])
+        );
+    }
+
+    #[test]
+    fn test_gap_statistics() {
+        let session = |ots: i64, open: f64, close: f64| {
+            df!(
+                "ots" => &[ots],
+                "open" => &[open],
+                "high" => &[open.max(close)],
+                "low" => &[open.min(close)],
+                "close" => &[close],
+            )
+            .unwrap()
+        };
+        // Fri, 2021-12-31 is in calendar week 52 of 2021, but before Mon, 2022-01-03 in week 1
+        let friday = TimeFrameSnapshotBuilder::new(52).with_weekday(5).build();
+        let monday = TimeFrameSnapshotBuilder::new(1).with_weekday(1).build();
+        // Wed, 2022-01-05 follows Mon, 2022-01-03, since Tuesday is a holiday without data
+        let wednesday = TimeFrameSnapshotBuilder::new(1).with_weekday(3).build();
+        let sessions = HashMap::from([
+            (friday, session(1_640_908_800_000, 100.0, 101.0)),
+            (monday, session(1_641_168_000_000, 103.0, 104.0)),
+            (wednesday, session(1_641_340_800_000, 103.0, 104.0)),
+        ]);
+
+        let statistics = gap_statistics(&sessions);
+
+        assert_eq!(2, statistics.len());
+        assert!(!statistics.contains_key(&friday));
+        assert_eq!(2.0, statistics[&monday].gap.size);
+        assert_eq!(0.0, statistics[&monday].gap_fill_probability);
+        assert_eq!(-1.0, statistics[&wednesday].gap.size);
+    }
+}
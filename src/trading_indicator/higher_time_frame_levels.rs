@@ -0,0 +1,187 @@
+use crate::{
+    enums::{bot::TimeFrameKind, trade_and_pre_trade::PreTradeDataKind},
+    DataProviderColumnKind,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveDate};
+use polars::prelude::DataFrame;
+use std::collections::HashMap;
+
+/// The high, low and close of a reference period, e.g. the previous calendar week.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// The levels of the calendar week and the calendar month before the one a session starts in,
+/// independent of the time frame of the backtest. A level is `None` if there is no data for it,
+/// e.g. for the previous month of a session in January. Sessions without a level required by the
+/// strategy are not backtested.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HigherTimeFrameLevels {
+    pub previous_week: Option<Levels>,
+    pub previous_month: Option<Levels>,
+}
+
+/// The levels of every calendar week and calendar month of a set of candles. The candles are
+/// aggregated once, such that the `HigherTimeFrameLevels` of every session are looked up instead
+/// of filtering all candles per session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeriodLevels {
+    weeks: HashMap<NaiveDate, Levels>,
+    months: HashMap<NaiveDate, Levels>,
+}
+
+impl PeriodLevels {
+    /// * `df` - the candles of all sessions, where `ots` is in **UTC** **milliseconds**
+    pub fn new(df: &DataFrame) -> Self {
+        let df = df
+            .sort([DataProviderColumnKind::OpenTime.to_string()], false, false)
+            .unwrap();
+        let ots = i64_column(&df, DataProviderColumnKind::OpenTime);
+        let high = f64_column(&df, DataProviderColumnKind::High);
+        let low = f64_column(&df, DataProviderColumnKind::Low);
+        let close = f64_column(&df, DataProviderColumnKind::Close);
+
+        let mut period_levels = Self::default();
+        for idx in 0..df.height() {
+            let date = DateTime::from_timestamp_millis(ots[idx])
+                .unwrap()
+                .date_naive();
+            let candle = Levels {
+                high: high[idx],
+                low: low[idx],
+                close: close[idx],
+            };
+            update(&mut period_levels.weeks, week_start(date), candle);
+            update(&mut period_levels.months, month_start(date), candle);
+        }
+
+        period_levels
+    }
+
+    /// * `session_start` - the `ots` of the first candle of the session
+    pub fn higher_time_frame_levels(&self, session_start: i64) -> HigherTimeFrameLevels {
+        let date = DateTime::from_timestamp_millis(session_start)
+            .unwrap()
+            .date_naive();
+        let month_start = month_start(date);
+        let previous_month_start = if month_start.month() == 1 {
+            NaiveDate::from_ymd_opt(month_start.year() - 1, 12, 1).unwrap()
+        } else {
+            month_start.with_month(month_start.month() - 1).unwrap()
+        };
+
+        HigherTimeFrameLevels {
+            previous_week: self
+                .weeks
+                .get(&(week_start(date) - Duration::days(7)))
+                .copied(),
+            previous_month: self.months.get(&previous_month_start).copied(),
+        }
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday().into())
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap()
+}
+
+/// Adds a candle to the levels of its period. The candles are in chronological order, hence the
+/// close of the period is the close of the latest candle.
+fn update(periods: &mut HashMap<NaiveDate, Levels>, period_start: NaiveDate, candle: Levels) {
+    periods
+        .entry(period_start)
+        .and_modify(|levels| {
+            levels.high = levels.high.max(candle.high);
+            levels.low = levels.low.min(candle.low);
+            levels.close = candle.close;
+        })
+        .or_insert(candle);
+}
+
+pub fn is_higher_time_frame_level(value: &PreTradeDataKind) -> bool {
+    matches!(
+        value,
+        PreTradeDataKind::PreviousWeekHigh
+            | PreTradeDataKind::PreviousWeekLow
+            | PreTradeDataKind::PreviousWeekClose
+            | PreTradeDataKind::PreviousMonthHigh
+            | PreTradeDataKind::PreviousMonthLow
+            | PreTradeDataKind::PreviousMonthClose
+            | PreTradeDataKind::PivotPoint {
+                time_frame: TimeFrameKind::Weekly,
+                ..
+            }
+    )
+}
+
+fn i64_column(df: &DataFrame, column: DataProviderColumnKind) -> Vec<i64> {
+    df.column(&column.to_string())
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+fn f64_column(df: &DataFrame, column: DataProviderColumnKind) -> Vec<f64> {
+    df.column(&column.to_string())
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{df, prelude::NamedFrom};
+
+    #[test]
+    fn test_higher_time_frame_levels() {
+        // Thu 2023-02-23, Tue 2023-02-28, Wed 2023-03-01, Fri 2023-03-03, Mon 2023-03-06
+        let df = df!(
+            "ots" => &[
+                1_677_110_400_000_i64,
+                1_677_542_400_000,
+                1_677_628_800_000,
+                1_677_801_600_000,
+                1_678_060_800_000,
+            ],
+            "high" => &[105.0, 110.0, 108.0, 112.0, 111.0],
+            "low" => &[95.0, 100.0, 104.0, 101.0, 109.0],
+            "close" => &[100.0, 102.0, 107.0, 103.0, 110.0],
+        )
+        .unwrap();
+
+        let period_levels = PeriodLevels::new(&df);
+
+        let levels = period_levels.higher_time_frame_levels(1_678_060_800_000);
+        assert_eq!(
+            Some(Levels {
+                high: 112.0,
+                low: 100.0,
+                close: 103.0
+            }),
+            levels.previous_week
+        );
+        assert_eq!(
+            Some(Levels {
+                high: 110.0,
+                low: 95.0,
+                close: 102.0
+            }),
+            levels.previous_month
+        );
+
+        let levels = period_levels.higher_time_frame_levels(1_677_110_400_000);
+        assert_eq!(None, levels.previous_week);
+        assert_eq!(None, levels.previous_month);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    converter::any_value::AnyValueConverter,
+    enums::{column_names::DataProviderColumnKind, indicator::ExecutionDepthKind},
+};
+use polars::prelude::{col, lit, when, DataFrame, DataType, Expr, IntoLazy};
+
+const ONE_MINUTE_IN_MS: i64 = 60_000;
+
+fn weighted_volume(execution_depth: ExecutionDepthKind) -> Expr {
+    match execution_depth {
+        ExecutionDepthKind::Quantity => col(&DataProviderColumnKind::Quantity.to_string()),
+        ExecutionDepthKind::Fills => {
+            let ftid = DataProviderColumnKind::FirstTradeId.to_string();
+            let ltid = DataProviderColumnKind::LastTradeId.to_string();
+            (col(&ltid) - col(&ftid) + lit(1)).cast(DataType::Float64)
+        }
+    }
+}
+
+/// Computes `(buy volume - sell volume) / (buy volume + sell volume)` of the aggressor volume of
+/// aggregated trades. If the buyer is the maker, the seller is the aggressor and vice versa.
+///
+/// # Arguments
+/// * `df` - aggregated trades of one session
+/// * `window_minutes` - only trades within the last `window_minutes` before `session_end_ts` are
+///   considered, or all trades of the session if `None`. The window is anchored at the session
+///   end, such that a period without trades before the session end is part of the window
+/// * `execution_depth` - how an aggregated trade is weighted
+/// * `session_end_ts` - **UTC** timestamp in **milliseconds** when the session ends
+///
+/// The result is in `[-1, 1]` and `0.0` if nothing was traded within the window.
+pub fn order_flow_imbalance(
+    df: &DataFrame,
+    window_minutes: Option<u32>,
+    execution_depth: ExecutionDepthKind,
+    session_end_ts: i64,
+) -> f64 {
+    let ts = DataProviderColumnKind::Timestamp.to_string();
+    let bm = DataProviderColumnKind::BuyerEqualsMaker.to_string();
+    let volume = weighted_volume(execution_depth);
+
+    let window_start = window_minutes.map_or(i64::MIN, |minutes| {
+        session_end_ts - i64::from(minutes) * ONE_MINUTE_IN_MS
+    });
+    let res = df
+        .clone()
+        .lazy()
+        .filter(
+            col(&ts)
+                .gt_eq(lit(window_start))
+                .and(col(&ts).lt(lit(session_end_ts))),
+        )
+        .select([
+            when(col(&bm).eq(lit(false)))
+                .then(volume.clone())
+                .otherwise(lit(0.0))
+                .sum()
+                .alias("buy"),
+            when(col(&bm).eq(lit(true)))
+                .then(volume)
+                .otherwise(lit(0.0))
+                .sum()
+                .alias("sell"),
+        ])
+        .collect()
+        .unwrap();
+    let row = res.get(0).unwrap();
+    let (buy, sell) = (row[0].unwrap_float64(), row[1].unwrap_float64());
+
+    if buy + sell == 0.0 {
+        0.0
+    } else {
+        (buy - sell) / (buy + sell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::{df, prelude::NamedFrom};
+
+    #[test]
+    fn test_order_flow_imbalance() {
+        let agg_trades = df!(
+            "ts" => &[0_i64, 30_000, 60_000, 90_000, 120_000],
+            "qx" => &[1.0, 2.0, 3.0, 1.0, 4.0],
+            "ftid" => &[1_i64, 2, 5, 6, 7],
+            "ltid" => &[1_i64, 4, 5, 6, 7],
+            "bm" => &[false, true, false, false, true],
+        )
+        .unwrap();
+        let quantity = ExecutionDepthKind::Quantity;
+        let session_end = 180_000;
+
+        assert_eq!(
+            (5.0 - 6.0) / 11.0,
+            order_flow_imbalance(&agg_trades, None, quantity, session_end)
+        );
+        assert_eq!(
+            0.0,
+            order_flow_imbalance(&agg_trades, Some(2), quantity, session_end)
+        );
+        assert_eq!(
+            -1.0,
+            order_flow_imbalance(&agg_trades, Some(1), quantity, session_end)
+        );
+        assert_eq!(
+            (3.0 - 4.0) / 7.0,
+            order_flow_imbalance(&agg_trades, None, ExecutionDepthKind::Fills, session_end)
+        );
+    }
+
+    #[test]
+    fn test_order_flow_imbalance_is_anchored_at_session_end() {
+        let agg_trades = df!(
+            "ts" => &[0_i64, 60_000],
+            "qx" => &[1.0, 2.0],
+            "ftid" => &[1_i64, 2],
+            "ltid" => &[1_i64, 2],
+            "bm" => &[false, true],
+        )
+        .unwrap();
+        let quantity = ExecutionDepthKind::Quantity;
+
+        // Nothing was traded within the last five minutes before the session end
+        assert_eq!(
+            0.0,
+            order_flow_imbalance(&agg_trades, Some(5), quantity, 600_000)
+        );
+        assert_eq!(
+            -1.0 / 3.0,
+            order_flow_imbalance(&agg_trades, Some(10), quantity, 600_000)
+        );
+        // Trades at or after the session end are not part of the session
+        assert_eq!(
+            1.0,
+            order_flow_imbalance(&agg_trades, None, quantity, 60_000)
+        );
+    }
+}
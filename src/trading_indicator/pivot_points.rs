@@ -0,0 +1,55 @@
+use super::higher_time_frame_levels::Levels;
+use crate::enums::indicator::{PivotLevelKind, PivotPointKind};
+
+const CAMARILLA_FACTOR: f64 = 1.1;
+
+/// Computes the pivot point `level` from the `levels` of the prior period.
+pub fn pivot_point(kind: PivotPointKind, level: PivotLevelKind, levels: &Levels) -> f64 {
+    let Levels { high, low, close } = *levels;
+    let pp = (high + low + close) / 3.0;
+    let range = high - low;
+
+    match (kind, level) {
+        (_, PivotLevelKind::Pp) => pp,
+        (PivotPointKind::Classic, PivotLevelKind::R1) => 2.0 * pp - low,
+        (PivotPointKind::Classic, PivotLevelKind::R2) => pp + range,
+        (PivotPointKind::Classic, PivotLevelKind::R3) => high + 2.0 * (pp - low),
+        (PivotPointKind::Classic, PivotLevelKind::S1) => 2.0 * pp - high,
+        (PivotPointKind::Classic, PivotLevelKind::S2) => pp - range,
+        (PivotPointKind::Classic, PivotLevelKind::S3) => low - 2.0 * (high - pp),
+        (PivotPointKind::Camarilla, PivotLevelKind::R1) => close + range * CAMARILLA_FACTOR / 12.0,
+        (PivotPointKind::Camarilla, PivotLevelKind::R2) => close + range * CAMARILLA_FACTOR / 6.0,
+        (PivotPointKind::Camarilla, PivotLevelKind::R3) => close + range * CAMARILLA_FACTOR / 4.0,
+        (PivotPointKind::Camarilla, PivotLevelKind::S1) => close - range * CAMARILLA_FACTOR / 12.0,
+        (PivotPointKind::Camarilla, PivotLevelKind::S2) => close - range * CAMARILLA_FACTOR / 6.0,
+        (PivotPointKind::Camarilla, PivotLevelKind::S3) => close - range * CAMARILLA_FACTOR / 4.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pivot_point() {
+        let levels = Levels {
+            high: 110.0,
+            low: 98.0,
+            close: 104.0,
+        };
+        let classic = |level| pivot_point(PivotPointKind::Classic, level, &levels);
+        assert_eq!(104.0, classic(PivotLevelKind::Pp));
+        assert_eq!(110.0, classic(PivotLevelKind::R1));
+        assert_eq!(116.0, classic(PivotLevelKind::R2));
+        assert_eq!(122.0, classic(PivotLevelKind::R3));
+        assert_eq!(98.0, classic(PivotLevelKind::S1));
+        assert_eq!(92.0, classic(PivotLevelKind::S2));
+        assert_eq!(86.0, classic(PivotLevelKind::S3));
+
+        let camarilla = |level| pivot_point(PivotPointKind::Camarilla, level, &levels);
+        assert_eq!(104.0, camarilla(PivotLevelKind::Pp));
+        assert!((105.1 - camarilla(PivotLevelKind::R1)).abs() < 1e-9);
+        assert!((107.3 - camarilla(PivotLevelKind::R3)).abs() < 1e-9);
+        assert!((101.8 - camarilla(PivotLevelKind::S2)).abs() < 1e-9);
+    }
+}
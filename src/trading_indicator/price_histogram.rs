@@ -3,19 +3,58 @@ use std::{convert::identity, rc::Rc};
 use crate::{
     converter::any_value::AnyValueConverter,
     data_frame_operations::trait_extensions::MyDataFrameOperations,
-    enums::{column_names::VolumeProfileColumnKind, value_area::ValueAreaKind},
+    enums::{
+        column_names::{DataProviderColumnKind, VolumeProfileColumnKind},
+        indicator::{PocSelectionRule, ValueAreaRule},
+        value_area::ValueAreaKind,
+    },
     lazy_frame_operations::trait_extensions::MyLazyFrameOperations,
 };
 
-use polars::prelude::{col, AnyValue, DataFrame, IntoLazy};
+use polars::prelude::{col, AnyValue, DataFrame, DataType, IntoLazy};
+
+/// The share of the price histogram that is covered by the value area, if the bot does not set one.
+pub const DEFAULT_VALUE_AREA_PERCENT: f64 = 0.63;
 
 pub struct PriceHistogram {
     df: DataFrame,
+    poc_selection: Option<PocSelection>,
+    value_area_rule: ValueAreaRule,
+}
+
+/// The `PocSelectionRule` together with the market simulation data the price histogram was
+/// computed from.
+struct PocSelection {
+    rule: PocSelectionRule,
+    market_sim_data: DataFrame,
 }
 
 impl PriceHistogram {
     pub fn new(df: DataFrame) -> Self {
-        Self { df }
+        Self {
+            df,
+            poc_selection: None,
+            value_area_rule: ValueAreaRule::default(),
+        }
+    }
+
+    pub fn with_value_area_rule(self, value_area_rule: ValueAreaRule) -> Self {
+        Self {
+            value_area_rule,
+            ..self
+        }
+    }
+
+    /// Sets the rule to select the POC if several prices share the maximum volume. Without a rule,
+    /// the first of these prices in the price histogram is the POC.
+    pub fn with_poc_selection_rule(self, rule: PocSelectionRule, market_sim_data: DataFrame) -> Self {
+        Self {
+            poc_selection: Some(PocSelection {
+                rule,
+                market_sim_data,
+            }),
+            ..self
+        }
     }
 
     /// This function computes the POC for the given volume profile. The POC is the point of control. Hence,
@@ -24,24 +63,22 @@ impl PriceHistogram {
     /// # Arguments
     /// * `df_vol` - volume profile
     pub fn poc(&self) -> f64 {
-        let qx = VolumeProfileColumnKind::Quantity.to_string();
-        let px = VolumeProfileColumnKind::Price.to_string();
-
-        self.df
-            .clone()
-            .lazy()
-            .select([col(&px).filter(col(&qx).eq(col(&qx).max()))])
-            .collect()
-            .unwrap()
-            .get(0)
-            .unwrap()[0]
-            .unwrap_float64()
+        self.get_poc_with_vol_tuple().0
     }
 
-    /// Computes the volume area described in <https://www.vtad.de/lexikon/market-profile/>
+    /// Computes the value area with the configured `ValueAreaRule`. The default rule is the
+    /// classic two row comparison described in <https://www.vtad.de/lexikon/market-profile/>
     /// # Arguments
     /// * `std_dev` - standard deviation
     pub fn value_area(&self, std_dev: f64) -> (f64, f64) {
+        match self.value_area_rule {
+            ValueAreaRule::ClassicTwoRow => self.value_area_classic_two_row(std_dev),
+            ValueAreaRule::GreedySingleBin => self.value_area_greedy_single_bin(std_dev),
+            ValueAreaRule::PercentOfPriceRange => self.value_area_percent_of_price_range(std_dev),
+        }
+    }
+
+    fn value_area_classic_two_row(&self, std_dev: f64) -> (f64, f64) {
         let (poc, poc_vol) = self.get_poc_with_vol_tuple();
         let total_tpo_count = self.get_total_tpo_count();
         let initial_value_area = ValueArea::new(&self.df, poc);
@@ -63,21 +100,115 @@ impl PriceHistogram {
         self.compute_value_area(total_tpo_count - tpo_delta, new_va)
     }
 
+    fn value_area_greedy_single_bin(&self, std_dev: f64) -> (f64, f64) {
+        let (prices, volumes) = self.get_prices_and_volumes();
+        let poc_idx = self.get_poc_row_idx(&prices);
+        let target_vol = volumes.iter().sum::<f64>() * std_dev;
+
+        let (mut low_idx, mut high_idx) = (poc_idx, poc_idx);
+        let mut vol = volumes[poc_idx];
+        while vol < target_vol {
+            let below = low_idx.checked_sub(1).map(|idx| volumes[idx]);
+            let above = volumes.get(high_idx + 1).copied();
+            match (below, above) {
+                (None, None) => break,
+                (Some(below), Some(above)) if below > above => {
+                    low_idx -= 1;
+                    vol += below;
+                }
+                (_, Some(above)) => {
+                    high_idx += 1;
+                    vol += above;
+                }
+                (Some(below), None) => {
+                    low_idx -= 1;
+                    vol += below;
+                }
+            }
+        }
+
+        (prices[low_idx], prices[high_idx])
+    }
+
+    /// The value area never exceeds the price range, i.e. a share above `1.0` covers the whole
+    /// price range. If all prices are equal, the value area is the POC.
+    fn value_area_percent_of_price_range(&self, std_dev: f64) -> (f64, f64) {
+        let (prices, _) = self.get_prices_and_volumes();
+        let poc = prices[self.get_poc_row_idx(&prices)];
+        let (min_px, max_px) = (prices[0], prices[prices.len() - 1]);
+        let range = max_px - min_px;
+        if range <= 0.0 {
+            return (poc, poc);
+        }
+        let width = range * std_dev.clamp(0.0, 1.0);
+
+        let low = (poc - width / 2.0).clamp(min_px, max_px - width);
+        let high = low + width;
+        let value_area_low = prices.iter().copied().find(|px| *px >= low).unwrap_or(poc);
+        let value_area_high = prices
+            .iter()
+            .copied()
+            .rev()
+            .find(|px| *px <= high)
+            .unwrap_or(poc);
+
+        (value_area_low, value_area_high.max(value_area_low))
+    }
+
+    /// Returns the prices and volumes of all rows in price histogram order.
+    fn get_prices_and_volumes(&self) -> (Vec<f64>, Vec<f64>) {
+        let qx = VolumeProfileColumnKind::Quantity.to_string();
+        let px = VolumeProfileColumnKind::Price.to_string();
+        let column_as_vec = |name: &str| -> Vec<f64> {
+            self.df
+                .column(name)
+                .unwrap()
+                .cast(&DataType::Float64)
+                .unwrap()
+                .f64()
+                .unwrap()
+                .into_no_null_iter()
+                .collect()
+        };
+
+        (column_as_vec(&px), column_as_vec(&qx))
+    }
+
+    fn get_poc_row_idx(&self, prices: &[f64]) -> usize {
+        let poc = self.poc();
+        prices.iter().position(|px| *px == poc).unwrap()
+    }
+
     fn get_poc_with_vol_tuple(&self) -> (f64, f64) {
+        let candidates = self.get_poc_candidates();
+        let poc = self
+            .poc_selection
+            .as_ref()
+            .map_or_else(|| candidates[0].0, |v| v.select(&candidates));
+        *candidates.iter().find(|(px, _)| *px == poc).unwrap()
+    }
+
+    /// Returns all `(price, volume)` tuples with the maximum volume in price histogram order.
+    fn get_poc_candidates(&self) -> Vec<(f64, f64)> {
         let qx = VolumeProfileColumnKind::Quantity.to_string();
+        let px = VolumeProfileColumnKind::Price.to_string();
 
-        let row = self
+        let df = self
             .df
             .clone()
             .lazy()
             .filter(col(&qx).eq(col(&qx).max()))
+            .select([col(&px), col(&qx).cast(DataType::Float64)])
             .collect()
             .unwrap();
-        let row = row.get(0).unwrap();
 
-        let poc = row[0].unwrap_float64();
-        let poc_vol = row[1].unwrap_float64();
-        (poc, poc_vol)
+        df.column(&px)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .zip(df.column(&qx).unwrap().f64().unwrap().into_no_null_iter())
+            .collect()
     }
 
     fn get_total_tpo_count(&self) -> f64 {
@@ -89,6 +220,70 @@ impl PriceHistogram {
     }
 }
 
+impl PocSelection {
+    fn select(&self, candidates: &[(f64, f64)]) -> f64 {
+        let prices: Vec<_> = candidates.iter().map(|(px, _)| *px).collect();
+        let lowest = prices.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest = prices.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        match self.rule {
+            PocSelectionRule::Lowest => lowest,
+            PocSelectionRule::Highest => highest,
+            PocSelectionRule::ClosestToClose => closest_to(&prices, self.last_close()),
+            PocSelectionRule::MostRecentlyTraded => self.most_recently_traded(&prices, lowest),
+            PocSelectionRule::MidpointOfPocCluster => {
+                closest_to(&prices, (lowest + highest) / 2.0)
+            }
+        }
+    }
+
+    fn last_close(&self) -> f64 {
+        let close = DataProviderColumnKind::Close.to_string();
+        self.market_sim_data
+            .column(&close)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .last()
+            .unwrap()
+    }
+
+    /// Returns the price that is contained in the most recent candle. If none of the prices was
+    /// traded, the lowest price is returned.
+    fn most_recently_traded(&self, prices: &[f64], lowest: f64) -> f64 {
+        let high = DataProviderColumnKind::High.to_string();
+        let low = DataProviderColumnKind::Low.to_string();
+        let highs = self.market_sim_data.column(&high).unwrap().f64().unwrap();
+        let lows = self.market_sim_data.column(&low).unwrap().f64().unwrap();
+
+        highs
+            .into_no_null_iter()
+            .zip(lows.into_no_null_iter())
+            .rev()
+            .find_map(|(high, low)| {
+                prices
+                    .iter()
+                    .copied()
+                    .filter(|px| low <= *px && *px <= high)
+                    .reduce(f64::min)
+            })
+            .unwrap_or(lowest)
+    }
+}
+
+/// Returns the price closest to `target`. On a tie the lower price is returned.
+fn closest_to(prices: &[f64], target: f64) -> f64 {
+    prices.iter().copied().fold(f64::NAN, |closest, px| {
+        let is_closer = (px - target).abs() < (closest - target).abs();
+        let is_tie_with_lower_px = (px - target).abs() == (closest - target).abs() && px < closest;
+        if closest.is_nan() || is_closer || is_tie_with_lower_px {
+            px
+        } else {
+            closest
+        }
+    })
+}
+
 struct ValueArea {
     low: ValueAreaPart,
     high: ValueAreaPart,
@@ -432,37 +627,38 @@ mod tests {
         let df_map = download_df_map(
             "ppp/btcusdt/2022/Mon1h0m-Fri23h0m/1w/target_vol-aggTrades.json".to_string(),
         )
-        .await;
+        .await
+        .unwrap();
 
         let mut snapshot = TimeFrameSnapshotBuilder::new(12).build();
         let mut df = df_map.get(&snapshot).unwrap().clone();
-        assert_eq!(42000.0, PriceHistogram { df }.poc());
+        assert_eq!(42000.0, PriceHistogram::new(df).poc());
 
         snapshot = TimeFrameSnapshotBuilder::new(8).build();
         df = df_map.get(&snapshot).unwrap().clone();
-        assert_eq!(38100.0, PriceHistogram { df }.poc());
+        assert_eq!(38100.0, PriceHistogram::new(df).poc());
 
         snapshot = TimeFrameSnapshotBuilder::new(9).build();
         df = df_map.get(&snapshot).unwrap().clone();
-        assert_eq!(42100.0, PriceHistogram { df }.poc());
+        assert_eq!(42100.0, PriceHistogram::new(df).poc());
 
         snapshot = TimeFrameSnapshotBuilder::new(10).build();
         df = df_map.get(&snapshot).unwrap().clone();
-        assert_eq!(42200.0, PriceHistogram { df }.poc());
+        assert_eq!(42200.0, PriceHistogram::new(df).poc());
 
         df = df!(
             "px" => &[1.0, 2.0, 3.0, 4.0],
             "qx" => &[10, 10, 9, 10]
         )
         .unwrap();
-        assert_eq!(1.0, PriceHistogram { df }.poc());
+        assert_eq!(1.0, PriceHistogram::new(df).poc());
 
         df = df!(
             "px" => &[ 83_200.0, 38_100.0, 38_000.0, 1.0],
             "qx" => &[100.0, 300.0, 150.0, 300.0],
         )
         .unwrap();
-        assert_eq!(38_100.0, PriceHistogram { df }.poc());
+        assert_eq!(38_100.0, PriceHistogram::new(df).poc());
     }
 
     #[test]
@@ -472,11 +668,60 @@ mod tests {
             "qx" => &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 20.0, 15.0, 10.0, 5.0, 0.0],
         )
         .unwrap();
-        let ph = PriceHistogram { df };
+        let ph = PriceHistogram::new(df);
 
         assert_eq!(ph.poc(), ph.get_poc_with_vol_tuple().0);
     }
 
+    #[test]
+    fn test_poc_selection_rule() {
+        let df = df!(
+            "px" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0],
+            "qx" => &[30.0, 30.0, 5.0, 10.0, 5.0, 30.0, 30.0],
+        )
+        .unwrap();
+        let market_sim_data = df!(
+            "ots" => &[0_i64, 1],
+            "high" => &[7.5, 6.5],
+            "low" => &[5.5, 5.8],
+            "close" => &[6.0, 6.9],
+        )
+        .unwrap();
+        let poc = |rule| {
+            PriceHistogram::new(df.clone())
+                .with_poc_selection_rule(rule, market_sim_data.clone())
+                .poc()
+        };
+
+        assert_eq!(1.0, PriceHistogram::new(df.clone()).poc());
+        assert_eq!(1.0, poc(PocSelectionRule::Lowest));
+        assert_eq!(7.0, poc(PocSelectionRule::Highest));
+        assert_eq!(7.0, poc(PocSelectionRule::ClosestToClose));
+        assert_eq!(6.0, poc(PocSelectionRule::MostRecentlyTraded));
+        assert_eq!(2.0, poc(PocSelectionRule::MidpointOfPocCluster));
+    }
+
+    #[test]
+    fn test_value_area_starts_at_selected_poc() {
+        let df = df!(
+            "px" => &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            "qx" => &[0.0, 50.0, 10.0, 5.0, 10.0, 50.0, 0.0],
+        )
+        .unwrap();
+        let market_sim_data = df!(
+            "ots" => &[0_i64],
+            "high" => &[6.0],
+            "low" => &[0.0],
+            "close" => &[5.0],
+        )
+        .unwrap();
+
+        let ph = PriceHistogram::new(df.clone())
+            .with_poc_selection_rule(PocSelectionRule::Highest, market_sim_data);
+        assert_eq!((5.0, 5.0), ph.value_area(0.3));
+        assert_eq!((1.0, 1.0), PriceHistogram::new(df).value_area(0.3));
+    }
+
     #[test]
     fn test_compute_value_area() {
         let df = df!(
@@ -484,7 +729,30 @@ mod tests {
             "qx" => &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 20.0, 15.0, 10.0, 5.0, 0.0],
         )
         .unwrap();
-        assert_eq!((3.0, 5.0), PriceHistogram { df }.value_area(0.3));
+        assert_eq!((3.0, 5.0), PriceHistogram::new(df).value_area(0.3));
+    }
+
+    #[test]
+    fn test_value_area_rule() {
+        let df = df!(
+            "px" => &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0],
+            "qx" => &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 20.0, 15.0, 10.0, 5.0, 0.0],
+        )
+        .unwrap();
+
+        let ph =
+            PriceHistogram::new(df.clone()).with_value_area_rule(ValueAreaRule::GreedySingleBin);
+        assert_eq!((4.0, 5.0), ph.value_area(0.3));
+        assert_eq!((3.0, 6.0), ph.value_area(0.63));
+
+        let ph = PriceHistogram::new(df).with_value_area_rule(ValueAreaRule::PercentOfPriceRange);
+        assert_eq!((3.0, 7.0), ph.value_area(0.4));
+        assert_eq!((1.0, 9.0), ph.value_area(0.8));
+        assert_eq!((0.0, 10.0), ph.value_area(1.5));
+
+        let flat = df!("px" => &[5.0], "qx" => &[50.0]).unwrap();
+        let ph = PriceHistogram::new(flat).with_value_area_rule(ValueAreaRule::PercentOfPriceRange);
+        assert_eq!((5.0, 5.0), ph.value_area(0.63));
     }
 
     /// This test computes the value area from the example given in <https://www.vtad.de/lexikon/market-profile/> in the section
@@ -500,7 +768,7 @@ mod tests {
             "qx" => &[0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 4.0, 4.0, 4.0, 5.0, 7.0, 8.0, 6.0, 5.0, 5.0, 3.0, 3.0, 1.0, 1.0, 0.0],
         )
         .unwrap();
-        assert_eq!((5080.0, 5160.0), PriceHistogram { df }.value_area(0.68));
+        assert_eq!((5080.0, 5160.0), PriceHistogram::new(df).value_area(0.68));
     }
 
     #[tokio::test]
@@ -510,6 +778,6 @@ mod tests {
             "ppp/_test_data_files/target_ohlc_tpo_for_tpo_test.csv".to_string(),
         )
         .await;
-        assert_eq!((1.15195, 1.15845), PriceHistogram { df }.value_area(0.68))
+        assert_eq!((1.15195, 1.15845), PriceHistogram::new(df).value_area(0.68))
     }
 }